@@ -0,0 +1,400 @@
+//! 住所の文字列を都道府県・市区町村・町域・丁目・番地・建物名に分解する
+use crate::fuzzy::{fold_variant, from_kanji_numeric};
+use crate::model::{City, Prefecture, Town, TownSpec};
+use crate::normalize::{normalize_address_query, parse_number};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// 町域名の末尾の丁目（「芝浦３丁目」の「３丁目」）
+static TOWN_CHOME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)([０-９]+)丁目$").unwrap());
+
+/// 町域名に続く丁目（「３丁目」「三丁目」）
+static CHOME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:([０-９]+)|([一二三四五六七八九十]+))丁目").unwrap());
+
+/// 丁目を省略してハイフンでつないだもの（「３－１－１」の「３」）
+static CHOME_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([０-９]+)(?:－|$)").unwrap());
+
+/// 番地・号（「１－１」「１番地１」「１番１号」など）
+static BANCHI: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[０-９]+(?:(?:番地の|番地|番|号|－|の)[０-９]+)*(?:番地|番|号)?").unwrap()
+});
+
+/// 町域名に続く地割（「第４０地割」「４０地割」）
+static JIWARI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^第?([０-９]+)地割").unwrap());
+
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[０-９]+").unwrap());
+
+/// 住所の分解に使う地名の一覧
+///
+/// 郵便番号データベースに含まれる都道府県・市区町村・町域で、町域名は
+/// [`normalize_utf_ken_all_record_town`](crate::normalize::normalize_utf_ken_all_record_town)
+/// で正規化されている前提。町域は対象の市区町村の分だけ含まれていればよい。
+#[derive(Debug, Clone, Default)]
+pub struct AreaDictionary {
+    pub prefectures: Vec<Prefecture>,
+    pub cities: Vec<City>,
+    pub towns: Vec<Town>,
+}
+
+/// 分解した住所
+///
+/// 住所は [`normalize_address_query`] で正規化した表記で返す。特定できなかった部分は `None` になる。
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ParsedAddress {
+    /// 都道府県名（省略されていれば市区町村から補う）
+    pub prefecture: Option<String>,
+    /// 市区町村名
+    pub city: Option<String>,
+    /// 全国地方公共団体コード（市区町村まで特定できた場合）
+    pub local_government_code: Option<String>,
+    /// 町域名（丁目を除く）
+    pub town: Option<String>,
+    /// 郵便番号（町域まで特定できた場合）
+    pub postal_code: Option<String>,
+    /// 丁目の番号（「３丁目」の「３」）
+    pub chome: Option<String>,
+    /// 番地・号をハイフンでつないだもの（「１番地１」「１番１号」はいずれも「１－１」）
+    pub banchi: Option<String>,
+    /// 番地より後ろの建物名・部屋番号など
+    ///
+    /// 市区町村や町域を特定できなかった場合は、それ以降のすべてを含む。
+    pub building: Option<String>,
+}
+
+/// `chars` の先頭が `name` と一致すれば、その文字数を返す
+///
+/// 異体字（ケ・ヶ、澤・沢 など）は同じ文字とみなす。
+fn match_name(chars: &[char], name: &str) -> Option<usize> {
+    let len = name.chars().count();
+    (len > 0
+        && chars.len() >= len
+        && chars
+            .iter()
+            .zip(name.chars())
+            .all(|(a, b)| fold_variant(*a) == fold_variant(b)))
+    .then_some(len)
+}
+
+/// `candidates` のうち `chars` の先頭と最も長く一致するものを返す（同じ長さなら先にあるもの）
+fn longest_match<'a, T>(
+    chars: &[char],
+    candidates: impl Iterator<Item = &'a T>,
+    name: impl Fn(&T) -> &str,
+) -> Option<(usize, &'a T)>
+where
+    T: 'a,
+{
+    candidates
+        .filter_map(|c| Some((match_name(chars, name(c))?, c)))
+        .fold(None, |best, (len, c)| match best {
+            Some((best_len, _)) if best_len >= len => best,
+            _ => Some((len, c)),
+        })
+}
+
+/// 住所の文字列を都道府県・市区町村・町域・丁目・番地・建物名に分解する
+///
+/// `dictionary` の地名と先頭から順に照合する。都道府県名は省略されていてもよい。町域名は
+/// 郵便番号データベースの正規化された町域名と照合し、丁目は町域名に含まれていても後ろに続いて
+/// いても取り出す。丁目を省略してハイフンでつないだ住所（「芝浦３－１－１」）は、その町域に
+/// 丁目がある場合にだけ先頭の数字を丁目とみなす。
+///
+/// 空白は番地と建物名の区切りとして扱い、番地は空白をまたがない。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::address::{parse, AreaDictionary};
+/// # use jp_postal_code_core::model::{City, Prefecture, Town};
+/// let dictionary = AreaDictionary {
+///     prefectures: vec![Prefecture {
+///         code: "13".to_string(),
+///         name: "東京都".to_string(),
+///         name_kana: "トウキョウト".to_string(),
+///     }],
+///     cities: vec![City {
+///         local_government_code: "13103".to_string(),
+///         name: "港区".to_string(),
+///         name_kana: "ミナトク".to_string(),
+///     }],
+///     towns: vec![Town {
+///         local_government_code: "13103".to_string(),
+///         postal_code: "1080023".to_string(),
+///         name: "芝浦３丁目".to_string(),
+///         name_kana: "シバウラ３チョウメ".to_string(),
+///     }],
+/// };
+/// let parsed = parse("東京都港区芝浦３丁目１－１ ABCビル5F", &dictionary);
+/// assert_eq!(parsed.town.as_deref(), Some("芝浦"));
+/// assert_eq!(parsed.chome.as_deref(), Some("３"));
+/// assert_eq!(parsed.banchi.as_deref(), Some("１－１"));
+/// assert_eq!(parsed.building.as_deref(), Some("ＡＢＣビル５Ｆ"));
+/// assert_eq!(parsed.postal_code.as_deref(), Some("1080023"));
+/// ```
+pub fn parse(address: &str, dictionary: &AreaDictionary) -> ParsedAddress {
+    // 空白で区切られた位置を覚えてから正規化する（正規化すると空白が除去される）
+    let mut chars = vec![];
+    let mut boundaries = vec![];
+    for token in address.split_whitespace() {
+        chars.extend(normalize_address_query(token).chars());
+        boundaries.push(chars.len());
+    }
+    let mut parsed = ParsedAddress::default();
+    let mut pos = 0;
+    let rest = |pos: usize| chars[pos..].iter().collect::<String>();
+
+    let prefecture = longest_match(&chars, dictionary.prefectures.iter(), |p| &p.name);
+    if let Some((len, prefecture)) = prefecture {
+        parsed.prefecture = Some(prefecture.name.clone());
+        pos += len;
+    }
+
+    let cities = dictionary.cities.iter().filter(|c| match prefecture {
+        Some((_, p)) => c.local_government_code.starts_with(&p.code),
+        None => true,
+    });
+    let Some((len, city)) = longest_match(&chars[pos..], cities, |c| &c.name) else {
+        parsed.building = Some(rest(pos)).filter(|s| !s.is_empty());
+        return parsed;
+    };
+    // 都道府県名が省略されていれば市区町村から補う
+    if parsed.prefecture.is_none() {
+        parsed.prefecture = dictionary
+            .prefectures
+            .iter()
+            .find(|p| city.local_government_code.starts_with(&p.code))
+            .map(|p| p.name.clone());
+    }
+    parsed.city = Some(city.name.clone());
+    parsed.local_government_code = Some(city.local_government_code.clone());
+    pos += len;
+
+    let towns = dictionary
+        .towns
+        .iter()
+        .filter(|t| t.local_government_code == city.local_government_code)
+        .collect::<Vec<_>>();
+    let Some((len, town)) = longest_match(&chars[pos..], towns.iter().copied(), |t| &t.name) else {
+        parsed.building = Some(rest(pos)).filter(|s| !s.is_empty());
+        return parsed;
+    };
+    pos += len;
+    parsed.postal_code = Some(town.postal_code.clone());
+    match TOWN_CHOME.captures(&town.name) {
+        Some(caps) => {
+            parsed.town = Some(caps[1].to_string());
+            parsed.chome = Some(caps[2].to_string());
+        }
+        None => parsed.town = Some(town.name.clone()),
+    }
+
+    // 町域名に丁目が含まれていなければ、後ろに続く丁目を探す
+    if parsed.chome.is_none() {
+        let base = town.name.as_str();
+        let has_chome = towns.iter().any(|t| {
+            TOWN_CHOME
+                .captures(&t.name)
+                .is_some_and(|caps| &caps[1] == base)
+        });
+        let text = rest(pos);
+        let chome = if let Some(caps) = CHOME.captures(&text) {
+            let chome = match caps.get(1) {
+                Some(number) => number.as_str().to_string(),
+                None => normalize_address_query(&from_kanji_numeric(&caps[2]).to_string()),
+            };
+            Some((caps[0].chars().count(), chome))
+        } else if has_chome {
+            CHOME_NUMBER
+                .captures(&text)
+                .map(|caps| (caps[0].chars().count(), caps[1].to_string()))
+        } else {
+            None
+        };
+        if let Some((len, chome)) = chome {
+            pos += len;
+            // 丁目ごとに郵便番号が分かれていれば、丁目の町域の郵便番号を使う
+            let name = format!("{base}{chome}丁目");
+            if let Some(town) = towns.iter().find(|t| t.name == name) {
+                parsed.postal_code = Some(town.postal_code.clone());
+            }
+            parsed.chome = Some(chome);
+        }
+    }
+
+    // 番地は次の空白までに限る
+    let end = boundaries
+        .iter()
+        .copied()
+        .find(|b| *b > pos)
+        .unwrap_or(chars.len());
+    let text = chars[pos..end].iter().collect::<String>();
+    if let Some(m) = BANCHI.find(&text) {
+        let numbers = NUMBER
+            .find_iter(m.as_str())
+            .map(|n| n.as_str())
+            .collect::<Vec<_>>();
+        parsed.banchi = Some(numbers.join("－"));
+        pos += m.as_str().chars().count();
+    }
+
+    let building = rest(pos);
+    let building = building.trim_start_matches(['－', '、', '，']);
+    parsed.building = Some(building.to_string()).filter(|s| !s.is_empty());
+    parsed
+}
+
+/// 町域名から始まる住所（「大江２丁目６５１番地」）が町域の範囲に含まれるか
+///
+/// 町域名（[`TownSpec::base`]）に続く丁目・地割・番地を取り出し、[`TownSpec::covers`] で判定する。
+/// 町域名が一致しなければ含まれない。町域名が空の範囲（「以下に掲載がない場合」など）は
+/// どの住所も含む。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::address::covers;
+/// # use jp_postal_code_core::model::{BanchiRange, NumberRange, TownSpec};
+/// let spec = TownSpec {
+///     base: "大江".to_string(),
+///     chome: Some(NumberRange::new(2, 2)),
+///     excluded: vec![BanchiRange::number(651)],
+///     ..Default::default()
+/// };
+/// assert!(covers(&spec, "大江２丁目６５０番地"));
+/// assert!(!covers(&spec, "大江２丁目６５１番地"));
+/// assert!(!covers(&spec, "大江三丁目"));
+/// ```
+pub fn covers(spec: &TownSpec, address: &str) -> bool {
+    if spec.base.is_empty() {
+        return true;
+    }
+    let chars = normalize_address_query(address).chars().collect::<Vec<_>>();
+    let Some(len) = match_name(&chars, &spec.base) else {
+        return false;
+    };
+    let mut rest = chars[len..].iter().collect::<String>();
+    let (mut chome, mut jiwari) = (None, None);
+    if let Some(caps) = CHOME.captures(&rest) {
+        chome = match caps.get(1) {
+            Some(number) => parse_number(number.as_str()),
+            None => u32::try_from(from_kanji_numeric(&caps[2])).ok(),
+        };
+        rest = rest[caps[0].len()..].to_string();
+    } else if let Some(caps) = JIWARI.captures(&rest) {
+        jiwari = parse_number(&caps[1]);
+        rest = rest[caps[0].len()..].to_string();
+    } else if spec.chome.is_some() {
+        if let Some(caps) = CHOME_NUMBER.captures(&rest) {
+            chome = parse_number(&caps[1]);
+            rest = rest[caps[0].len()..].to_string();
+        }
+    }
+    let banchi = BANCHI.find(&rest).and_then(|m| {
+        let mut numbers = NUMBER
+            .find_iter(m.as_str())
+            .map(|n| parse_number(n.as_str()));
+        Some((numbers.next()??, numbers.next().flatten()))
+    });
+    spec.covers(chome, jiwari, banchi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary() -> AreaDictionary {
+        let prefecture = |code: &str, name: &str| Prefecture {
+            code: code.to_string(),
+            name: name.to_string(),
+            name_kana: "".to_string(),
+        };
+        let city = |code: &str, name: &str| City {
+            local_government_code: code.to_string(),
+            name: name.to_string(),
+            name_kana: "".to_string(),
+        };
+        let town = |code: &str, postal_code: &str, name: &str| Town {
+            local_government_code: code.to_string(),
+            postal_code: postal_code.to_string(),
+            name: name.to_string(),
+            name_kana: "".to_string(),
+        };
+        AreaDictionary {
+            prefectures: vec![prefecture("01", "北海道"), prefecture("13", "東京都")],
+            cities: vec![
+                city("01101", "札幌市中央区"),
+                city("13103", "港区"),
+                city("13104", "新宿区"),
+            ],
+            towns: vec![
+                town("01101", "0640941", "旭ケ丘"),
+                town("01101", "0600041", "大通東"),
+                town("13103", "1080023", "芝浦"),
+                town("13103", "1050023", "芝浦１丁目"),
+                town("13103", "1080023", "芝浦２丁目"),
+                town("13103", "1080023", "芝浦３丁目"),
+                town("13103", "1050014", "芝１丁目"),
+                town("13104", "1600022", "新宿"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let dictionary = dictionary();
+        pretty_assertions::assert_eq!(
+            parse("東京都港区芝浦３丁目１－１ ABCビル5F", &dictionary),
+            ParsedAddress {
+                prefecture: Some("東京都".to_string()),
+                city: Some("港区".to_string()),
+                local_government_code: Some("13103".to_string()),
+                town: Some("芝浦".to_string()),
+                postal_code: Some("1080023".to_string()),
+                chome: Some("３".to_string()),
+                banchi: Some("１－１".to_string()),
+                building: Some("ＡＢＣビル５Ｆ".to_string()),
+            }
+        );
+
+        // 都道府県名の省略、丁目の省略、部屋番号
+        let parsed = parse("港区芝浦1-2-3 101", &dictionary);
+        assert_eq!(parsed.prefecture.as_deref(), Some("東京都"));
+        assert_eq!(parsed.town.as_deref(), Some("芝浦"));
+        assert_eq!(parsed.chome.as_deref(), Some("１"));
+        assert_eq!(parsed.postal_code.as_deref(), Some("1050023"));
+        assert_eq!(parsed.banchi.as_deref(), Some("２－３"));
+        assert_eq!(parsed.building.as_deref(), Some("１０１"));
+
+        // 漢数字の丁目と「番地」「号」
+        let parsed = parse("東京都 港区 芝浦二丁目3番地4号", &dictionary);
+        assert_eq!(parsed.chome.as_deref(), Some("２"));
+        assert_eq!(parsed.banchi.as_deref(), Some("３－４"));
+        assert_eq!(parsed.building, None);
+
+        // 丁目のない町域では先頭の数字も番地とみなす
+        let parsed = parse("新宿区新宿3-1-1 新宿ビル", &dictionary);
+        assert_eq!(parsed.town.as_deref(), Some("新宿"));
+        assert_eq!(parsed.chome, None);
+        assert_eq!(parsed.banchi.as_deref(), Some("３－１－１"));
+        assert_eq!(parsed.building.as_deref(), Some("新宿ビル"));
+
+        // 異体字は同じ文字とみなす
+        let parsed = parse("北海道札幌市中央区旭ヶ丘5丁目6-51", &dictionary);
+        assert_eq!(parsed.town.as_deref(), Some("旭ケ丘"));
+        assert_eq!(parsed.chome.as_deref(), Some("５"));
+        assert_eq!(parsed.banchi.as_deref(), Some("６－５１"));
+
+        // 特定できなかった部分は建物名に残す
+        let parsed = parse("東京都千代田区丸の内1-1", &dictionary);
+        assert_eq!(parsed.prefecture.as_deref(), Some("東京都"));
+        assert_eq!(parsed.city, None);
+        assert_eq!(parsed.building.as_deref(), Some("千代田区丸の内１－１"));
+        let parsed = parse("港区海岸1-1", &dictionary);
+        assert_eq!(parsed.city.as_deref(), Some("港区"));
+        assert_eq!(parsed.town, None);
+        assert_eq!(parsed.building.as_deref(), Some("海岸１－１"));
+
+        assert_eq!(parse("", &dictionary), ParsedAddress::default());
+    }
+}
@@ -0,0 +1,271 @@
+//! 表記揺れや入力の誤りを許容した住所の照合
+//!
+//! 利用者が入力した住所には、ケ・ヶ・が や 之・ノ のような異体字・表記揺れ、旧字体、「丁目」の
+//! 省略などが含まれる。[`fold_address`] でこれらを同じ文字列に寄せてから、編集距離で候補を
+//! 比較する。
+use crate::normalize::normalize_address_query;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// 異体字・旧字体とその代表となる文字
+const VARIANTS: &[(char, char)] = &[
+    // 地名の「が」は ケ・ヶ・ガ・が のいずれでも書かれる
+    ('ヶ', 'ケ'),
+    ('ヵ', 'ケ'),
+    ('ガ', 'ケ'),
+    ('が', 'ケ'),
+    ('之', 'ノ'),
+    ('の', 'ノ'),
+    ('乃', 'ノ'),
+    ('澤', '沢'),
+    ('邊', '辺'),
+    ('邉', '辺'),
+    ('齋', '斉'),
+    ('齊', '斉'),
+    ('斎', '斉'),
+    ('髙', '高'),
+    ('﨑', '崎'),
+    ('嵜', '崎'),
+    ('碕', '崎'),
+    ('濱', '浜'),
+    ('濵', '浜'),
+    ('德', '徳'),
+    ('國', '国'),
+    ('廣', '広'),
+    ('龍', '竜'),
+    ('櫻', '桜'),
+    ('關', '関'),
+    ('黑', '黒'),
+    ('眞', '真'),
+    ('條', '条'),
+    ('瀨', '瀬'),
+    ('萬', '万'),
+    ('冨', '富'),
+    ('\u{fa10}', '塚'),
+    ('檜', '桧'),
+    ('嶋', '島'),
+    ('嶌', '島'),
+    ('峯', '峰'),
+    ('舘', '館'),
+    ('曾', '曽'),
+    ('惠', '恵'),
+    ('藪', '薮'),
+    ('禰', '祢'),
+    ('藏', '蔵'),
+    ('鐵', '鉄'),
+    ('鴬', '鶯'),
+    ('縣', '県'),
+    ('區', '区'),
+    ('驛', '駅'),
+    ('榮', '栄'),
+    ('發', '発'),
+    ('篭', '籠'),
+    ('彌', '弥'),
+    ('瀧', '滝'),
+    ('乘', '乗'),
+    ('學', '学'),
+    ('槇', '槙'),
+    ('淵', '渕'),
+    ('穗', '穂'),
+    ('豐', '豊'),
+    ('禮', '礼'),
+    ('龜', '亀'),
+    ('壽', '寿'),
+    ('稻', '稲'),
+    ('邨', '村'),
+];
+
+/// 漢数字の丁目（「三丁目」「二十一丁目」など）
+static KANJI_CHOME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([一二三四五六七八九十]+)丁目").unwrap());
+
+/// 括弧で囲まれた補足（「大通西（１～１９丁目）」の「（１～１９丁目）」など）
+static PARENTHESES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"（[^（）]*）").unwrap());
+
+/// 漢数字（十の位まで）を数値に変換する
+pub(crate) fn from_kanji_numeric(s: &str) -> usize {
+    let (mut tens, mut ones) = (0, 0);
+    for c in s.chars() {
+        match c {
+            '十' => {
+                tens = if ones == 0 { 10 } else { ones * 10 };
+                ones = 0;
+            }
+            _ => {
+                ones = "一二三四五六七八九"
+                    .chars()
+                    .position(|d| d == c)
+                    .unwrap_or(0)
+                    + 1
+            }
+        }
+    }
+    tens + ones
+}
+
+/// 表記揺れを吸収して住所を比較するための文字列に変換する
+///
+/// [`normalize_address_query`] と同じ規則で正規化した上で、次の変換を行う。正規化済みの町域名と
+/// 利用者の入力のどちらにも適用し、変換後の文字列同士を比較する。
+///
+/// - 括弧で囲まれた補足を除去する
+/// - 漢数字の丁目を全角数字にし、「丁目」を除去する
+/// - 異体字・旧字体を代表となる文字に寄せる（ケ・ヶ・が → ケ、之・の → ノ、澤 → 沢 など）
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::fuzzy::fold_address;
+/// assert_eq!(fold_address("旭ヶ丘三丁目"), fold_address("旭が丘3"));
+/// assert_eq!(fold_address("大通西（１～１９丁目）"), "大通西");
+/// ```
+pub fn fold_address(s: &str) -> String {
+    let s = normalize_address_query(s);
+    let s = PARENTHESES.replace_all(&s, "");
+    let s = KANJI_CHOME.replace_all(&s, |caps: &regex::Captures| {
+        normalize_address_query(&from_kanji_numeric(&caps[1]).to_string())
+    });
+    s.replace("丁目", "").chars().map(fold_variant).collect()
+}
+
+/// 異体字・旧字体を代表となる文字に寄せる
+///
+/// 1文字を1文字に変換するので、変換の前後で文字の位置が変わらない。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::fuzzy::fold_variant;
+/// assert_eq!(fold_variant('ヶ'), 'ケ');
+/// assert_eq!(fold_variant('澤'), '沢');
+/// assert_eq!(fold_variant('芝'), '芝');
+/// ```
+pub fn fold_variant(c: char) -> char {
+    VARIANTS
+        .iter()
+        .find(|(variant, _)| *variant == c)
+        .map_or(c, |(_, canonical)| *canonical)
+}
+
+/// 2つの文字列の編集距離（レーベンシュタイン距離）を文字単位で求める
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::fuzzy::edit_distance;
+/// assert_eq!(edit_distance("芝浦", "芝浦"), 0);
+/// assert_eq!(edit_distance("芝浦", "柴浦"), 1);
+/// assert_eq!(edit_distance("芝浦", "芝浦３"), 1);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let row = distance_row(a, b);
+    row[row.len() - 1]
+}
+
+/// `text` の先頭部分のうち `pattern` との編集距離が最小になるものを求める
+///
+/// 住所の後ろに番地などが続いていても町域名と比較できるように、`text` の後ろの余分な部分は
+/// 距離に含めない。ただし「２」が「２２」の先頭と一致しないように、数字の途中では区切らない。
+/// 距離と、その距離になる `text` の先頭部分の文字数を返す（複数ある場合は短い方）。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::fuzzy::prefix_edit_distance;
+/// assert_eq!(prefix_edit_distance("芝浦", "芝浦３－４－１"), (0, 2));
+/// assert_eq!(prefix_edit_distance("芝浦", "柴浦３－４－１"), (1, 2));
+/// assert_eq!(prefix_edit_distance("芝浦２", "芝浦２２"), (1, 2));
+/// ```
+pub fn prefix_edit_distance(pattern: &str, text: &str) -> (usize, usize) {
+    let row = distance_row(pattern, text);
+    let text = text.chars().collect::<Vec<_>>();
+    let is_digit = |c: &char| c.is_ascii_digit() || matches!(c, '０'..='９');
+    row.iter()
+        .enumerate()
+        .filter(|(len, _)| {
+            !(*len > 0 && *len < text.len() && is_digit(&text[len - 1]) && is_digit(&text[*len]))
+        })
+        .map(|(len, distance)| (*distance, len))
+        .min()
+        .unwrap_or((0, 0))
+}
+
+/// `pattern` 全体と `text` の各先頭部分（0文字〜全体）との編集距離を求める
+fn distance_row(pattern: &str, text: &str) -> Vec<usize> {
+    let text = text.chars().collect::<Vec<_>>();
+    // row[j] は pattern の先頭 i 文字と text の先頭 j 文字の距離
+    let mut row = (0..=text.len()).collect::<Vec<_>>();
+    for (i, p) in pattern.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, t) in text.iter().enumerate() {
+            let substitution = diagonal + usize::from(p != *t);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row
+}
+
+/// 候補として許容する編集距離の上限
+///
+/// 短い名前ほど誤りの余地が小さいので、[`fold_address`] で変換した文字数の3分の1（最低1）とする。
+pub fn max_edit_distance(folded: &str) -> usize {
+    (folded.chars().count() / 3).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_address() {
+        // ケ・ヶ・が、之・ノ の揺れ
+        assert_eq!(fold_address("旭ケ丘"), "旭ケ丘");
+        assert_eq!(fold_address("旭ヶ丘"), "旭ケ丘");
+        assert_eq!(fold_address("旭が丘"), "旭ケ丘");
+        assert_eq!(fold_address("霞ガ関"), "霞ケ関");
+        assert_eq!(fold_address("猪之頭"), "猪ノ頭");
+        assert_eq!(fold_address("猪の頭"), "猪ノ頭");
+        // 旧字体
+        assert_eq!(fold_address("金澤"), "金沢");
+        assert_eq!(fold_address("髙島"), "高島");
+        assert_eq!(fold_address("長﨑"), "長崎");
+        // 丁目の省略と漢数字
+        assert_eq!(fold_address("芝浦３丁目"), "芝浦３");
+        assert_eq!(fold_address("芝浦三丁目"), "芝浦３");
+        assert_eq!(fold_address("芝浦3"), "芝浦３");
+        assert_eq!(fold_address("西二十一丁目"), "西２１");
+        assert_eq!(fold_address("十条仲原十丁目"), "十条仲原１０");
+        // 括弧の補足は比較しない
+        assert_eq!(fold_address("大通西（２０～２８丁目）"), "大通西");
+        assert_eq!(fold_address(""), "");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("芝浦", ""), 2);
+        assert_eq!(edit_distance("", "芝浦"), 2);
+        assert_eq!(edit_distance("しばうら", "しばうら"), 0);
+        assert_eq!(edit_distance("しばうら", "しばら"), 1);
+        assert_eq!(edit_distance("しばうら", "しぱうら"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_prefix_edit_distance() {
+        assert_eq!(prefix_edit_distance("芝浦３", "芝浦３－４－１"), (0, 3));
+        assert_eq!(prefix_edit_distance("芝浦３", "芝浦"), (1, 2));
+        assert_eq!(prefix_edit_distance("港区", "湊区芝浦"), (1, 2));
+        assert_eq!(prefix_edit_distance("港区", ""), (2, 0));
+        assert_eq!(prefix_edit_distance("", "芝浦"), (0, 0));
+        // 数字の途中では区切らない
+        assert_eq!(prefix_edit_distance("西２", "西２２－１"), (1, 1));
+        assert_eq!(prefix_edit_distance("西２２", "西２２－１"), (0, 3));
+    }
+
+    #[test]
+    fn test_max_edit_distance() {
+        assert_eq!(max_edit_distance("芝"), 1);
+        assert_eq!(max_edit_distance("芝浦"), 1);
+        assert_eq!(max_edit_distance("大通西"), 1);
+        assert_eq!(max_edit_distance("札幌市中央区"), 2);
+    }
+}
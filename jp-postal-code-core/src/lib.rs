@@ -1,2 +1,4 @@
+pub mod address;
+pub mod fuzzy;
 pub mod model;
 pub mod normalize;
@@ -4,7 +4,7 @@
 ///
 /// # Example
 /// ```rust
-/// # use jp_postal_code_core::model::UtfKenAllRecord;
+/// # use jp_postal_code_core::model::{UpdateCode, UpdateReason, UtfKenAllRecord};
 /// let _ = UtfKenAllRecord {
 ///     local_government_code: "01101".to_string(),
 ///     old_postal_code: "060  ".to_string(),
@@ -18,8 +18,12 @@
 ///     has_multi_postal_code: 0,
 ///     has_chome: 0,
 ///     has_multi_town: 0,
-///     update_code: 0,
-///     update_reason: 0,
+///     update_code: UpdateCode::Unchanged,
+///     update_reason: UpdateReason::Unchanged,
+///     prefecture_roman: "HOKKAIDO".to_string(),
+///     city_roman: "SAPPORO SHI CHUO KU".to_string(),
+///     town_roman: "IKANIKEISAIGANAIBAAI".to_string(),
+///     town_spec: Default::default(),
 /// };
 /// ```
 ///
@@ -62,19 +66,508 @@ pub struct UtfKenAllRecord {
     /// - 1: 該当
     pub has_multi_town: i16,
     /// 更新の表示
-    ///
-    /// - 0: 変更なし
-    /// - 1: 変更あり
-    /// - 2: 廃止
-    pub update_code: i16,
+    pub update_code: UpdateCode,
     /// 更新理由
+    pub update_reason: UpdateReason,
+    /// 都道府県名（ローマ字）
+    ///
+    /// `utf_ken_all.csv` には含まれないため、CSV からは読み込まずに `KEN_ALL_ROME.CSV` と突き合わせて補う。
+    #[serde(skip_deserializing)]
+    pub prefecture_roman: String,
+    /// 市区町村名（ローマ字）
+    #[serde(skip_deserializing)]
+    pub city_roman: String,
+    /// 町域名（ローマ字）
+    #[serde(skip_deserializing)]
+    pub town_roman: String,
+    /// 町域の範囲（丁目・地割・番地）
+    ///
+    /// `utf_ken_all.csv` には含まれないため、CSV からは読み込まずに正規化時に町域名から求める。
+    #[serde(skip_deserializing)]
+    pub town_spec: TownSpec,
+}
+
+/// 町域が表す範囲
+///
+/// 町域名の「三ツ木（１～５丁目）」や「竹房（４５０番地以下）」のような丁目・地割・番地の範囲を
+/// 構造化したもの。範囲が指定されていない項目は町域全体を表す。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::model::{BanchiRange, NumberRange, TownSpec};
+/// // 大江（…、２丁目「６５１、６６２、６６８番地」以外、…）の２丁目
+/// let spec = TownSpec {
+///     base: "大江".to_string(),
+///     chome: Some(NumberRange::new(2, 2)),
+///     excluded: vec![BanchiRange::number(651), BanchiRange::number(662), BanchiRange::number(668)],
+///     ..Default::default()
+/// };
+/// assert!(spec.covers(Some(2), None, Some((650, None))));
+/// assert!(!spec.covers(Some(2), None, Some((651, None))));
+/// assert!(!spec.covers(Some(3), None, None));
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(default)]
+pub struct TownSpec {
+    /// 丁目・地割を除いた町域名（「以下に掲載がない場合」などは空）
+    pub base: String,
+    /// 丁目の範囲
+    pub chome: Option<NumberRange>,
+    /// 地割の範囲
+    pub jiwari: Option<NumberRange>,
+    /// 番地の範囲（空なら制限なし）
+    pub banchi: Vec<BanchiRange>,
+    /// 除外される番地の範囲
+    pub excluded: Vec<BanchiRange>,
+}
+
+/// 両端を含む数値の範囲
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct NumberRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// 番地の範囲
+///
+/// 下限・上限のない範囲（「４５０番地以上」）や、枝番の範囲（「１７７０－１～２」）を表せる。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct BanchiRange {
+    /// 番地の下限（含む、`None` なら下限なし）
+    pub start: Option<u32>,
+    /// 番地の上限（含む、`None` なら上限なし）
+    pub end: Option<u32>,
+    /// 枝番の範囲（`None` なら枝番を問わない）
+    pub sub: Option<NumberRange>,
+}
+
+impl NumberRange {
+    pub fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+
+    pub fn contains(&self, n: u32) -> bool {
+        self.start <= n && n <= self.end
+    }
+}
+
+impl BanchiRange {
+    /// 1つの番地
+    pub fn number(n: u32) -> Self {
+        Self {
+            start: Some(n),
+            end: Some(n),
+            sub: None,
+        }
+    }
+
+    /// 番地（と枝番）が範囲に含まれるか
     ///
-    /// - 0: 変更なし
-    /// - 1: 市政・区政・町政・分区・政令指定都市施行
-    /// - 2: 住居表示の実施
-    /// - 3: 区画整理
-    /// - 4: 郵便区調整等
-    /// - 5: 訂正
-    /// - 6: 廃止
-    pub update_reason: i16,
+    /// 枝番の範囲が指定されている場合、枝番のない番地は含まれない。
+    pub fn contains(&self, banchi: u32, sub: Option<u32>) -> bool {
+        self.start.is_none_or(|start| start <= banchi)
+            && self.end.is_none_or(|end| banchi <= end)
+            && match (self.sub, sub) {
+                (None, _) => true,
+                (Some(range), Some(sub)) => range.contains(sub),
+                (Some(_), None) => false,
+            }
+    }
+}
+
+impl TownSpec {
+    /// 丁目・地割・番地（と枝番）が町域の範囲に含まれるか
+    ///
+    /// 指定しなかった項目は範囲を問わない。
+    pub fn covers(
+        &self,
+        chome: Option<u32>,
+        jiwari: Option<u32>,
+        banchi: Option<(u32, Option<u32>)>,
+    ) -> bool {
+        if let (Some(range), Some(chome)) = (self.chome, chome) {
+            if !range.contains(chome) {
+                return false;
+            }
+        }
+        if let (Some(range), Some(jiwari)) = (self.jiwari, jiwari) {
+            if !range.contains(jiwari) {
+                return false;
+            }
+        }
+        let Some((banchi, sub)) = banchi else {
+            return true;
+        };
+        (self.banchi.is_empty() || self.banchi.iter().any(|r| r.contains(banchi, sub)))
+            && !self.excluded.iter().any(|r| r.contains(banchi, sub))
+    }
+}
+
+/// 数値で表現されるコードの範囲外の値を変換しようとした場合のエラー
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid {name}: {value}")]
+pub struct OutOfRangeError {
+    name: &'static str,
+    value: i16,
+}
+
+/// 数値で表現されるコードの列挙型に、数値との相互変換と serde・sqlx の実装を与える
+///
+/// CSV やデータベース上では数値のまま扱い、範囲外の値はパース時にエラーにする。
+macro_rules! impl_i16_code {
+    ($name:ident, $description:literal, { $($variant:ident = $value:literal),+ $(,)? }) => {
+        impl From<$name> for i16 {
+            fn from(code: $name) -> Self {
+                match code {
+                    $($name::$variant => $value,)+
+                }
+            }
+        }
+
+        impl TryFrom<i16> for $name {
+            type Error = OutOfRangeError;
+
+            fn try_from(value: i16) -> Result<Self, Self::Error> {
+                match value {
+                    $($value => Ok($name::$variant),)+
+                    _ => Err(OutOfRangeError {
+                        name: $description,
+                        value,
+                    }),
+                }
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_i16((*self).into())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = i16::deserialize(deserializer)?;
+                $name::try_from(value).map_err(serde::de::Error::custom)
+            }
+        }
+
+        #[cfg(feature = "sqlx")]
+        impl<DB> sqlx::Type<DB> for $name
+        where
+            DB: sqlx::Database,
+            i16: sqlx::Type<DB>,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <i16 as sqlx::Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <i16 as sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        #[cfg(feature = "sqlx")]
+        impl<'q, DB> sqlx::Encode<'q, DB> for $name
+        where
+            DB: sqlx::Database,
+            i16: sqlx::Encode<'q, DB>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+                i16::from(*self).encode_by_ref(buf)
+            }
+        }
+
+        #[cfg(feature = "sqlx")]
+        impl<'r, DB> sqlx::Decode<'r, DB> for $name
+        where
+            DB: sqlx::Database,
+            i16: sqlx::Decode<'r, DB>,
+        {
+            fn decode(
+                value: <DB as sqlx::Database>::ValueRef<'r>,
+            ) -> Result<Self, sqlx::error::BoxDynError> {
+                let value = <i16 as sqlx::Decode<DB>>::decode(value)?;
+                Ok($name::try_from(value)?)
+            }
+        }
+    };
+}
+
+/// 更新の表示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UpdateCode {
+    /// 変更なし
+    Unchanged,
+    /// 変更あり
+    Changed,
+    /// 廃止
+    Abolished,
+}
+
+impl_i16_code!(UpdateCode, "update code", {
+    Unchanged = 0,
+    Changed = 1,
+    Abolished = 2,
+});
+
+/// 更新理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UpdateReason {
+    /// 変更なし
+    Unchanged,
+    /// 市政・区政・町政・分区・政令指定都市施行
+    MunicipalityEnforcement,
+    /// 住居表示の実施
+    ResidenceIndication,
+    /// 区画整理
+    LandReadjustment,
+    /// 郵便区調整等
+    PostalDistrictAdjustment,
+    /// 訂正
+    Correction,
+    /// 廃止
+    Abolished,
+}
+
+impl_i16_code!(UpdateReason, "update reason", {
+    Unchanged = 0,
+    MunicipalityEnforcement = 1,
+    ResidenceIndication = 2,
+    LandReadjustment = 3,
+    PostalDistrictAdjustment = 4,
+    Correction = 5,
+    Abolished = 6,
+});
+
+/// 事業所の個別郵便番号レコード
+///
+/// オリジナルの情報を残すようなモデルにしている。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::model::JigyosyoRecord;
+/// let _ = JigyosyoRecord {
+///     local_government_code: "01101".to_string(),
+///     business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+///     business_name: "札幌市役所".to_string(),
+///     prefecture: "北海道".to_string(),
+///     city: "札幌市中央区".to_string(),
+///     town: "北一条西".to_string(),
+///     block_address: "２丁目".to_string(),
+///     postal_code: "0608611".to_string(),
+///     old_postal_code: "060  ".to_string(),
+///     handling_office: "札幌中央".to_string(),
+///     business_type: 0,
+///     multi_number: 0,
+///     update_code: 0,
+/// };
+/// ```
+///
+/// # References
+/// - [事業所の個別郵便番号データの説明](https://www.post.japanpost.jp/zipcode/dl/jigyosyo/readme.html)
+///
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct JigyosyoRecord {
+    /// 大口事業所の所在地の全国地方公共団体コード（JIS X0401、X0402）
+    pub local_government_code: String,
+    /// 大口事業所名（半角カタカナ）
+    pub business_name_kana: String,
+    /// 大口事業所名
+    pub business_name: String,
+    /// 都道府県名
+    pub prefecture: String,
+    /// 市区町村名
+    pub city: String,
+    /// 町域名
+    pub town: String,
+    /// 小字名、丁目、番地等
+    pub block_address: String,
+    /// 大口事業所個別番号（7桁）
+    pub postal_code: String,
+    /// 旧郵便番号（5桁）
+    pub old_postal_code: String,
+    /// 取扱局
+    pub handling_office: String,
+    /// 個別番号の種別の表示
+    ///
+    /// - 0: 大口事業所
+    /// - 1: 私書箱
+    pub business_type: i16,
+    /// 複数番号の有無
+    ///
+    /// - 0: 複数番号無し
+    /// - 1: 複数番号を設定している場合の個別番号の1
+    /// - 2: 複数番号を設定している場合の個別番号の2
+    /// - 3: 複数番号を設定している場合の個別番号の3
+    pub multi_number: i16,
+    /// 修正コード
+    ///
+    /// - 0: 修正なし
+    /// - 1: 新規追加
+    /// - 5: 廃止
+    pub update_code: i16,
+}
+
+/// 住所の郵便番号（ローマ字）レコード
+///
+/// `KEN_ALL_ROME.CSV` の1行に対応する。郵便番号と漢字の住所に加えて、住所のローマ字表記を持つ。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::model::KenAllRomeRecord;
+/// let _ = KenAllRomeRecord {
+///     postal_code: "0600000".to_string(),
+///     prefecture: "北海道".to_string(),
+///     city: "札幌市　中央区".to_string(),
+///     town: "以下に掲載がない場合".to_string(),
+///     prefecture_roman: "HOKKAIDO".to_string(),
+///     city_roman: "SAPPORO SHI CHUO KU".to_string(),
+///     town_roman: "IKANIKEISAIGANAIBAAI".to_string(),
+/// };
+/// ```
+///
+/// # References
+/// - [住所の郵便番号（ローマ字）の説明](https://www.post.japanpost.jp/zipcode/dl/readme_ro.html)
+///
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct KenAllRomeRecord {
+    /// 郵便番号（7桁）
+    pub postal_code: String,
+    /// 都道府県名
+    pub prefecture: String,
+    /// 市区町村名
+    pub city: String,
+    /// 町域名
+    pub town: String,
+    /// 都道府県名（ローマ字）
+    pub prefecture_roman: String,
+    /// 市区町村名（ローマ字）
+    pub city_roman: String,
+    /// 町域名（ローマ字）
+    pub town_roman: String,
+}
+
+/// 郵便番号に対応するレコード
+///
+/// 住所の郵便番号と事業所の個別郵便番号のどちらのレコードかを表す。
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub enum PostalCodeRecord {
+    /// 住所の郵便番号
+    Address(UtfKenAllRecord),
+    /// 事業所の個別郵便番号
+    Business(JigyosyoRecord),
+}
+
+impl PostalCodeRecord {
+    /// 郵便番号（7桁）
+    pub fn postal_code(&self) -> &str {
+        match self {
+            Self::Address(r) => &r.postal_code,
+            Self::Business(r) => &r.postal_code,
+        }
+    }
+}
+
+impl From<UtfKenAllRecord> for PostalCodeRecord {
+    fn from(record: UtfKenAllRecord) -> Self {
+        Self::Address(record)
+    }
+}
+
+impl From<JigyosyoRecord> for PostalCodeRecord {
+    fn from(record: JigyosyoRecord) -> Self {
+        Self::Business(record)
+    }
+}
+
+/// 都道府県
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct Prefecture {
+    /// 都道府県コード（JIS X0401、2桁）
+    pub code: String,
+    /// 都道府県名
+    pub name: String,
+    /// 都道府県名（カタカナ）
+    pub name_kana: String,
+}
+
+/// 市区町村
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct City {
+    /// 全国地方公共団体コード（JIS X0402、5桁）
+    pub local_government_code: String,
+    /// 市区町村名
+    pub name: String,
+    /// 市区町村名（カタカナ）
+    pub name_kana: String,
+}
+
+/// 町域
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct Town {
+    /// 町域が属する市区町村の全国地方公共団体コード（JIS X0402、5桁）
+    pub local_government_code: String,
+    /// 郵便番号（7桁）
+    pub postal_code: String,
+    /// 町域名
+    pub name: String,
+    /// 町域名（カタカナ）
+    pub name_kana: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_code_try_from() {
+        assert_eq!(UpdateCode::try_from(0), Ok(UpdateCode::Unchanged));
+        assert_eq!(UpdateCode::try_from(2), Ok(UpdateCode::Abolished));
+        assert!(UpdateCode::try_from(3).is_err());
+        assert!(UpdateCode::try_from(-1).is_err());
+        assert_eq!(i16::from(UpdateCode::Changed), 1);
+    }
+
+    #[test]
+    fn test_update_reason_try_from() {
+        assert_eq!(UpdateReason::try_from(0), Ok(UpdateReason::Unchanged));
+        assert_eq!(UpdateReason::try_from(6), Ok(UpdateReason::Abolished));
+        assert!(UpdateReason::try_from(7).is_err());
+        assert_eq!(i16::from(UpdateReason::Correction), 5);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_out_of_range() {
+        assert_eq!(
+            serde_json::from_str::<UpdateReason>("3").unwrap(),
+            UpdateReason::LandReadjustment
+        );
+        assert_eq!(serde_json::to_string(&UpdateCode::Abolished).unwrap(), "2");
+        let err = serde_json::from_str::<UpdateCode>("9").unwrap_err();
+        assert_eq!(err.to_string(), "invalid update code: 9");
+    }
 }
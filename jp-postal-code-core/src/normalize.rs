@@ -6,9 +6,21 @@ macro_rules! define {
         static $name: LazyLock<Regex> = LazyLock::new(|| Regex::new($re).unwrap());
     };
 }
+mod address;
+mod kana;
+mod postal_code;
+mod romaji;
 #[macro_use]
 mod town;
 mod town_kana;
+mod town_spec;
 
+pub use address::normalize_address_query;
+pub use kana::{is_kana_query, normalize_kana_query, to_zenkaku_katakana};
+pub use postal_code::{normalize_postal_code_query, PostalCodeQueryError};
+pub use romaji::to_hepburn;
 pub use town::normalize_utf_ken_all_record_town;
+pub(crate) use town::unify_tilde_and_dash;
 pub use town_kana::normalize_utf_ken_all_record_town_kana;
+pub use town_spec::normalize_utf_ken_all_record_town_spec;
+pub(crate) use town_spec::parse_number;
@@ -0,0 +1,51 @@
+use super::unify_tilde_and_dash;
+
+/// 住所の検索クエリを正規化する
+///
+/// [`normalize_utf_ken_all_record_town`](super::normalize_utf_ken_all_record_town) で正規化した
+/// 町域と比較できるように、表記揺れを同じ規則で統一する。
+///
+/// - 空白（全角空白を含む）を除去する
+/// - 半角英数字・記号を全角に変換する
+/// - 波ダッシュとダッシュ系統を全角チルダと全角ハイフンに統一する
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::normalize::normalize_address_query;
+/// assert_eq!(normalize_address_query("東京都 港区 芝浦3-4"), "東京都港区芝浦３－４");
+/// ```
+pub fn normalize_address_query(query: &str) -> String {
+    // 空白を除去し、半角英数字・記号（U+0021〜U+007E）を全角に変換
+    let query: String = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '!'..='~' => char::from_u32(c as u32 + 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect();
+    unify_tilde_and_dash(&query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_address_query() {
+        assert_eq!(normalize_address_query("東京都港区芝浦"), "東京都港区芝浦");
+        assert_eq!(
+            normalize_address_query(" 東京都　港区 芝浦 "),
+            "東京都港区芝浦"
+        );
+        assert_eq!(normalize_address_query("芝浦3丁目"), "芝浦３丁目");
+        assert_eq!(normalize_address_query("ABC-1"), "ＡＢＣ－１");
+        assert_eq!(
+            normalize_address_query("１—２-３−４–５"),
+            "１－２－３－４－５"
+        );
+        assert_eq!(normalize_address_query("1〜5"), "１～５");
+        // ダッシュ系統以外の記号はそのまま
+        assert_eq!(normalize_address_query("第Ⅱ地割※"), "第Ⅱ地割※");
+    }
+}
@@ -0,0 +1,145 @@
+use super::normalize_address_query;
+
+/// 半角カタカナ（U+FF61〜U+FF9F）に対応する全角文字
+const HANKAKU_KATAKANA_TO_ZENKAKU: [char; 63] = [
+    '。', '「', '」', '、', '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー',
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン', '゛', '゜',
+];
+
+/// 濁点を付けた全角カタカナを返す
+fn with_dakuten(c: char) -> Option<char> {
+    match c {
+        // カ〜ト、ハ〜ホの濁音は直後の符号位置にある
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+        | 'ツ' | 'テ' | 'ト' | 'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => {
+            char::from_u32(c as u32 + 1)
+        }
+        'ウ' => Some('ヴ'),
+        _ => None,
+    }
+}
+
+/// 半濁点を付けた全角カタカナを返す
+fn with_handakuten(c: char) -> Option<char> {
+    match c {
+        // ハ〜ホの半濁音は2つ後の符号位置にある
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => char::from_u32(c as u32 + 2),
+        _ => None,
+    }
+}
+
+/// ひらがな・半角カタカナを全角カタカナに変換する
+///
+/// 半角カタカナの濁点（ﾞ）・半濁点（ﾟ）は直前の文字と合成する。それ以外の文字はそのまま残す。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::normalize::to_zenkaku_katakana;
+/// assert_eq!(to_zenkaku_katakana("しばうら"), "シバウラ");
+/// assert_eq!(to_zenkaku_katakana("ｼﾊﾞｳﾗ"), "シバウラ");
+/// ```
+pub fn to_zenkaku_katakana(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        let c = match c {
+            // ひらがな（ぁ〜ゖ）は 0x60 後ろにカタカナがある
+            'ぁ'..='ゖ' | 'ゝ' | 'ゞ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            '｡'..='ﾟ' => HANKAKU_KATAKANA_TO_ZENKAKU[(c as u32 - '｡' as u32) as usize],
+            _ => c,
+        };
+        let combined = match c {
+            '゛' => result.chars().last().and_then(with_dakuten),
+            '゜' => result.chars().last().and_then(with_handakuten),
+            _ => None,
+        };
+        match combined {
+            Some(combined) => {
+                result.pop();
+                result.push(combined);
+            }
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+/// 仮名の検索クエリを正規化する
+///
+/// 郵便番号データの仮名（全角カタカナ）と比較できるように、ひらがな・半角カタカナを全角カタカナに
+/// 変換してから [`normalize_address_query`] と同じ規則で空白の除去や全角への変換を行う。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::normalize::normalize_kana_query;
+/// assert_eq!(normalize_kana_query("とうきょうと ﾐﾅﾄｸ"), "トウキョウトミナトク");
+/// assert_eq!(normalize_kana_query("きた7じょう"), "キタ７ジョウ");
+/// ```
+pub fn normalize_kana_query(query: &str) -> String {
+    normalize_address_query(&to_zenkaku_katakana(query))
+}
+
+/// [`normalize_kana_query`] で正規化したクエリが住所の読みとして検索できるかどうか
+///
+/// 全角カタカナを1文字以上含み、それ以外は長音記号と全角英数字・記号だけからなる場合に `true`
+/// を返す。漢字やひらがなを含む場合は住所として検索するべきなので `false` を返す。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::normalize::{is_kana_query, normalize_kana_query};
+/// assert!(is_kana_query(&normalize_kana_query("しばうら")));
+/// assert!(is_kana_query(&normalize_kana_query("きた7じょう")));
+/// assert!(!is_kana_query(&normalize_kana_query("芝浦")));
+/// ```
+pub fn is_kana_query(query: &str) -> bool {
+    query.chars().any(|c| matches!(c, 'ァ'..='ヺ'))
+        && query
+            .chars()
+            .all(|c| matches!(c, 'ァ'..='ヺ' | 'ー' | '・' | '！'..='～'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_zenkaku_katakana() {
+        assert_eq!(to_zenkaku_katakana("シバウラ"), "シバウラ");
+        assert_eq!(to_zenkaku_katakana("しばうら"), "シバウラ");
+        assert_eq!(to_zenkaku_katakana("ぁゖゝゞ"), "ァヶヽヾ");
+        assert_eq!(to_zenkaku_katakana("ｼﾊﾞｳﾗ"), "シバウラ");
+        assert_eq!(to_zenkaku_katakana("ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ"), "パピプペポ");
+        assert_eq!(to_zenkaku_katakana("ｳﾞｧｲｵﾘﾝ"), "ヴァイオリン");
+        assert_eq!(to_zenkaku_katakana("ｰｯｬ"), "ーッャ");
+        // 合成できない濁点・半濁点はそのまま残す
+        assert_eq!(to_zenkaku_katakana("ｱﾞﾟ"), "ア゛゜");
+        assert_eq!(to_zenkaku_katakana("ﾞ"), "゛");
+        // 仮名以外はそのまま
+        assert_eq!(to_zenkaku_katakana("港区1丁目"), "港区1丁目");
+    }
+
+    #[test]
+    fn test_normalize_kana_query() {
+        assert_eq!(normalize_kana_query("しばうら"), "シバウラ");
+        assert_eq!(normalize_kana_query(" ｼﾊﾞ ｳﾗ "), "シバウラ");
+        assert_eq!(
+            normalize_kana_query("おおどおりにし(1-19ちょうめ)"),
+            "オオドオリニシ（１－１９チョウメ）"
+        );
+    }
+
+    #[test]
+    fn test_is_kana_query() {
+        assert!(is_kana_query("シバウラ"));
+        assert!(is_kana_query("ニセコチョウ"));
+        assert!(is_kana_query("キタ７ジョウ"));
+        assert!(is_kana_query("ファミリー・パーク"));
+        // 漢字・ひらがなを含むものや仮名を含まないものは住所として扱う
+        assert!(!is_kana_query("ニセコ町"));
+        assert!(!is_kana_query("シバうら"));
+        assert!(!is_kana_query("ＡＢＣ"));
+        assert!(!is_kana_query("ー"));
+        assert!(!is_kana_query(""));
+    }
+}
@@ -0,0 +1,115 @@
+/// 郵便番号の検索クエリを正規化できなかったことを表すエラー
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PostalCodeQueryError {
+    /// 数字・ハイフン以外の文字が含まれている
+    #[error("postal code must consist of digits, but found {0:?}")]
+    InvalidCharacter(char),
+    /// 郵便番号の桁数（7桁）を超えている
+    #[error("postal code must be at most 7 digits")]
+    TooLong,
+}
+
+/// 郵便番号の最大桁数
+const POSTAL_CODE_LENGTH: usize = 7;
+
+/// 郵便番号の検索クエリを正規化する
+///
+/// 入力された郵便番号の表記揺れを吸収し、半角数字のみの文字列に変換する。
+///
+/// - 空白（全角空白を含む）を除去する
+/// - 先頭の郵便記号（〒）を除去する
+/// - 全角数字を半角数字に変換する
+/// - ダッシュ系統（[`normalize_utf_ken_all_record_town`](super::normalize_utf_ken_all_record_town)
+///   で全角ハイフンに統一するもの）と長音記号を除去する
+///
+/// 上記以外の文字が含まれている場合や、7桁を超える場合はエラーを返す。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::normalize::normalize_postal_code_query;
+/// assert_eq!(normalize_postal_code_query("〒１０８－００２３").unwrap(), "1080023");
+/// assert!(normalize_postal_code_query("108%").is_err());
+/// ```
+pub fn normalize_postal_code_query(query: &str) -> Result<String, PostalCodeQueryError> {
+    let query = query.trim_start();
+    let query = query.strip_prefix('〒').unwrap_or(query);
+    let mut postal_code = String::with_capacity(POSTAL_CODE_LENGTH);
+    for c in query.chars() {
+        match c {
+            '0'..='9' => postal_code.push(c),
+            // 全角数字（U+FF10〜U+FF19）を半角に変換
+            '０'..='９' => postal_code.push(char::from_u32(c as u32 - 0xFEE0).unwrap()),
+            // ダッシュ系統と長音記号は区切りとして読み飛ばす
+            // — 全角ダッシュ（EMダッシュ）	U+2014
+            // - ハイフン	                U+002d
+            // − マイナス	                U+2212
+            // – ENダッシュ	                U+2013
+            // －全角ハイフン	            U+ff0d
+            // ー長音記号	                U+30fc
+            '—' | '-' | '−' | '–' | '－' | 'ー' => {}
+            c if c.is_whitespace() => {}
+            c => return Err(PostalCodeQueryError::InvalidCharacter(c)),
+        }
+    }
+    if postal_code.len() > POSTAL_CODE_LENGTH {
+        return Err(PostalCodeQueryError::TooLong);
+    }
+    Ok(postal_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_postal_code_query() {
+        assert_eq!(normalize_postal_code_query("1080023").unwrap(), "1080023");
+        assert_eq!(normalize_postal_code_query("108").unwrap(), "108");
+        assert_eq!(normalize_postal_code_query("").unwrap(), "");
+        assert_eq!(normalize_postal_code_query("108-0023").unwrap(), "1080023");
+        assert_eq!(
+            normalize_postal_code_query("１０８００２３").unwrap(),
+            "1080023"
+        );
+        assert_eq!(
+            normalize_postal_code_query("〒１０８－００２３").unwrap(),
+            "1080023"
+        );
+        assert_eq!(
+            normalize_postal_code_query(" 〒 108 0023　").unwrap(),
+            "1080023"
+        );
+        for hyphen in ['—', '-', '−', '–', '－', 'ー'] {
+            assert_eq!(
+                normalize_postal_code_query(&format!("108{hyphen}0023")).unwrap(),
+                "1080023"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_postal_code_query_error() {
+        assert_eq!(
+            normalize_postal_code_query("abc"),
+            Err(PostalCodeQueryError::InvalidCharacter('a'))
+        );
+        // LIKE のワイルドカードは受け付けない
+        assert_eq!(
+            normalize_postal_code_query("10%"),
+            Err(PostalCodeQueryError::InvalidCharacter('%'))
+        );
+        assert_eq!(
+            normalize_postal_code_query("10_"),
+            Err(PostalCodeQueryError::InvalidCharacter('_'))
+        );
+        // 〒は先頭にのみ書ける
+        assert_eq!(
+            normalize_postal_code_query("108〒0023"),
+            Err(PostalCodeQueryError::InvalidCharacter('〒'))
+        );
+        assert_eq!(
+            normalize_postal_code_query("10800234"),
+            Err(PostalCodeQueryError::TooLong)
+        );
+    }
+}
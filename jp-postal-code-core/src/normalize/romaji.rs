@@ -0,0 +1,231 @@
+use super::to_zenkaku_katakana;
+
+/// 全角カタカナ1文字をヘボン式ローマ字に変換する
+fn syllable(c: char) -> Option<&'static str> {
+    let romaji = match c {
+        'ア' | 'ァ' => "A",
+        'イ' | 'ィ' => "I",
+        'ウ' | 'ゥ' => "U",
+        'エ' | 'ェ' => "E",
+        'オ' | 'ォ' => "O",
+        'カ' | 'ヵ' => "KA",
+        'キ' => "KI",
+        'ク' => "KU",
+        'ケ' | 'ヶ' => "KE",
+        'コ' => "KO",
+        'ガ' => "GA",
+        'ギ' => "GI",
+        'グ' => "GU",
+        'ゲ' => "GE",
+        'ゴ' => "GO",
+        'サ' => "SA",
+        'シ' => "SHI",
+        'ス' => "SU",
+        'セ' => "SE",
+        'ソ' => "SO",
+        'ザ' => "ZA",
+        'ジ' | 'ヂ' => "JI",
+        'ズ' | 'ヅ' => "ZU",
+        'ゼ' => "ZE",
+        'ゾ' => "ZO",
+        'タ' => "TA",
+        'チ' => "CHI",
+        'ツ' => "TSU",
+        'テ' => "TE",
+        'ト' => "TO",
+        'ダ' => "DA",
+        'デ' => "DE",
+        'ド' => "DO",
+        'ナ' => "NA",
+        'ニ' => "NI",
+        'ヌ' => "NU",
+        'ネ' => "NE",
+        'ノ' => "NO",
+        'ハ' => "HA",
+        'ヒ' => "HI",
+        'フ' => "FU",
+        'ヘ' => "HE",
+        'ホ' => "HO",
+        'バ' => "BA",
+        'ビ' => "BI",
+        'ブ' => "BU",
+        'ベ' => "BE",
+        'ボ' => "BO",
+        'パ' => "PA",
+        'ピ' => "PI",
+        'プ' => "PU",
+        'ペ' => "PE",
+        'ポ' => "PO",
+        'マ' => "MA",
+        'ミ' => "MI",
+        'ム' => "MU",
+        'メ' => "ME",
+        'モ' => "MO",
+        'ヤ' | 'ャ' => "YA",
+        'ユ' | 'ュ' => "YU",
+        'ヨ' | 'ョ' => "YO",
+        'ラ' => "RA",
+        'リ' => "RI",
+        'ル' => "RU",
+        'レ' => "RE",
+        'ロ' => "RO",
+        'ワ' | 'ヮ' => "WA",
+        'ヰ' => "I",
+        'ヱ' => "E",
+        'ヲ' => "O",
+        'ン' => "N",
+        'ヴ' => "VU",
+        _ => return None,
+    };
+    Some(romaji)
+}
+
+/// 拗音（キャ、シュ、チョなど）や外来語の表記（ファ、ティなど）をヘボン式ローマ字に変換する
+fn digraph(c: char, small: char) -> Option<String> {
+    let romaji = match (c, small) {
+        ('フ', 'ァ') => "FA",
+        ('フ', 'ィ') => "FI",
+        ('フ', 'ェ') => "FE",
+        ('フ', 'ォ') => "FO",
+        ('ヴ', 'ァ') => "VA",
+        ('ヴ', 'ィ') => "VI",
+        ('ヴ', 'ェ') => "VE",
+        ('ヴ', 'ォ') => "VO",
+        ('ウ', 'ィ') => "WI",
+        ('ウ', 'ェ') => "WE",
+        ('ウ', 'ォ') => "WO",
+        ('テ', 'ィ') => "TI",
+        ('デ', 'ィ') => "DI",
+        ('ト', 'ゥ') => "TU",
+        ('ド', 'ゥ') => "DU",
+        ('シ', 'ェ') => "SHE",
+        ('チ', 'ェ') => "CHE",
+        ('ジ', 'ェ') => "JE",
+        (_, 'ャ' | 'ュ' | 'ョ') => {
+            // イ段の子音に ya/yu/yo を続ける（シ・チ・ジは y を付けない）
+            let stem = syllable(c)?.strip_suffix('I')?;
+            if stem.is_empty() {
+                return None;
+            }
+            let vowel = &syllable(small)?[1..];
+            return Some(match stem {
+                "SH" | "CH" | "J" => format!("{stem}{vowel}"),
+                _ => format!("{stem}Y{vowel}"),
+            });
+        }
+        _ => return None,
+    };
+    Some(romaji.to_string())
+}
+
+/// 仮名をヘボン式ローマ字（大文字）に変換する
+///
+/// 日本郵便のローマ字住所にならい、長音（ー、オウ、オオ、ウウ）は母音を重ねずに表し、撥音は常に
+/// `N` とする。促音は続く子音を重ね、チの前では `T` とする。ひらがな・半角カタカナも受け付け、
+/// 全角英数字や記号は半角に変換する。それ以外の文字はそのまま残す。
+///
+/// 語の区切りは判別できないため空白は入れない。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_core::normalize::to_hepburn;
+/// assert_eq!(to_hepburn("トウキョウト"), "TOKYOTO");
+/// assert_eq!(to_hepburn("サッポロシチュウオウク"), "SAPPOROSHICHUOKU");
+/// assert_eq!(to_hepburn("シバ１チョウメ"), "SHIBA1CHOME");
+/// ```
+pub fn to_hepburn(kana: &str) -> String {
+    let chars = to_zenkaku_katakana(kana).chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(chars.len() * 2);
+    let mut sokuon = false;
+    // 直前の仮名の母音（長音の判定に使う）
+    let mut last_vowel = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let (romaji, len) = match chars.get(i + 1).and_then(|small| digraph(c, *small)) {
+            Some(romaji) => (Some(romaji), 2),
+            None => (syllable(c).map(str::to_string), 1),
+        };
+        i += len;
+        let Some(romaji) = romaji else {
+            match c {
+                'ッ' => sokuon = true,
+                // 長音符は直前の母音を伸ばすだけなので表記しない
+                'ー' => {}
+                '！'..='～' => {
+                    result.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c));
+                    last_vowel = None;
+                }
+                '　' => {
+                    result.push(' ');
+                    last_vowel = None;
+                }
+                // 全角以外のダッシュや波ダッシュも半角にする
+                '—' | '−' | '–' => {
+                    result.push('-');
+                    last_vowel = None;
+                }
+                '〜' => {
+                    result.push('~');
+                    last_vowel = None;
+                }
+                _ => {
+                    result.push(c);
+                    last_vowel = None;
+                }
+            }
+            continue;
+        };
+        // 長音（オウ、オオ、ウウ）は後ろの母音を省く
+        if matches!(
+            (last_vowel, romaji.as_str()),
+            (Some('O' | 'U'), "U") | (Some('O'), "O")
+        ) {
+            continue;
+        }
+        if std::mem::take(&mut sokuon) {
+            if romaji.starts_with("CH") {
+                result.push('T');
+            } else if let Some(consonant) = romaji.chars().next().filter(|c| !"AIUEON".contains(*c))
+            {
+                result.push(consonant);
+            }
+        }
+        result.push_str(&romaji);
+        last_vowel = romaji.chars().last().filter(|c| "AIUEO".contains(*c));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_hepburn() {
+        assert_eq!(to_hepburn("ホッカイドウ"), "HOKKAIDO");
+        assert_eq!(to_hepburn("オオドオリヒガシ"), "ODORIHIGASHI");
+        assert_eq!(to_hepburn("アサヒガオカ"), "ASAHIGAOKA");
+        assert_eq!(to_hepburn("ミナトク"), "MINATOKU");
+        assert_eq!(to_hepburn("シンジュク"), "SHINJUKU");
+        assert_eq!(to_hepburn("ジュウニソウ"), "JUNISO");
+        assert_eq!(to_hepburn("キョウト"), "KYOTO");
+        assert_eq!(to_hepburn("ハッチョウボリ"), "HATCHOBORI");
+        assert_eq!(to_hepburn("ベップ"), "BEPPU");
+        assert_eq!(to_hepburn("ヂュウ"), "JU");
+        assert_eq!(to_hepburn("ファミリーパーク"), "FAMIRIPAKU");
+        // ひらがな・半角カタカナも受け付ける
+        assert_eq!(to_hepburn("しばうら"), "SHIBAURA");
+        assert_eq!(to_hepburn("ｼﾊﾞｳﾗ"), "SHIBAURA");
+        // 仮名以外は全角英数字を半角にしてそのまま残す
+        assert_eq!(
+            to_hepburn("オオドオリニシ（１－１９チョウメ）"),
+            "ODORINISHI(1-19CHOME)"
+        );
+        assert_eq!(
+            to_hepburn("オオドオリニシ（２０−２８チョウメ）"),
+            "ODORINISHI(20-28CHOME)"
+        );
+        assert_eq!(to_hepburn(""), "");
+    }
+}
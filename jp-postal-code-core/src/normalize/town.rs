@@ -25,21 +25,7 @@ pub fn normalize_utf_ken_all_record_town(record: &UtfKenAllRecord) -> Vec<String
         return vec!["".to_string()];
     }
 
-    // 波ダッシュ（U+301C）と全角チルダ（U+FF5E）を全角チルダに統一
-    let town = {
-        define!(PATTERN, r"[〜～]");
-        PATTERN.replace_all(&record.town, "～").to_string()
-    };
-    // ダッシュ系統を全角ハイフンに統一
-    // — 全角ダッシュ（EMダッシュ）	U+2014
-    // - ハイフン	                U+002d
-    // − マイナス	                U+2212
-    // – ENダッシュ	                U+2013
-    // －全角ハイフン	            U+ff0d
-    let mut town = {
-        define!(PATTERN, r"[—-−–－]");
-        PATTERN.replace_all(&town, "－").to_string()
-    };
+    let mut town = unify_tilde_and_dash(&record.town);
 
     //
     // 不要文字列の除去
@@ -307,9 +293,29 @@ pub fn normalize_utf_ken_all_record_town(record: &UtfKenAllRecord) -> Vec<String
     vec![town]
 }
 
+/// 波ダッシュと全角チルダを全角チルダに、ダッシュ系統を全角ハイフンに統一する
+///
+/// 住所の検索クエリも町域と同じ規則で統一する。
+pub(crate) fn unify_tilde_and_dash(s: &str) -> String {
+    // 波ダッシュ（U+301C）と全角チルダ（U+FF5E）を全角チルダに統一
+    let s = {
+        define!(PATTERN, r"[〜～]");
+        PATTERN.replace_all(s, "～").to_string()
+    };
+    // ダッシュ系統を全角ハイフンに統一
+    // — 全角ダッシュ（EMダッシュ）	U+2014
+    // - ハイフン	                U+002d
+    // − マイナス	                U+2212
+    // – ENダッシュ	                U+2013
+    // －全角ハイフン	            U+ff0d
+    define!(PATTERN, r"[—\-−–－]");
+    PATTERN.replace_all(&s, "－").to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{UpdateCode, UpdateReason};
     use datafile_test::datafile_test;
     use itertools::Itertools as _;
 
@@ -338,8 +344,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 0,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             }
         }
     }
@@ -315,6 +315,7 @@ pub fn normalize_utf_ken_all_record_town_kana(record: &UtfKenAllRecord) -> Vec<S
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{UpdateCode, UpdateReason};
     use datafile_test::datafile_test;
     use itertools::Itertools as _;
 
@@ -343,8 +344,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 0,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             }
         }
     }
@@ -0,0 +1,396 @@
+use super::town::normalize_utf_ken_all_record_town;
+use crate::model::{BanchiRange, NumberRange, TownSpec, UtfKenAllRecord};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// 郵便番号レコードから、正規化した町域ごとの範囲を抽出する
+///
+/// [`normalize_utf_ken_all_record_town`] が返す町域と同じ順序・同じ数の範囲を返す。丁目・地割は
+/// 町域名から、番地の範囲や除外は元の町域名の括弧内から求める。丁目・地割ごとに分割した町域に
+/// 加えられる親の町域（「三ツ木（１～５丁目）」の「三ツ木」）は、分割した丁目・地割全体の範囲を表す。
+pub fn normalize_utf_ken_all_record_town_spec(record: &UtfKenAllRecord) -> Vec<TownSpec> {
+    let towns = normalize_utf_ken_all_record_town(record);
+    let splitted = towns.iter().map(|t| split_town(t)).collect::<Vec<_>>();
+    let mut constraints = banchi_constraints(&record.town);
+    towns
+        .iter()
+        .zip(&splitted)
+        .map(|(town, spec)| {
+            let mut spec = spec.clone();
+            if !spec.base.is_empty() && spec.chome.is_none() && spec.jiwari.is_none() {
+                let children = splitted
+                    .iter()
+                    .filter(|s| s.base == spec.base && (s.chome.is_some() || s.jiwari.is_some()));
+                spec.chome = span(children.clone().filter_map(|s| s.chome));
+                spec.jiwari = span(children.filter_map(|s| s.jiwari));
+            }
+            if let Some((banchi, excluded)) = constraints.remove(town) {
+                spec.banchi = banchi;
+                spec.excluded = excluded;
+            }
+            spec
+        })
+        .collect()
+}
+
+/// 町域名を丁目・地割とそれ以外に分ける
+fn split_town(town: &str) -> TownSpec {
+    define!(CHOME, r"^(.+?)([０-９]+)丁目$");
+    define!(JIWARI, r"^(.+?)第?([０-９]+)地割$");
+    if let Some(caps) = CHOME.captures(town) {
+        return TownSpec {
+            base: caps[1].to_string(),
+            chome: parse_number(&caps[2]).map(|n| NumberRange::new(n, n)),
+            ..Default::default()
+        };
+    }
+    if let Some(caps) = JIWARI.captures(town) {
+        return TownSpec {
+            base: caps[1].to_string(),
+            jiwari: parse_number(&caps[2]).map(|n| NumberRange::new(n, n)),
+            ..Default::default()
+        };
+    }
+    TownSpec {
+        base: town.to_string(),
+        ..Default::default()
+    }
+}
+
+/// 範囲をすべて含む最小の範囲
+fn span(ranges: impl Iterator<Item = NumberRange>) -> Option<NumberRange> {
+    ranges.reduce(|a, b| NumberRange::new(a.start.min(b.start), a.end.max(b.end)))
+}
+
+/// 全角数字の文字列を数値に変換する
+pub(crate) fn parse_number(s: &str) -> Option<u32> {
+    s.chars().try_fold(0u32, |n, c| {
+        let digit = ('０'..='９').position(|d| d == c)? as u32;
+        n.checked_mul(10)?.checked_add(digit)
+    })
+}
+
+/// 町域名の括弧内から、町域ごとの番地の範囲と除外される番地の範囲を求める
+///
+/// 町域は分割後の町域名（括弧の前の町域名と括弧内の地名をつないだもの）で表す。
+///
+/// - 竹房（４５０番地以下） → 竹房: ～４５０
+/// - 青木島町青木島乙（９５６番地以外） → 青木島町青木島乙: ９５６を除く
+/// - 大江（１丁目、２丁目「６５１、６６２、６６８番地」以外、３丁目５、１３－４、２０、６７８、６８７番地）
+///   → 大江２丁目: ６５１・６６２・６６８を除く、大江３丁目: ５・１３－４・２０・６７８・６８７
+fn banchi_constraints(town: &str) -> HashMap<String, (Vec<BanchiRange>, Vec<BanchiRange>)> {
+    // 「５７番地１２５」は「５７－１２５」と同じ
+    define!(BANCHI_SUB, r"([０-９])番地([０-９])");
+    define!(EXCLUSION, r"「([^」]*?)(?:を除く)?」(?:以外)?");
+    let town = super::unify_tilde_and_dash(town);
+    let town = BANCHI_SUB.replace_all(&town, "$1－$2");
+    let mut constraints = HashMap::<String, (Vec<BanchiRange>, Vec<BanchiRange>)>::new();
+    let (Some(open), Some(close)) = (town.find('（'), town.rfind('）')) else {
+        return constraints;
+    };
+    let prefix = &town[..open];
+    let inner = &town[open + '（'.len_utf8()..close];
+
+    // 括弧内の地名の後に番地が続いていれば、以降の番地はその地名のもの
+    let mut target = String::new();
+    for item in split_items(inner) {
+        let before = item.split('「').next().unwrap_or_default();
+        let text = EXCLUSION.replace_all(item, "");
+        let (label, range, modifier) = parse_item(&text);
+        if !label.is_empty() {
+            target = if range.is_some() {
+                label.to_string()
+            } else {
+                String::new()
+            };
+        }
+        if let Some(range) = range {
+            let entry = constraints.entry(format!("{prefix}{target}")).or_default();
+            match modifier {
+                Some("以外") => entry.1.push(range),
+                Some("以上" | "以降") => entry.0.push(BanchiRange { end: None, ..range }),
+                Some("以下") => entry.0.push(BanchiRange {
+                    start: None,
+                    ..range
+                }),
+                _ => entry.0.push(range),
+            }
+        }
+        for caps in EXCLUSION.captures_iter(item) {
+            let (label, ..) = parse_item(before);
+            let label = if label.is_empty() { &target } else { label };
+            let excluded = caps[1]
+                .split('、')
+                .filter_map(|s| parse_item(s).1)
+                .collect::<Vec<_>>();
+            constraints
+                .entry(format!("{prefix}{label}"))
+                .or_default()
+                .1
+                .extend(excluded);
+        }
+    }
+    constraints
+}
+
+/// 括弧内を読点で区切る（鉤括弧の中の読点では区切らない）
+fn split_items(inner: &str) -> Vec<&str> {
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '「' => depth += 1,
+            '」' => depth -= 1,
+            '、' if depth == 0 => {
+                items.push(&inner[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    items.push(&inner[start..]);
+    items
+}
+
+/// 括弧内の項目を地名・番地の範囲・範囲の修飾（以上・以下など）に分ける
+///
+/// 「３丁目５」は地名「３丁目」と番地５、「１７７０－１～２」は番地１７７０の枝番１～２になる。
+fn parse_item(item: &str) -> (&str, Option<BanchiRange>, Option<&str>) {
+    define!(
+        ITEM,
+        r"^(.*?)([０-９]+(?:－[０-９]+)?(?:～[０-９]+(?:－[０-９]+)?)?)?(?:番地|番)?(以降|以下|以上|以外)?$"
+    );
+    let Some(caps) = ITEM.captures(item) else {
+        return (item, None, None);
+    };
+    let label = caps.get(1).map_or("", |m| m.as_str());
+    let modifier = caps.get(3).map(|m| m.as_str());
+    let Some(numbers) = caps.get(2) else {
+        return (item, None, None);
+    };
+    let number = |s: &str| -> Option<(u32, Option<u32>)> {
+        match s.split_once('－') {
+            Some((banchi, sub)) => Some((parse_number(banchi)?, Some(parse_number(sub)?))),
+            None => Some((parse_number(s)?, None)),
+        }
+    };
+    let range = match numbers.as_str().split_once('～') {
+        Some((start, end)) => match (number(start), number(end)) {
+            // １７７０－１～２
+            (Some((banchi, Some(sub_start))), Some((sub_end, None))) => Some(BanchiRange {
+                start: Some(banchi),
+                end: Some(banchi),
+                sub: Some(NumberRange::new(sub_start, sub_end)),
+            }),
+            (Some((start, _)), Some((end, _))) => Some(BanchiRange {
+                start: Some(start),
+                end: Some(end),
+                sub: None,
+            }),
+            _ => None,
+        },
+        None => number(numbers.as_str()).map(|(banchi, sub)| BanchiRange {
+            sub: sub.map(|sub| NumberRange::new(sub, sub)),
+            ..BanchiRange::number(banchi)
+        }),
+    };
+    (label, range, modifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{UpdateCode, UpdateReason};
+
+    fn record(town: &str) -> UtfKenAllRecord {
+        UtfKenAllRecord {
+            local_government_code: "00000".to_string(),
+            old_postal_code: "000  ".to_string(),
+            postal_code: "0000000".to_string(),
+            prefecture_kana: "".to_string(),
+            city_kana: "".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "".to_string(),
+            city: "".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: TownSpec::default(),
+        }
+    }
+
+    /// 町域名ごとの範囲
+    fn town_specs(town: &str) -> Vec<(String, TownSpec)> {
+        let record = record(town);
+        normalize_utf_ken_all_record_town(&record)
+            .into_iter()
+            .zip(normalize_utf_ken_all_record_town_spec(&record))
+            .collect()
+    }
+
+    fn banchi(start: Option<u32>, end: Option<u32>) -> BanchiRange {
+        BanchiRange {
+            start,
+            end,
+            sub: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_utf_ken_all_record_town_spec_chome() {
+        let specs = town_specs("三ツ木（１～５丁目）");
+        assert_eq!(specs.len(), 6);
+        assert_eq!(
+            specs[2],
+            (
+                "三ツ木３丁目".to_string(),
+                TownSpec {
+                    base: "三ツ木".to_string(),
+                    chome: Some(NumberRange::new(3, 3)),
+                    ..Default::default()
+                }
+            )
+        );
+        // 親の町域は丁目全体の範囲を表す
+        assert_eq!(
+            specs[5],
+            (
+                "三ツ木".to_string(),
+                TownSpec {
+                    base: "三ツ木".to_string(),
+                    chome: Some(NumberRange::new(1, 5)),
+                    ..Default::default()
+                }
+            )
+        );
+
+        let specs = town_specs("種市第５０地割～第７０地割（大沢、城内、滝沢）");
+        let (town, spec) = specs.last().unwrap();
+        assert_eq!(town, "種市");
+        assert_eq!(spec.jiwari, Some(NumberRange::new(50, 70)));
+    }
+
+    #[test]
+    fn test_normalize_utf_ken_all_record_town_spec_banchi() {
+        assert_eq!(
+            town_specs("竹房（４５０番地以下）")[0].1.banchi,
+            vec![banchi(None, Some(450))]
+        );
+        assert_eq!(
+            town_specs("緑ヶ丘町（３５番以降）")[0].1.banchi,
+            vec![banchi(Some(35), None)]
+        );
+        let spec = &town_specs("青木島町青木島乙（９５６番地以外）")[0].1;
+        assert_eq!(spec.banchi, vec![]);
+        assert_eq!(spec.excluded, vec![BanchiRange::number(956)]);
+        assert_eq!(
+            town_specs("島内（９８２０、９８２１、９８２３～９８３０、９８６４番地以上）")[0]
+                .1
+                .banchi,
+            vec![
+                BanchiRange::number(9820),
+                BanchiRange::number(9821),
+                banchi(Some(9823), Some(9830)),
+                banchi(Some(9864), None),
+            ]
+        );
+
+        // 地名と番地の混在
+        let specs =
+            town_specs("東洋（油駒、南東洋、１３２～１５６、１５８～３５４、３６６、３６７番地）");
+        let (town, spec) = specs.last().unwrap();
+        assert_eq!(town, "東洋");
+        assert_eq!(spec.banchi.len(), 4);
+        assert_eq!(specs[0].1.banchi, vec![]);
+    }
+
+    #[test]
+    fn test_normalize_utf_ken_all_record_town_spec_symbol() {
+        // ダッシュ系統以外の記号を含む町域名でも、正規化した町域名と同じ町域に範囲を持たせる
+        assert_eq!(
+            town_specs("第Ⅱ工区※（４５０番地以下）"),
+            vec![(
+                "第Ⅱ工区※".to_string(),
+                TownSpec {
+                    base: "第Ⅱ工区※".to_string(),
+                    banchi: vec![banchi(None, Some(450))],
+                    ..Default::default()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_normalize_utf_ken_all_record_town_spec_excluded() {
+        let specs = town_specs("大江（１丁目、２丁目「６５１、６６２、６６８番地」以外、３丁目５、１３－４、２０、６７８、６８７番地）");
+        let find = |town: &str| specs.iter().find(|(t, _)| t == town).unwrap().1.clone();
+        let spec = find("大江２丁目");
+        assert_eq!(spec.chome, Some(NumberRange::new(2, 2)));
+        assert_eq!(
+            spec.excluded,
+            vec![
+                BanchiRange::number(651),
+                BanchiRange::number(662),
+                BanchiRange::number(668)
+            ]
+        );
+        assert!(!spec.covers(Some(2), None, Some((651, None))));
+        assert!(spec.covers(Some(2), None, Some((652, None))));
+        let spec = find("大江３丁目");
+        assert_eq!(
+            spec.banchi[1],
+            BanchiRange {
+                sub: Some(NumberRange::new(4, 4)),
+                ..BanchiRange::number(13)
+            }
+        );
+        assert!(spec.covers(Some(3), None, Some((678, None))));
+        assert!(!spec.covers(Some(3), None, Some((679, None))));
+        assert_eq!(find("大江１丁目").banchi, vec![]);
+        assert_eq!(find("大江").chome, Some(NumberRange::new(1, 3)));
+
+        // 枝番の除外
+        let (_, spec) = town_specs(
+            "南山（４３０番地以上「１７７０－１～２、１８６２－４２、１９２３－５を除く」、大谷地、折渡）",
+        )
+        .pop()
+        .unwrap();
+        assert_eq!(spec.base, "南山");
+        assert_eq!(spec.banchi, vec![banchi(Some(430), None)]);
+        assert_eq!(
+            spec.excluded[0],
+            BanchiRange {
+                sub: Some(NumberRange::new(1, 2)),
+                ..BanchiRange::number(1770)
+            }
+        );
+        assert!(spec.covers(None, None, Some((1770, Some(3)))));
+        assert!(!spec.covers(None, None, Some((1770, Some(2)))));
+        assert!(!spec.covers(None, None, Some((429, None))));
+
+        let specs = town_specs("葛巻（第４０地割「５７番地１２５、１７６を除く」～第４５地割）");
+        assert_eq!(specs[0].0, "葛巻第４０地割");
+        assert_eq!(specs[0].1.jiwari, Some(NumberRange::new(40, 40)));
+        assert_eq!(specs[0].1.excluded.len(), 2);
+    }
+
+    #[test]
+    fn test_normalize_utf_ken_all_record_town_spec_whole() {
+        assert_eq!(town_specs("以下に掲載がない場合")[0].1, TownSpec::default());
+        assert_eq!(
+            town_specs("芝浦")[0].1,
+            TownSpec {
+                base: "芝浦".to_string(),
+                ..Default::default()
+            }
+        );
+    }
+}
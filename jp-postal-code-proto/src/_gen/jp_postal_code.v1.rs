@@ -1,5 +1,70 @@
 // @generated
 // This file is @generated by prost-build.
+/// 都道府県
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Prefecture {
+    /// 都道府県コード（JIS X0401、2桁）
+    #[prost(string, tag="1")]
+    pub code: ::prost::alloc::string::String,
+    /// 都道府県名
+    #[prost(string, tag="2")]
+    pub name: ::prost::alloc::string::String,
+    /// 都道府県名（仮名）
+    #[prost(string, tag="3")]
+    pub name_kana: ::prost::alloc::string::String,
+}
+/// 市区町村
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct City {
+    /// 全国地方公共団体コード（JIS X0402、5桁）
+    #[prost(string, tag="1")]
+    pub local_government_code: ::prost::alloc::string::String,
+    /// 市区町村名
+    #[prost(string, tag="2")]
+    pub name: ::prost::alloc::string::String,
+    /// 市区町村名（仮名）
+    #[prost(string, tag="3")]
+    pub name_kana: ::prost::alloc::string::String,
+}
+/// 町域
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Town {
+    /// 町域が属する市区町村の全国地方公共団体コード（JIS X0402、5桁）
+    #[prost(string, tag="1")]
+    pub local_government_code: ::prost::alloc::string::String,
+    /// 郵便番号（7桁）
+    #[prost(string, tag="2")]
+    pub postal_code: ::prost::alloc::string::String,
+    /// 町域名
+    #[prost(string, tag="3")]
+    pub name: ::prost::alloc::string::String,
+    /// 町域名（仮名）
+    #[prost(string, tag="4")]
+    pub name_kana: ::prost::alloc::string::String,
+}
+/// 事業所
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Business {
+    /// 大口事業所名
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    /// 大口事業所名（仮名）
+    #[prost(string, tag="2")]
+    pub name_kana: ::prost::alloc::string::String,
+    /// 小字名、丁目、番地等
+    #[prost(string, tag="3")]
+    pub block_address: ::prost::alloc::string::String,
+    /// 取扱局
+    #[prost(string, tag="4")]
+    pub handling_office: ::prost::alloc::string::String,
+    /// 個別番号の種別
+    #[prost(enumeration="BusinessType", tag="5")]
+    pub business_type: i32,
+}
 /// 郵便番号住所
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -25,6 +90,433 @@ pub struct PostalAddress {
     /// 町域（仮名）
     #[prost(string, tag="7")]
     pub town_kana: ::prost::alloc::string::String,
+    /// 郵便番号の種別
+    #[prost(enumeration="PostalAddressKind", tag="8")]
+    pub kind: i32,
+    /// 事業所（事業所の個別郵便番号の場合のみ）
+    #[prost(message, optional, tag="9")]
+    pub business: ::core::option::Option<Business>,
+    /// 全国地方公共団体コード（JIS X0401、X0402）
+    #[prost(string, tag="10")]
+    pub local_government_code: ::prost::alloc::string::String,
+    /// 旧郵便番号（5桁）
+    #[prost(string, tag="11")]
+    pub old_postal_code: ::prost::alloc::string::String,
+    /// 一町域が二以上の郵便番号で表される場合か
+    #[prost(bool, tag="12")]
+    pub has_multi_postal_code: bool,
+    /// 丁目を有する町域か
+    #[prost(bool, tag="13")]
+    pub has_chome: bool,
+    /// 一つの郵便番号で二以上の町域を表す場合か
+    #[prost(bool, tag="14")]
+    pub has_multi_town: bool,
+    /// 更新の表示
+    #[prost(enumeration="UpdateCode", tag="15")]
+    pub update_code: i32,
+    /// 更新理由（住所の郵便番号の場合のみ）
+    #[prost(enumeration="UpdateReason", tag="16")]
+    pub update_reason: i32,
+    /// 都道府県（ローマ字、住所の郵便番号の場合のみ）
+    #[prost(string, tag="17")]
+    pub prefecture_roman: ::prost::alloc::string::String,
+    /// 市区町村（ローマ字、住所の郵便番号の場合のみ）
+    #[prost(string, tag="18")]
+    pub city_roman: ::prost::alloc::string::String,
+    /// 町域（ローマ字、住所の郵便番号の場合のみ）
+    #[prost(string, tag="19")]
+    pub town_roman: ::prost::alloc::string::String,
+}
+/// 郵便番号の種別
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PostalAddressKind {
+    /// 未指定
+    Unspecified = 0,
+    /// 住所の郵便番号
+    Address = 1,
+    /// 事業所の個別郵便番号
+    Business = 2,
+}
+impl PostalAddressKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "POSTAL_ADDRESS_KIND_UNSPECIFIED",
+            Self::Address => "POSTAL_ADDRESS_KIND_ADDRESS",
+            Self::Business => "POSTAL_ADDRESS_KIND_BUSINESS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "POSTAL_ADDRESS_KIND_UNSPECIFIED" => Some(Self::Unspecified),
+            "POSTAL_ADDRESS_KIND_ADDRESS" => Some(Self::Address),
+            "POSTAL_ADDRESS_KIND_BUSINESS" => Some(Self::Business),
+            _ => None,
+        }
+    }
+}
+/// 個別番号の種別
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BusinessType {
+    /// 未指定
+    Unspecified = 0,
+    /// 大口事業所
+    Office = 1,
+    /// 私書箱
+    PoBox = 2,
+}
+impl BusinessType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "BUSINESS_TYPE_UNSPECIFIED",
+            Self::Office => "BUSINESS_TYPE_OFFICE",
+            Self::PoBox => "BUSINESS_TYPE_PO_BOX",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BUSINESS_TYPE_UNSPECIFIED" => Some(Self::Unspecified),
+            "BUSINESS_TYPE_OFFICE" => Some(Self::Office),
+            "BUSINESS_TYPE_PO_BOX" => Some(Self::PoBox),
+            _ => None,
+        }
+    }
+}
+/// 更新の表示
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UpdateCode {
+    /// 未指定
+    Unspecified = 0,
+    /// 変更なし
+    Unchanged = 1,
+    /// 変更あり
+    Changed = 2,
+    /// 廃止
+    Abolished = 3,
+}
+impl UpdateCode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "UPDATE_CODE_UNSPECIFIED",
+            Self::Unchanged => "UPDATE_CODE_UNCHANGED",
+            Self::Changed => "UPDATE_CODE_CHANGED",
+            Self::Abolished => "UPDATE_CODE_ABOLISHED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UPDATE_CODE_UNSPECIFIED" => Some(Self::Unspecified),
+            "UPDATE_CODE_UNCHANGED" => Some(Self::Unchanged),
+            "UPDATE_CODE_CHANGED" => Some(Self::Changed),
+            "UPDATE_CODE_ABOLISHED" => Some(Self::Abolished),
+            _ => None,
+        }
+    }
+}
+/// 更新理由
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UpdateReason {
+    /// 未指定
+    Unspecified = 0,
+    /// 変更なし
+    Unchanged = 1,
+    /// 市政・区政・町政・分区・政令指定都市施行
+    MunicipalityEnforcement = 2,
+    /// 住居表示の実施
+    ResidenceIndication = 3,
+    /// 区画整理
+    LandReadjustment = 4,
+    /// 郵便区調整等
+    PostalDistrictAdjustment = 5,
+    /// 訂正
+    Correction = 6,
+    /// 廃止
+    Abolished = 7,
+}
+impl UpdateReason {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "UPDATE_REASON_UNSPECIFIED",
+            Self::Unchanged => "UPDATE_REASON_UNCHANGED",
+            Self::MunicipalityEnforcement => "UPDATE_REASON_MUNICIPALITY_ENFORCEMENT",
+            Self::ResidenceIndication => "UPDATE_REASON_RESIDENCE_INDICATION",
+            Self::LandReadjustment => "UPDATE_REASON_LAND_READJUSTMENT",
+            Self::PostalDistrictAdjustment => "UPDATE_REASON_POSTAL_DISTRICT_ADJUSTMENT",
+            Self::Correction => "UPDATE_REASON_CORRECTION",
+            Self::Abolished => "UPDATE_REASON_ABOLISHED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UPDATE_REASON_UNSPECIFIED" => Some(Self::Unspecified),
+            "UPDATE_REASON_UNCHANGED" => Some(Self::Unchanged),
+            "UPDATE_REASON_MUNICIPALITY_ENFORCEMENT" => {
+                Some(Self::MunicipalityEnforcement)
+            }
+            "UPDATE_REASON_RESIDENCE_INDICATION" => Some(Self::ResidenceIndication),
+            "UPDATE_REASON_LAND_READJUSTMENT" => Some(Self::LandReadjustment),
+            "UPDATE_REASON_POSTAL_DISTRICT_ADJUSTMENT" => {
+                Some(Self::PostalDistrictAdjustment)
+            }
+            "UPDATE_REASON_CORRECTION" => Some(Self::Correction),
+            "UPDATE_REASON_ABOLISHED" => Some(Self::Abolished),
+            _ => None,
+        }
+    }
+}
+/// 複数の郵便番号と完全一致する住所をまとめて取得するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchGetPostalAddressesRequest {
+    /// 郵便番号（7桁）の一覧
+    #[prost(string, repeated, tag="1")]
+    pub postal_codes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// 複数の郵便番号と完全一致する住所をまとめて取得するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchGetPostalAddressesResponse {
+    /// 入力された郵便番号ごとの取得結果
+    #[prost(map="string, message", tag="1")]
+    pub results: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        batch_get_postal_addresses_response::Item,
+    >,
+    /// 見つからなかった郵便番号（入力順）
+    #[prost(string, repeated, tag="2")]
+    pub not_found: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Nested message and enum types in `BatchGetPostalAddressesResponse`.
+pub mod batch_get_postal_addresses_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Item {
+        /// 郵便番号に対応するすべての住所
+        #[prost(message, repeated, tag="1")]
+        pub addresses: ::prost::alloc::vec::Vec<super::PostalAddress>,
+    }
+}
+/// 郵便番号と完全一致する住所を取得するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPostalAddressRequest {
+    /// 郵便番号（7桁）
+    #[prost(string, tag="1")]
+    pub postal_code: ::prost::alloc::string::String,
+}
+/// 郵便番号と完全一致する住所を取得するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPostalAddressResponse {
+    /// 郵便番号に対応するすべての住所
+    #[prost(message, repeated, tag="1")]
+    pub items: ::prost::alloc::vec::Vec<get_postal_address_response::Item>,
+}
+/// Nested message and enum types in `GetPostalAddressResponse`.
+pub mod get_postal_address_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Item {
+        #[prost(message, optional, tag="1")]
+        pub address: ::core::option::Option<super::PostalAddress>,
+    }
+}
+/// 都道府県の市区町村を一覧するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListCitiesRequest {
+    /// 都道府県コード（2桁）
+    #[prost(string, tag="1")]
+    pub prefecture_code: ::prost::alloc::string::String,
+}
+/// 都道府県の市区町村を一覧するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListCitiesResponse {
+    /// 全国地方公共団体コード順の市区町村
+    #[prost(message, repeated, tag="1")]
+    pub cities: ::prost::alloc::vec::Vec<City>,
+}
+/// 郵便番号の変更履歴を一覧するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPostalAddressHistoryRequest {
+    /// 郵便番号（7桁）
+    #[prost(string, tag="1")]
+    pub postal_code: ::prost::alloc::string::String,
+}
+/// 郵便番号の変更履歴を一覧するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPostalAddressHistoryResponse {
+    /// 取り込みの新しい順の変更履歴
+    #[prost(message, repeated, tag="1")]
+    pub items: ::prost::alloc::vec::Vec<list_postal_address_history_response::Item>,
+}
+/// Nested message and enum types in `ListPostalAddressHistoryResponse`.
+pub mod list_postal_address_history_response {
+    /// 変更された項目の変更前と変更後の値
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FieldChange {
+        /// 項目名（town, town_kana など）
+        #[prost(string, tag="1")]
+        pub field: ::prost::alloc::string::String,
+        /// 変更前の値（追加された場合は空）
+        #[prost(string, optional, tag="2")]
+        pub old_value: ::core::option::Option<::prost::alloc::string::String>,
+        /// 変更後の値（削除された場合は空）
+        #[prost(string, optional, tag="3")]
+        pub new_value: ::core::option::Option<::prost::alloc::string::String>,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Item {
+        #[prost(enumeration="super::PostalAddressChange", tag="1")]
+        pub change: i32,
+        /// 変更された項目（追加・削除された場合は全ての項目）
+        #[prost(message, repeated, tag="2")]
+        pub fields: ::prost::alloc::vec::Vec<FieldChange>,
+        /// 更新の表示（削除された場合は削除前のレコードのもの）
+        #[prost(enumeration="super::UpdateCode", tag="3")]
+        pub update_code: i32,
+        /// 更新理由（削除された場合は削除前のレコードのもの）
+        #[prost(enumeration="super::UpdateReason", tag="4")]
+        pub update_reason: i32,
+        /// 変更を取り込んだ郵便番号データベースのバージョン
+        #[prost(int64, tag="5")]
+        pub dataset_version: i64,
+        /// 変更を取り込んだ日時（RFC 3339）
+        #[prost(string, tag="6")]
+        pub imported_at: ::prost::alloc::string::String,
+    }
+}
+/// 取り込みによる住所の郵便番号のレコードの変更の種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PostalAddressChange {
+    /// 未指定
+    Unspecified = 0,
+    /// レコードが追加された
+    Added = 1,
+    /// レコードが削除された
+    Removed = 2,
+    /// レコードの項目が変更された
+    Changed = 3,
+}
+impl PostalAddressChange {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "POSTAL_ADDRESS_CHANGE_UNSPECIFIED",
+            Self::Added => "POSTAL_ADDRESS_CHANGE_ADDED",
+            Self::Removed => "POSTAL_ADDRESS_CHANGE_REMOVED",
+            Self::Changed => "POSTAL_ADDRESS_CHANGE_CHANGED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "POSTAL_ADDRESS_CHANGE_UNSPECIFIED" => Some(Self::Unspecified),
+            "POSTAL_ADDRESS_CHANGE_ADDED" => Some(Self::Added),
+            "POSTAL_ADDRESS_CHANGE_REMOVED" => Some(Self::Removed),
+            "POSTAL_ADDRESS_CHANGE_CHANGED" => Some(Self::Changed),
+            _ => None,
+        }
+    }
+}
+/// 都道府県を一覧するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListPrefecturesRequest {}
+/// 都道府県を一覧するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPrefecturesResponse {
+    /// 都道府県コード順の都道府県
+    #[prost(message, repeated, tag="1")]
+    pub prefectures: ::prost::alloc::vec::Vec<Prefecture>,
+}
+/// 市区町村の町域を一覧するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTownsRequest {
+    /// 全国地方公共団体コード（5桁）
+    #[prost(string, tag="1")]
+    pub local_government_code: ::prost::alloc::string::String,
+}
+/// 市区町村の町域を一覧するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTownsResponse {
+    /// 郵便番号順の町域
+    #[prost(message, repeated, tag="1")]
+    pub towns: ::prost::alloc::vec::Vec<Town>,
+}
+/// 住所を分解するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ParseAddressRequest {
+    /// 住所（例: 東京都港区芝浦３丁目１－１ ＡＢＣビル５Ｆ）
+    #[prost(string, tag="1")]
+    pub address: ::prost::alloc::string::String,
+}
+/// 住所を分解するレスポンス
+///
+/// 特定できなかった部分は設定されない。
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ParseAddressResponse {
+    /// 都道府県名
+    #[prost(string, optional, tag="1")]
+    pub prefecture: ::core::option::Option<::prost::alloc::string::String>,
+    /// 市区町村名
+    #[prost(string, optional, tag="2")]
+    pub city: ::core::option::Option<::prost::alloc::string::String>,
+    /// 全国地方公共団体コード
+    #[prost(string, optional, tag="3")]
+    pub local_government_code: ::core::option::Option<::prost::alloc::string::String>,
+    /// 町域名（丁目を除く）
+    #[prost(string, optional, tag="4")]
+    pub town: ::core::option::Option<::prost::alloc::string::String>,
+    /// 郵便番号
+    #[prost(string, optional, tag="5")]
+    pub postal_code: ::core::option::Option<::prost::alloc::string::String>,
+    /// 丁目の番号
+    #[prost(string, optional, tag="6")]
+    pub chome: ::core::option::Option<::prost::alloc::string::String>,
+    /// 番地・号をハイフンでつないだもの
+    #[prost(string, optional, tag="7")]
+    pub banchi: ::core::option::Option<::prost::alloc::string::String>,
+    /// 番地より後ろの建物名・部屋番号など
+    #[prost(string, optional, tag="8")]
+    pub building: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// 郵便番号に対応する住所を検索するリクエスト
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -60,5 +552,261 @@ pub mod search_postal_address_response {
         pub address: ::core::option::Option<super::PostalAddress>,
     }
 }
+/// 住所に対応する郵便番号を検索するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchPostalAddressByAddressRequest {
+    /// 検索クエリ（都道府県名・市区町村名・町域名）
+    #[prost(string, tag="1")]
+    pub address: ::prost::alloc::string::String,
+    /// 検索結果の最大件数
+    #[prost(int32, optional, tag="2")]
+    pub page_size: ::core::option::Option<i32>,
+    /// 一致する住所がない場合に、表記揺れや入力の誤りを許容した候補を探すかどうか
+    #[prost(bool, tag="3")]
+    pub did_you_mean: bool,
+}
+/// 住所に対応する郵便番号を検索するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchPostalAddressByAddressResponse {
+    /// 一致度の高い順に並べた検索結果
+    #[prost(message, repeated, tag="1")]
+    pub items: ::prost::alloc::vec::Vec<search_postal_address_by_address_response::Item>,
+    /// 表記揺れや入力の誤りを許容して見つけた候補（近い順、did_you_mean を指定して items が空の場合のみ）
+    #[prost(message, repeated, tag="2")]
+    pub did_you_mean: ::prost::alloc::vec::Vec<
+        search_postal_address_by_address_response::Item,
+    >,
+}
+/// Nested message and enum types in `SearchPostalAddressByAddressResponse`.
+pub mod search_postal_address_by_address_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Item {
+        #[prost(message, optional, tag="1")]
+        pub address: ::core::option::Option<super::PostalAddress>,
+    }
+}
+/// 住所の読みに対応する郵便番号を検索するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchPostalAddressByKanaRequest {
+    /// 検索クエリ（ひらがな・全角カタカナ・半角カタカナ）
+    #[prost(string, tag="1")]
+    pub kana: ::prost::alloc::string::String,
+    /// 検索結果の最大件数
+    #[prost(int32, optional, tag="2")]
+    pub page_size: ::core::option::Option<i32>,
+    /// ページトークン
+    #[prost(string, optional, tag="3")]
+    pub page_token: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// 住所の読みに対応する郵便番号を検索するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchPostalAddressByKanaResponse {
+    /// 検索結果
+    #[prost(message, repeated, tag="1")]
+    pub items: ::prost::alloc::vec::Vec<search_postal_address_by_kana_response::Item>,
+    /// 次のページトークン
+    #[prost(string, optional, tag="2")]
+    pub next_page_token: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Nested message and enum types in `SearchPostalAddressByKanaResponse`.
+pub mod search_postal_address_by_kana_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Item {
+        #[prost(message, optional, tag="1")]
+        pub address: ::core::option::Option<super::PostalAddress>,
+    }
+}
+/// 入力補完の候補を取得するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuggestRequest {
+    /// 入力途中のクエリ（郵便番号・住所・住所の読み）
+    #[prost(string, tag="1")]
+    pub query: ::prost::alloc::string::String,
+    /// 候補の最大件数
+    #[prost(int32, optional, tag="2")]
+    pub limit: ::core::option::Option<i32>,
+}
+/// 入力補完の候補を取得するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuggestResponse {
+    /// 順位の高い順に並べた候補
+    #[prost(message, repeated, tag="1")]
+    pub items: ::prost::alloc::vec::Vec<suggest_response::Item>,
+}
+/// Nested message and enum types in `SuggestResponse`.
+pub mod suggest_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Item {
+        /// クエリと一致した項目
+        #[prost(enumeration="super::SuggestField", tag="1")]
+        pub field: i32,
+        /// 候補として表示する文字列
+        #[prost(string, tag="2")]
+        pub text: ::prost::alloc::string::String,
+        /// text のうちクエリと一致した範囲の開始位置（文字単位）
+        #[prost(int32, tag="3")]
+        pub highlight_start: i32,
+        /// text のうちクエリと一致した範囲の終了位置（文字単位、この位置は含まない）
+        #[prost(int32, tag="4")]
+        pub highlight_end: i32,
+        #[prost(message, optional, tag="5")]
+        pub address: ::core::option::Option<super::PostalAddress>,
+    }
+}
+/// 入力補完でクエリと一致した項目
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SuggestField {
+    /// 未指定
+    Unspecified = 0,
+    /// 郵便番号
+    PostalCode = 1,
+    /// 住所
+    Address = 2,
+    /// 住所の読み
+    Kana = 3,
+}
+impl SuggestField {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "SUGGEST_FIELD_UNSPECIFIED",
+            Self::PostalCode => "SUGGEST_FIELD_POSTAL_CODE",
+            Self::Address => "SUGGEST_FIELD_ADDRESS",
+            Self::Kana => "SUGGEST_FIELD_KANA",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SUGGEST_FIELD_UNSPECIFIED" => Some(Self::Unspecified),
+            "SUGGEST_FIELD_POSTAL_CODE" => Some(Self::PostalCode),
+            "SUGGEST_FIELD_ADDRESS" => Some(Self::Address),
+            "SUGGEST_FIELD_KANA" => Some(Self::Kana),
+            _ => None,
+        }
+    }
+}
+/// 郵便番号と住所の組み合わせを検証するリクエスト
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidatePostalAddressRequest {
+    /// 郵便番号（7桁）
+    #[prost(string, tag="1")]
+    pub postal_code: ::prost::alloc::string::String,
+    /// 都道府県名（空なら照合しない）
+    #[prost(string, tag="2")]
+    pub prefecture: ::prost::alloc::string::String,
+    /// 市区町村名（空なら照合しない）
+    #[prost(string, tag="3")]
+    pub city: ::prost::alloc::string::String,
+    /// 町域名（空なら照合しない、番地などが続いていてもよい）
+    #[prost(string, tag="4")]
+    pub town: ::prost::alloc::string::String,
+}
+/// 郵便番号と住所の組み合わせを検証するレスポンス
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidatePostalAddressResponse {
+    /// 検証結果
+    #[prost(enumeration="ValidationResult", tag="1")]
+    pub result: i32,
+    /// 一致しなかった項目
+    #[prost(enumeration="AddressField", repeated, tag="2")]
+    pub mismatched_fields: ::prost::alloc::vec::Vec<i32>,
+    /// 修正の候補
+    #[prost(message, repeated, tag="3")]
+    pub suggestions: ::prost::alloc::vec::Vec<PostalAddress>,
+}
+/// 住所の検証で照合する項目
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AddressField {
+    /// 未指定
+    Unspecified = 0,
+    /// 郵便番号
+    PostalCode = 1,
+    /// 都道府県名
+    Prefecture = 2,
+    /// 市区町村名
+    City = 3,
+    /// 町域名
+    Town = 4,
+}
+impl AddressField {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "ADDRESS_FIELD_UNSPECIFIED",
+            Self::PostalCode => "ADDRESS_FIELD_POSTAL_CODE",
+            Self::Prefecture => "ADDRESS_FIELD_PREFECTURE",
+            Self::City => "ADDRESS_FIELD_CITY",
+            Self::Town => "ADDRESS_FIELD_TOWN",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ADDRESS_FIELD_UNSPECIFIED" => Some(Self::Unspecified),
+            "ADDRESS_FIELD_POSTAL_CODE" => Some(Self::PostalCode),
+            "ADDRESS_FIELD_PREFECTURE" => Some(Self::Prefecture),
+            "ADDRESS_FIELD_CITY" => Some(Self::City),
+            "ADDRESS_FIELD_TOWN" => Some(Self::Town),
+            _ => None,
+        }
+    }
+}
+/// 住所の検証結果
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ValidationResult {
+    /// 未指定
+    Unspecified = 0,
+    /// すべての項目が一致する
+    Valid = 1,
+    /// 一部の項目が一致しない
+    Partial = 2,
+    /// 指定したすべての項目が一致しない
+    Invalid = 3,
+}
+impl ValidationResult {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "VALIDATION_RESULT_UNSPECIFIED",
+            Self::Valid => "VALIDATION_RESULT_VALID",
+            Self::Partial => "VALIDATION_RESULT_PARTIAL",
+            Self::Invalid => "VALIDATION_RESULT_INVALID",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "VALIDATION_RESULT_UNSPECIFIED" => Some(Self::Unspecified),
+            "VALIDATION_RESULT_VALID" => Some(Self::Valid),
+            "VALIDATION_RESULT_PARTIAL" => Some(Self::Partial),
+            "VALIDATION_RESULT_INVALID" => Some(Self::Invalid),
+            _ => None,
+        }
+    }
+}
 include!("jp_postal_code.v1.tonic.rs");
 // @@protoc_insertion_point(module)
\ No newline at end of file
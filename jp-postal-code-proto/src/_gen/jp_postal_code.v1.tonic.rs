@@ -118,6 +118,358 @@ pub mod postal_address_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /** 住所に対応する郵便番号を検索する
+*/
+        pub async fn search_postal_address_by_address(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SearchPostalAddressByAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SearchPostalAddressByAddressResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/SearchPostalAddressByAddress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "SearchPostalAddressByAddress",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 住所の読みに対応する郵便番号を検索する
+*/
+        pub async fn search_postal_address_by_kana(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SearchPostalAddressByKanaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SearchPostalAddressByKanaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/SearchPostalAddressByKana",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "SearchPostalAddressByKana",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 入力途中の郵便番号・住所・住所の読みから入力補完の候補を取得する
+*/
+        pub async fn suggest(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SuggestRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SuggestResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/Suggest",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "Suggest",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 住所を都道府県・市区町村・町域・丁目・番地・建物名に分解する
+*/
+        pub async fn parse_address(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ParseAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ParseAddressResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/ParseAddress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "ParseAddress",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 郵便番号と住所（都道府県・市区町村・町域）の組み合わせが正しいか検証する
+*/
+        pub async fn validate_postal_address(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ValidatePostalAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ValidatePostalAddressResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/ValidatePostalAddress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "ValidatePostalAddress",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 郵便番号と完全一致する住所を取得する
+*/
+        pub async fn get_postal_address(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetPostalAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetPostalAddressResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/GetPostalAddress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "GetPostalAddress",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 複数の郵便番号と完全一致する住所をまとめて取得する
+*/
+        pub async fn batch_get_postal_addresses(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BatchGetPostalAddressesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BatchGetPostalAddressesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/BatchGetPostalAddresses",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "BatchGetPostalAddresses",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 郵便番号の住所の変更履歴を取り込みの新しい順に一覧する
+*/
+        pub async fn list_postal_address_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListPostalAddressHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPostalAddressHistoryResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/ListPostalAddressHistory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "ListPostalAddressHistory",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 都道府県を一覧する
+*/
+        pub async fn list_prefectures(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListPrefecturesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPrefecturesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/ListPrefectures",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "ListPrefectures",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 都道府県の市区町村を一覧する
+*/
+        pub async fn list_cities(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListCitiesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListCitiesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/ListCities",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "ListCities",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /** 市区町村の町域を一覧する
+*/
+        pub async fn list_towns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTownsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTownsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/jp_postal_code.v1.PostalAddressService/ListTowns",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "jp_postal_code.v1.PostalAddressService",
+                        "ListTowns",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -129,11 +481,110 @@ pub mod postal_address_service_server {
     pub trait PostalAddressService: Send + Sync + 'static {
         /** 郵便番号に対応する住所を検索する
 */
-        async fn search_postal_address(
+        async fn search_postal_address(
+            &self,
+            request: tonic::Request<super::SearchPostalAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SearchPostalAddressResponse>,
+            tonic::Status,
+        >;
+        /** 住所に対応する郵便番号を検索する
+*/
+        async fn search_postal_address_by_address(
+            &self,
+            request: tonic::Request<super::SearchPostalAddressByAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SearchPostalAddressByAddressResponse>,
+            tonic::Status,
+        >;
+        /** 住所の読みに対応する郵便番号を検索する
+*/
+        async fn search_postal_address_by_kana(
+            &self,
+            request: tonic::Request<super::SearchPostalAddressByKanaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SearchPostalAddressByKanaResponse>,
+            tonic::Status,
+        >;
+        /** 入力途中の郵便番号・住所・住所の読みから入力補完の候補を取得する
+*/
+        async fn suggest(
+            &self,
+            request: tonic::Request<super::SuggestRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SuggestResponse>,
+            tonic::Status,
+        >;
+        /** 住所を都道府県・市区町村・町域・丁目・番地・建物名に分解する
+*/
+        async fn parse_address(
+            &self,
+            request: tonic::Request<super::ParseAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ParseAddressResponse>,
+            tonic::Status,
+        >;
+        /** 郵便番号と住所（都道府県・市区町村・町域）の組み合わせが正しいか検証する
+*/
+        async fn validate_postal_address(
             &self,
-            request: tonic::Request<super::SearchPostalAddressRequest>,
+            request: tonic::Request<super::ValidatePostalAddressRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::SearchPostalAddressResponse>,
+            tonic::Response<super::ValidatePostalAddressResponse>,
+            tonic::Status,
+        >;
+        /** 郵便番号と完全一致する住所を取得する
+*/
+        async fn get_postal_address(
+            &self,
+            request: tonic::Request<super::GetPostalAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetPostalAddressResponse>,
+            tonic::Status,
+        >;
+        /** 複数の郵便番号と完全一致する住所をまとめて取得する
+*/
+        async fn batch_get_postal_addresses(
+            &self,
+            request: tonic::Request<super::BatchGetPostalAddressesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BatchGetPostalAddressesResponse>,
+            tonic::Status,
+        >;
+        /** 郵便番号の住所の変更履歴を取り込みの新しい順に一覧する
+*/
+        async fn list_postal_address_history(
+            &self,
+            request: tonic::Request<super::ListPostalAddressHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPostalAddressHistoryResponse>,
+            tonic::Status,
+        >;
+        /** 都道府県を一覧する
+*/
+        async fn list_prefectures(
+            &self,
+            request: tonic::Request<super::ListPrefecturesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPrefecturesResponse>,
+            tonic::Status,
+        >;
+        /** 都道府県の市区町村を一覧する
+*/
+        async fn list_cities(
+            &self,
+            request: tonic::Request<super::ListCitiesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListCitiesResponse>,
+            tonic::Status,
+        >;
+        /** 市区町村の町域を一覧する
+*/
+        async fn list_towns(
+            &self,
+            request: tonic::Request<super::ListTownsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTownsResponse>,
             tonic::Status,
         >;
     }
@@ -221,23 +672,323 @@ pub mod postal_address_service_server {
             match req.uri().path() {
                 "/jp_postal_code.v1.PostalAddressService/SearchPostalAddress" => {
                     #[allow(non_camel_case_types)]
-                    struct SearchPostalAddressSvc<T: PostalAddressService>(pub Arc<T>);
+                    struct SearchPostalAddressSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::SearchPostalAddressRequest>
+                    for SearchPostalAddressSvc<T> {
+                        type Response = super::SearchPostalAddressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchPostalAddressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::search_postal_address(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SearchPostalAddressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/SearchPostalAddressByAddress" => {
+                    #[allow(non_camel_case_types)]
+                    struct SearchPostalAddressByAddressSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::SearchPostalAddressByAddressRequest>
+                    for SearchPostalAddressByAddressSvc<T> {
+                        type Response = super::SearchPostalAddressByAddressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchPostalAddressByAddressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::search_postal_address_by_address(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SearchPostalAddressByAddressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/SearchPostalAddressByKana" => {
+                    #[allow(non_camel_case_types)]
+                    struct SearchPostalAddressByKanaSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::SearchPostalAddressByKanaRequest>
+                    for SearchPostalAddressByKanaSvc<T> {
+                        type Response = super::SearchPostalAddressByKanaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchPostalAddressByKanaRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::search_postal_address_by_kana(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SearchPostalAddressByKanaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/Suggest" => {
+                    #[allow(non_camel_case_types)]
+                    struct SuggestSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::SuggestRequest>
+                    for SuggestSvc<T> {
+                        type Response = super::SuggestResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SuggestRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::suggest(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SuggestSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/ParseAddress" => {
+                    #[allow(non_camel_case_types)]
+                    struct ParseAddressSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::ParseAddressRequest>
+                    for ParseAddressSvc<T> {
+                        type Response = super::ParseAddressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ParseAddressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::parse_address(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ParseAddressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/ValidatePostalAddress" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidatePostalAddressSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::ValidatePostalAddressRequest>
+                    for ValidatePostalAddressSvc<T> {
+                        type Response = super::ValidatePostalAddressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ValidatePostalAddressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::validate_postal_address(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ValidatePostalAddressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/GetPostalAddress" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPostalAddressSvc<T: PostalAddressService>(pub Arc<T>);
                     impl<
                         T: PostalAddressService,
-                    > tonic::server::UnaryService<super::SearchPostalAddressRequest>
-                    for SearchPostalAddressSvc<T> {
-                        type Response = super::SearchPostalAddressResponse;
+                    > tonic::server::UnaryService<super::GetPostalAddressRequest>
+                    for GetPostalAddressSvc<T> {
+                        type Response = super::GetPostalAddressResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SearchPostalAddressRequest>,
+                            request: tonic::Request<super::GetPostalAddressRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as PostalAddressService>::search_postal_address(
+                                <T as PostalAddressService>::get_postal_address(
                                         &inner,
                                         request,
                                     )
@@ -253,7 +1004,257 @@ pub mod postal_address_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = SearchPostalAddressSvc(inner);
+                        let method = GetPostalAddressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/BatchGetPostalAddresses" => {
+                    #[allow(non_camel_case_types)]
+                    struct BatchGetPostalAddressesSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::BatchGetPostalAddressesRequest>
+                    for BatchGetPostalAddressesSvc<T> {
+                        type Response = super::BatchGetPostalAddressesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::BatchGetPostalAddressesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::batch_get_postal_addresses(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = BatchGetPostalAddressesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/ListPostalAddressHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListPostalAddressHistorySvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::ListPostalAddressHistoryRequest>
+                    for ListPostalAddressHistorySvc<T> {
+                        type Response = super::ListPostalAddressHistoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListPostalAddressHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::list_postal_address_history(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListPostalAddressHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/ListPrefectures" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListPrefecturesSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::ListPrefecturesRequest>
+                    for ListPrefecturesSvc<T> {
+                        type Response = super::ListPrefecturesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListPrefecturesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::list_prefectures(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListPrefecturesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/ListCities" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListCitiesSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::ListCitiesRequest>
+                    for ListCitiesSvc<T> {
+                        type Response = super::ListCitiesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListCitiesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::list_cities(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListCitiesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/jp_postal_code.v1.PostalAddressService/ListTowns" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTownsSvc<T: PostalAddressService>(pub Arc<T>);
+                    impl<
+                        T: PostalAddressService,
+                    > tonic::server::UnaryService<super::ListTownsRequest>
+                    for ListTownsSvc<T> {
+                        type Response = super::ListTownsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTownsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PostalAddressService>::list_towns(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTownsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
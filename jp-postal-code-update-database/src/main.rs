@@ -1,14 +1,116 @@
 use anyhow::Context;
 use clap::Parser;
-use jp_postal_code::{config, infra, usecase, MIGRATOR};
+use jp_postal_code::{
+    config, infra, repo::UtfKenAllRepository, usecase, MIGRATOR, SQLITE_MIGRATOR,
+};
+use jp_postal_code_util::{utf_add_url, utf_del_url};
 use tracing_subscriber::prelude::*;
 
 #[derive(Parser)]
 #[command(name = "jp-postal-code-update-database")]
 #[command(about = "Update Japanese postal code database")]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
     #[arg(long, help = "Custom URL for utf_ken_all.zip (optional)")]
     url: Option<String>,
+    #[arg(long, help = "Custom URL for jigyosyo.zip (optional)")]
+    jigyosyo_url: Option<String>,
+    #[arg(long, help = "Custom URL for KEN_ALL_ROME.zip (optional)")]
+    rome_url: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["url", "jigyosyo_url", "rome_url"],
+        help = "Local utf_ken_all.zip or utf_ken_all.csv to import without network access (optional)"
+    )]
+    file: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["url", "jigyosyo_url", "rome_url"],
+        help = "Local jigyosyo.zip or JIGYOSYO.CSV to import without network access (optional)"
+    )]
+    jigyosyo_file: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "file",
+        help = "Local KEN_ALL_ROME.zip or KEN_ALL_ROME.CSV to import with --file (optional, romanized addresses are converted from kana if omitted)"
+    )]
+    rome_file: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        value_name = "YYMM",
+        value_parser = parse_yymm,
+        conflicts_with_all = ["url", "jigyosyo_url", "rome_url", "file", "jigyosyo_file", "rome_file"],
+        help = "Apply monthly diff files (utf_add_YYMM.zip / utf_del_YYMM.zip) instead of replacing all records"
+    )]
+    diff: Option<String>,
+    #[arg(
+        long,
+        requires = "diff",
+        help = "Custom URL for utf_add_YYMM.zip (optional)"
+    )]
+    add_url: Option<String>,
+    #[arg(
+        long,
+        requires = "diff",
+        help = "Custom URL for utf_del_YYMM.zip (optional)"
+    )]
+    del_url: Option<String>,
+    #[arg(
+        long,
+        value_name = "COUNT",
+        default_value_t = usecase::DEFAULT_IMPORT_MIN_RECORDS,
+        conflicts_with = "diff",
+        help = "Minimum number of postal code records required to replace the database"
+    )]
+    min_records: usize,
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = usecase::DEFAULT_IMPORT_MAX_CHANGE_PERCENT,
+        conflicts_with = "diff",
+        help = "Maximum change in the number of records from the current database, in percent"
+    )]
+    max_change_percent: f64,
+    #[arg(
+        long,
+        conflicts_with_all = ["diff", "dry_run"],
+        help = "Replace the database even if the postal code records fail validation"
+    )]
+    force: bool,
+    #[arg(
+        long,
+        conflicts_with = "diff",
+        help = "Validate the postal code records and print a report without updating the database"
+    )]
+    dry_run: bool,
+}
+
+// 取り込み済みの郵便番号データベースのバージョンを操作するサブコマンド
+// （省略した場合は郵便番号データベースを更新する）
+#[derive(clap::Subcommand)]
+enum Command {
+    #[command(about = "List dataset versions kept in the database (newest first)")]
+    ListVersions,
+    #[command(about = "Switch the active dataset to the given version")]
+    Activate {
+        #[arg(help = "Dataset version ID shown by list-versions")]
+        id: i64,
+    },
+    #[command(about = "Switch the active dataset back to the previous version")]
+    Rollback,
+}
+
+fn parse_yymm(s: &str) -> Result<String, String> {
+    if s.len() == 4 && s.chars().all(|c| c.is_ascii_digit()) {
+        Ok(s.to_string())
+    } else {
+        Err(format!("expected 4 digits like 2501, got {s:?}"))
+    }
 }
 
 #[tokio::main]
@@ -24,7 +126,7 @@ async fn main() {
         .init();
 
     if let Err(err) = run().await {
-        tracing::error!(?err, "Failed to update postal code database");
+        tracing::error!(?err, "Failed to run jp-postal-code-update-database");
         std::process::exit(1);
     }
 }
@@ -34,23 +136,201 @@ async fn run() -> Result<(), anyhow::Error> {
     let conf = config::Config::new();
 
     tracing::info!("Connecting to database...");
-    let pool = sqlx::PgPool::connect(conf.database_url.as_ref())
-        .await
-        .context("Failed to connect to database")?;
+    // `DATABASE_URL` のスキームでリポジトリの実装を切り替える
+    if conf.database_url.starts_with("memory:") {
+        anyhow::bail!("In-memory database cannot be updated. Restart the server instead.");
+    } else if conf.database_url.starts_with("sqlite:") {
+        let options = conf
+            .database_url
+            .parse::<sqlx::sqlite::SqliteConnectOptions>()
+            .context("Failed to parse database URL")?
+            .create_if_missing(true);
+        let pool = sqlx::SqlitePool::connect_with(options)
+            .await
+            .context("Failed to connect to database")?;
 
-    tracing::info!("Running database migrations...");
-    MIGRATOR
-        .run(&pool)
-        .await
-        .context("Failed to run database migrations")?;
+        tracing::info!("Running database migrations...");
+        SQLITE_MIGRATOR
+            .run(&pool)
+            .await
+            .context("Failed to run database migrations")?;
 
-    let mut repo = infra::postgres::UtfKenAllRepositoryPostgres::new(pool);
+        let repo = infra::sqlite::UtfKenAllRepositorySqlite::new(pool)
+            .with_versions_to_keep(conf.dataset_versions_to_keep);
+        execute(cli, repo).await
+    } else {
+        let pool = sqlx::PgPool::connect(conf.database_url.as_ref())
+            .await
+            .context("Failed to connect to database")?;
 
-    tracing::info!("Starting postal code database update...");
-    usecase::update_postal_code_database(&mut repo, cli.url)
-        .await
-        .context("Failed to update postal code database")?;
+        tracing::info!("Running database migrations...");
+        MIGRATOR
+            .run(&pool)
+            .await
+            .context("Failed to run database migrations")?;
+
+        let repo = infra::postgres::UtfKenAllRepositoryPostgres::new(pool)
+            .with_versions_to_keep(conf.dataset_versions_to_keep);
+        execute(cli, repo).await
+    }
+}
+
+async fn execute<R>(cli: Cli, mut repo: R) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository,
+{
+    match cli.command {
+        Some(Command::ListVersions) => {
+            let versions = usecase::list_dataset_versions(&repo)
+                .await
+                .context("Failed to list dataset versions")?;
+            println!(
+                "  {:>6}  {:<23}  {:>11}  {:>8}",
+                "ID", "CREATED AT", "UTF_KEN_ALL", "JIGYOSYO"
+            );
+            for v in versions {
+                println!(
+                    "{} {:>6}  {:<23}  {:>11}  {:>8}",
+                    if v.active { '*' } else { ' ' },
+                    v.id,
+                    v.created_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    v.utf_ken_all_count,
+                    v.jigyosyo_count
+                );
+            }
+            Ok(())
+        }
+        Some(Command::Activate { id }) => {
+            usecase::activate_dataset_version(&mut repo, id)
+                .await
+                .with_context(|| format!("Failed to activate dataset version {id}"))?;
+            tracing::info!(id, "Dataset version activated successfully!");
+            Ok(())
+        }
+        Some(Command::Rollback) => {
+            let id = usecase::rollback_dataset_version(&mut repo)
+                .await
+                .context("Failed to roll back dataset version")?;
+            tracing::info!(id, "Dataset version rolled back successfully!");
+            Ok(())
+        }
+        None => update(cli, repo).await,
+    }
+}
 
-    tracing::info!("Postal code database updated successfully!");
+async fn update<R>(cli: Cli, mut repo: R) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository,
+{
+    if let Some(yymm) = cli.diff {
+        tracing::info!(?yymm, "Applying monthly diff to postal code database...");
+        let add_url = cli.add_url.unwrap_or_else(|| utf_add_url(&yymm));
+        let del_url = cli.del_url.unwrap_or_else(|| utf_del_url(&yymm));
+        usecase::apply_postal_code_database_diff(&mut repo, add_url, del_url)
+            .await
+            .context("Failed to apply diff to postal code database")?;
+        tracing::info!("Postal code database updated successfully!");
+        return Ok(());
+    }
+
+    let options = usecase::ImportOptions {
+        min_records: cli.min_records,
+        max_change_percent: cli.max_change_percent,
+        force: cli.force,
+        dry_run: cli.dry_run,
+    };
+    if let Some(file) = cli.file {
+        // ローカルのファイルが指定された場合はダウンロードせず、事業所の個別郵便番号は指定された
+        // 場合だけ合わせて置き換える（省略した場合は使用中のバージョンのものを引き継ぐ）
+        tracing::info!(
+            ?file,
+            jigyosyo_file = ?cli.jigyosyo_file,
+            "Updating postal code database from local files..."
+        );
+        report_import(
+            usecase::update_postal_code_database_from_file(
+                &mut repo,
+                file,
+                cli.rome_file,
+                cli.jigyosyo_file,
+                &options,
+            )
+            .await,
+        )?;
+        if !options.dry_run {
+            tracing::info!("Postal code database updated successfully!");
+        }
+        return Ok(());
+    }
+    if let Some(file) = cli.jigyosyo_file {
+        // 事業所の個別郵便番号だけが指定された場合は、住所の郵便番号を使用中のバージョンから引き継ぐ
+        if options.dry_run {
+            tracing::info!(?file, "Dry run; skip updating jigyosyo database");
+            return Ok(());
+        }
+        tracing::info!(?file, "Updating jigyosyo database from a local file...");
+        usecase::update_jigyosyo_database_from_file(&mut repo, file)
+            .await
+            .context("Failed to update jigyosyo database")?;
+        tracing::info!("Postal code database updated successfully!");
+        return Ok(());
+    }
+
+    tracing::info!("Starting postal code database update...");
+    report_import(
+        usecase::update_postal_code_database(
+            &mut repo,
+            cli.url,
+            cli.rome_url,
+            cli.jigyosyo_url,
+            &options,
+        )
+        .await,
+    )?;
+    if !options.dry_run {
+        tracing::info!("Postal code database updated successfully!");
+    }
     Ok(())
 }
+
+// 郵便番号データベースを置き換える前の検証結果を表示する（検証に失敗した場合も表示する）
+fn report_import(
+    result: Result<usecase::ImportReport, anyhow::Error>,
+) -> Result<(), anyhow::Error> {
+    let report = match &result {
+        Ok(report) => report,
+        Err(err) => match err.downcast_ref::<usecase::ImportValidationError>() {
+            Some(usecase::ImportValidationError(report)) => report,
+            None => {
+                return result
+                    .map(|_| ())
+                    .context("Failed to update postal code database")
+            }
+        },
+    };
+    println!("Records:      {}", report.record_count);
+    match report.change_percent {
+        Some(percent) => println!("Current:      {} ({percent:+.1}%)", report.current_count),
+        None => println!("Current:      {}", report.current_count),
+    }
+    println!("Prefectures:  {}", report.prefecture_count);
+    if report.violations.is_empty() {
+        println!("Validation:   passed");
+    } else {
+        println!("Validation:   failed");
+        for violation in &report.violations {
+            println!("  - {violation}");
+        }
+    }
+    println!(
+        "Result:       {}",
+        match (&result, report.applied) {
+            (Err(_), _) => "not applied (use --force to replace the database anyway)",
+            (Ok(_), true) => "applied",
+            (Ok(_), false) => "not applied (dry run)",
+        }
+    );
+    result
+        .map(|_| ())
+        .context("Failed to update postal code database")
+}
@@ -18,6 +18,56 @@ pub enum DownloadError {
 pub static UTF_KEN_ALL_URL: &str =
     "https://www.post.japanpost.jp/zipcode/dl/utf/zip/utf_ken_all.zip";
 
+/// 日本郵便が配布している `utf_add_YYMM.zip`（追加・変更分の差分）のダウンロード URL を返す
+///
+/// `yymm` には差分の対象年月を `2409` のように西暦の下 2 桁と月の 2 桁で指定する。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::utf_add_url;
+/// assert_eq!(
+///     utf_add_url("2409"),
+///     "https://www.post.japanpost.jp/zipcode/dl/utf/zip/utf_add_2409.zip"
+/// );
+/// ```
+pub fn utf_add_url(yymm: &str) -> String {
+    format!("https://www.post.japanpost.jp/zipcode/dl/utf/zip/utf_add_{yymm}.zip")
+}
+
+/// 日本郵便が配布している `utf_del_YYMM.zip`（廃止・変更前の差分）のダウンロード URL を返す
+///
+/// `yymm` の指定方法は [utf_add_url] と同じ。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::utf_del_url;
+/// assert_eq!(
+///     utf_del_url("2409"),
+///     "https://www.post.japanpost.jp/zipcode/dl/utf/zip/utf_del_2409.zip"
+/// );
+/// ```
+pub fn utf_del_url(yymm: &str) -> String {
+    format!("https://www.post.japanpost.jp/zipcode/dl/utf/zip/utf_del_{yymm}.zip")
+}
+
+/// 日本郵便が配布している `jigyosyo.zip` のダウンロード URL
+///
+/// `jigyosyo.zip` に関しては [事業所の個別郵便番号（CSV形式）] を参照。
+/// 文字コードは Shift_JIS で、住所の郵便番号と同様に毎月末に情報が更新されている様子。
+///
+/// [事業所の個別郵便番号（CSV形式）]: https://www.post.japanpost.jp/zipcode/dl/jigyosyo/index-zip.html
+pub static JIGYOSYO_URL: &str =
+    "https://www.post.japanpost.jp/zipcode/dl/jigyosyo/zip/jigyosyo.zip";
+
+/// 日本郵便が配布している `KEN_ALL_ROME.zip`（住所の郵便番号のローマ字版）のダウンロード URL
+///
+/// `KEN_ALL_ROME.zip` に関しては [住所の郵便番号（ローマ字）（CSV形式）] を参照。
+/// 文字コードは Shift_JIS で、住所の郵便番号と同様に毎月末に情報が更新されている様子。
+///
+/// [住所の郵便番号（ローマ字）（CSV形式）]: https://www.post.japanpost.jp/zipcode/dl/roman-zip.html
+pub static KEN_ALL_ROME_URL: &str =
+    "https://www.post.japanpost.jp/zipcode/dl/roman/KEN_ALL_ROME.zip";
+
 /// 指定された `url` の内容を指定された `writer` に書き込む
 ///
 /// 日本郵便が配布している郵便番号データをダウンロードするには [UTF_KEN_ALL_URL] を指定する。
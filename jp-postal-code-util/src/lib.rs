@@ -1,5 +1,7 @@
 mod download;
 mod parse;
+mod rome;
 
 pub use download::*;
 pub use parse::*;
+pub use rome::*;
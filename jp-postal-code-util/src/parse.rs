@@ -1,4 +1,4 @@
-use jp_postal_code_core::model::UtfKenAllRecord;
+use jp_postal_code_core::model::{JigyosyoRecord, KenAllRomeRecord, UtfKenAllRecord};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
@@ -7,6 +7,60 @@ pub enum ParseError {
 
     #[error(transparent)]
     CsvError(#[from] csv::Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+/// zip ファイルの先頭に現れるシグネチャ（ローカルファイルヘッダ）
+const ZIP_SIGNATURE: &[u8; 4] = b"PK\x03\x04";
+
+/// 指定された `reader` が zip ファイルかどうかを内容から判定する
+///
+/// 判定のために先頭の数バイトを読み込むが、読み込み位置は元に戻す。
+fn is_zip<R>(reader: &mut R) -> Result<bool, std::io::Error>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    let position = reader.stream_position()?;
+    let mut signature = [0u8; 4];
+    let result = match reader.read_exact(&mut signature) {
+        Ok(()) => Ok(&signature == ZIP_SIGNATURE),
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    };
+    reader.seek(std::io::SeekFrom::Start(position))?;
+    result
+}
+
+/// 指定された `reader` を `utf_ken_all.zip` または `utf_ken_all.csv` としてパースし、郵便番号レコードを返す
+///
+/// ファイル名ではなく内容から zip か CSV かを判定し、[parse_utf_ken_all_zip] または
+/// [parse_utf_ken_all_csv] でパースする。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::parse_utf_ken_all;
+/// # fn main() {
+/// # let ken_all_file: std::path::PathBuf = vec![
+/// #   env!("CARGO_MANIFEST_DIR"),
+/// #   "testdata",
+/// #   "partial_utf_ken_all.csv",
+/// # ].iter().collect();
+/// let ken_all: std::fs::File = std::fs::File::open(ken_all_file).unwrap();
+/// let records = parse_utf_ken_all(ken_all).unwrap();
+/// # }
+/// ```
+#[tracing::instrument(skip(reader))]
+pub fn parse_utf_ken_all<R>(mut reader: R) -> Result<Vec<UtfKenAllRecord>, ParseError>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    if is_zip(&mut reader)? {
+        parse_utf_ken_all_zip(reader)
+    } else {
+        parse_utf_ken_all_csv(reader).map_err(Into::into)
+    }
 }
 
 /// 指定された `reader` を `utf_ken_all.zip` としてパースし、郵便番号レコードを返す
@@ -14,7 +68,8 @@ pub enum ParseError {
 /// `utf_ken_all.zip` に関しては [住所の郵便番号（1レコード1行、UTF-8形式）（CSV形式）] を参照。
 ///
 /// 内部的には `utf_ken_all.zip` は `utf_ken_all.csv` を zip で圧縮したものとして扱われる（最初
-/// のファイルが `utf_ken_all.csv` として扱われる）。
+/// のファイルが `utf_ken_all.csv` として扱われる）。差分ファイルの `utf_add_YYMM.zip` と
+/// `utf_del_YYMM.zip` も同じ形式なので、この関数でパースできる。
 ///
 /// # Example
 /// ```rust
@@ -85,11 +140,243 @@ where
     Ok(result)
 }
 
+/// 指定された `reader` を `jigyosyo.zip` または `JIGYOSYO.CSV` としてパースし、事業所の個別郵便番号レコードを返す
+///
+/// ファイル名ではなく内容から zip か CSV かを判定し、[parse_jigyosyo_zip] または
+/// [parse_jigyosyo_csv] でパースする。
+#[tracing::instrument(skip(reader))]
+pub fn parse_jigyosyo<R>(mut reader: R) -> Result<Vec<JigyosyoRecord>, ParseError>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    if is_zip(&mut reader)? {
+        parse_jigyosyo_zip(reader)
+    } else {
+        parse_jigyosyo_csv(reader).map_err(Into::into)
+    }
+}
+
+/// 指定された `reader` を `jigyosyo.zip` としてパースし、事業所の個別郵便番号レコードを返す
+///
+/// `jigyosyo.zip` に関しては [事業所の個別郵便番号（CSV形式）] を参照。
+///
+/// 内部的には `jigyosyo.zip` は `JIGYOSYO.CSV` を zip で圧縮したものとして扱われる（最初
+/// のファイルが `JIGYOSYO.CSV` として扱われる）。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::parse_jigyosyo_zip;
+/// # fn main() {
+/// # let jigyosyo_zip_file: std::path::PathBuf = vec![
+/// #   env!("CARGO_MANIFEST_DIR"),
+/// #   "testdata",
+/// #   "partial_jigyosyo.zip",
+/// # ].iter().collect();
+/// let jigyosyo_zip: std::fs::File = std::fs::File::open(jigyosyo_zip_file).unwrap();
+/// let records = parse_jigyosyo_zip(jigyosyo_zip).unwrap();
+/// # }
+/// ```
+///
+/// [事業所の個別郵便番号（CSV形式）]: https://www.post.japanpost.jp/zipcode/dl/jigyosyo/index-zip.html
+#[tracing::instrument(skip(reader))]
+pub fn parse_jigyosyo_zip<R>(reader: R) -> Result<Vec<JigyosyoRecord>, ParseError>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    tracing::info!("Start extracting `jigyosyo.zip`");
+    let mut zip = zip::ZipArchive::new(reader)?;
+    let file = zip.by_index(0)?;
+    tracing::info!(
+        name = file.name(),
+        compression = ?file.compression(),
+        size = file.size(),
+        last_modified = ?file.last_modified(),
+        version_made_by = ?file.version_made_by(),
+        "Finish extracting `jigyosyo.zip`"
+    );
+    parse_jigyosyo_csv(file).map_err(Into::into)
+}
+
+/// 指定された `reader` を `JIGYOSYO.CSV` としてパースし、事業所の個別郵便番号レコードを返す
+///
+/// `JIGYOSYO.CSV` は Shift_JIS でエンコードされているため、UTF-8 に変換してからパースする。
+/// 仕様は [事業所の個別郵便番号データの説明] を参照
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::parse_jigyosyo_csv;
+/// # fn main() {
+/// # let jigyosyo_csv_file: std::path::PathBuf = vec![
+/// #   env!("CARGO_MANIFEST_DIR"),
+/// #   "testdata",
+/// #   "partial_jigyosyo.csv",
+/// # ].iter().collect();
+/// let jigyosyo_csv: std::fs::File = std::fs::File::open(jigyosyo_csv_file).unwrap();
+/// let records = parse_jigyosyo_csv(jigyosyo_csv).unwrap();
+/// # }
+/// ```
+///
+/// [事業所の個別郵便番号データの説明]: https://www.post.japanpost.jp/zipcode/dl/jigyosyo/readme.html
+#[tracing::instrument(skip(reader))]
+pub fn parse_jigyosyo_csv<R>(reader: R) -> Result<Vec<JigyosyoRecord>, csv::Error>
+where
+    R: std::io::Read,
+{
+    tracing::info!("Start parsing `JIGYOSYO.CSV`");
+    let reader = encoding_rs_io::DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding_rs::SHIFT_JIS))
+        .build(reader);
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(reader);
+    let result = rdr
+        .deserialize::<JigyosyoRecord>()
+        .collect::<Result<Vec<_>, _>>()?;
+    tracing::info!(count = result.len(), "Finish parsing `JIGYOSYO.CSV`");
+    Ok(result)
+}
+
+/// 指定された `reader` を `KEN_ALL_ROME.zip` または `KEN_ALL_ROME.CSV` としてパースし、住所の郵便番号（ローマ字）レコードを返す
+///
+/// ファイル名ではなく内容から zip か CSV かを判定し、[parse_ken_all_rome_zip] または
+/// [parse_ken_all_rome_csv] でパースする。
+#[tracing::instrument(skip(reader))]
+pub fn parse_ken_all_rome<R>(mut reader: R) -> Result<Vec<KenAllRomeRecord>, ParseError>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    if is_zip(&mut reader)? {
+        parse_ken_all_rome_zip(reader)
+    } else {
+        parse_ken_all_rome_csv(reader).map_err(Into::into)
+    }
+}
+
+/// 指定された `reader` を `KEN_ALL_ROME.zip` としてパースし、住所の郵便番号（ローマ字）レコードを返す
+///
+/// `KEN_ALL_ROME.zip` に関しては [住所の郵便番号（ローマ字）（CSV形式）] を参照。
+///
+/// 内部的には `KEN_ALL_ROME.zip` は `KEN_ALL_ROME.CSV` を zip で圧縮したものとして扱われる（最初
+/// のファイルが `KEN_ALL_ROME.CSV` として扱われる）。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::parse_ken_all_rome_zip;
+/// # fn main() {
+/// # let ken_all_rome_zip_file: std::path::PathBuf = vec![
+/// #   env!("CARGO_MANIFEST_DIR"),
+/// #   "testdata",
+/// #   "partial_ken_all_rome.zip",
+/// # ].iter().collect();
+/// let ken_all_rome_zip: std::fs::File = std::fs::File::open(ken_all_rome_zip_file).unwrap();
+/// let records = parse_ken_all_rome_zip(ken_all_rome_zip).unwrap();
+/// # }
+/// ```
+///
+/// [住所の郵便番号（ローマ字）（CSV形式）]: https://www.post.japanpost.jp/zipcode/dl/roman-zip.html
+#[tracing::instrument(skip(reader))]
+pub fn parse_ken_all_rome_zip<R>(reader: R) -> Result<Vec<KenAllRomeRecord>, ParseError>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    tracing::info!("Start extracting `KEN_ALL_ROME.zip`");
+    let mut zip = zip::ZipArchive::new(reader)?;
+    let file = zip.by_index(0)?;
+    tracing::info!(
+        name = file.name(),
+        compression = ?file.compression(),
+        size = file.size(),
+        last_modified = ?file.last_modified(),
+        version_made_by = ?file.version_made_by(),
+        "Finish extracting `KEN_ALL_ROME.zip`"
+    );
+    parse_ken_all_rome_csv(file).map_err(Into::into)
+}
+
+/// 指定された `reader` を `KEN_ALL_ROME.CSV` としてパースし、住所の郵便番号（ローマ字）レコードを返す
+///
+/// `KEN_ALL_ROME.CSV` は Shift_JIS でエンコードされているため、UTF-8 に変換してからパースする。
+/// 仕様は [住所の郵便番号（ローマ字）の説明] を参照
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::parse_ken_all_rome_csv;
+/// # fn main() {
+/// # let ken_all_rome_csv_file: std::path::PathBuf = vec![
+/// #   env!("CARGO_MANIFEST_DIR"),
+/// #   "testdata",
+/// #   "partial_ken_all_rome.csv",
+/// # ].iter().collect();
+/// let ken_all_rome_csv: std::fs::File = std::fs::File::open(ken_all_rome_csv_file).unwrap();
+/// let records = parse_ken_all_rome_csv(ken_all_rome_csv).unwrap();
+/// # }
+/// ```
+///
+/// [住所の郵便番号（ローマ字）の説明]: https://www.post.japanpost.jp/zipcode/dl/readme_ro.html
+#[tracing::instrument(skip(reader))]
+pub fn parse_ken_all_rome_csv<R>(reader: R) -> Result<Vec<KenAllRomeRecord>, csv::Error>
+where
+    R: std::io::Read,
+{
+    tracing::info!("Start parsing `KEN_ALL_ROME.CSV`");
+    let reader = encoding_rs_io::DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding_rs::SHIFT_JIS))
+        .build(reader);
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(reader);
+    let result = rdr
+        .deserialize::<KenAllRomeRecord>()
+        .collect::<Result<Vec<_>, _>>()?;
+    tracing::info!(count = result.len(), "Finish parsing `KEN_ALL_ROME.CSV`");
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_parse_utf_ken_all_detects_zip_and_csv() {
+        // zip と CSV のどちらを渡しても同じレコードが得られる
+        let testdata: PathBuf = [env!("CARGO_MANIFEST_DIR"), "testdata"].iter().collect();
+        let zip = std::fs::File::open(testdata.join("partial_utf_ken_all.zip")).unwrap();
+        let csv = std::fs::File::open(testdata.join("partial_utf_ken_all.csv")).unwrap();
+        let from_zip = parse_utf_ken_all(zip).unwrap();
+        let from_csv = parse_utf_ken_all(csv).unwrap();
+        assert_eq!(from_zip.len(), 5);
+        assert_eq!(from_zip, from_csv);
+
+        // 空のファイルは CSV として扱われる
+        let records = parse_utf_ken_all(std::io::Cursor::new(vec![])).unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_parse_utf_ken_all_csv_rejects_out_of_range_codes() {
+        let csv = "01101,\"060  \",\"0600000\",\"ホッカイドウ\",\"サッポロシチュウオウク\",\"イカニケイサイガナイバアイ\",\"北海道\",\"札幌市中央区\",\"以下に掲載がない場合\",0,0,0,0,0,0\n";
+        assert!(parse_utf_ken_all_csv(csv.as_bytes()).is_ok());
+
+        // 更新の表示が範囲外
+        let invalid = csv.replace(",0,0,0,0,0,0\n", ",0,0,0,3,0,0\n");
+        assert!(parse_utf_ken_all_csv(invalid.as_bytes()).is_err());
+        // 更新理由が範囲外
+        let invalid = csv.replace(",0,0,0,0,0,0\n", ",0,0,0,0,7,0\n");
+        assert!(parse_utf_ken_all_csv(invalid.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_jigyosyo_detects_zip_and_csv() {
+        let testdata: PathBuf = [env!("CARGO_MANIFEST_DIR"), "testdata"].iter().collect();
+        let zip = std::fs::File::open(testdata.join("partial_jigyosyo.zip")).unwrap();
+        let csv = std::fs::File::open(testdata.join("partial_jigyosyo.csv")).unwrap();
+        let from_zip = parse_jigyosyo(zip).unwrap();
+        let from_csv = parse_jigyosyo(csv).unwrap();
+        assert_eq!(from_zip.len(), 4);
+        assert_eq!(from_zip, from_csv);
+    }
+
     #[test]
     fn test_parse_utf_ken_all_zip() {
         // テスト用にデータを削った partial_utf_ken_all.zip を利用
@@ -119,4 +406,60 @@ mod tests {
         let records = parse_utf_ken_all_csv(file).unwrap();
         insta::assert_debug_snapshot!(records);
     }
+
+    #[test]
+    fn test_parse_jigyosyo_zip() {
+        // テスト用にデータを削った partial_jigyosyo.zip を利用
+        let jigyosyo_zip: PathBuf = [
+            env!("CARGO_MANIFEST_DIR"),
+            "testdata",
+            "partial_jigyosyo.zip",
+        ]
+        .iter()
+        .collect();
+        let file = std::fs::File::open(jigyosyo_zip).unwrap();
+        let records = parse_jigyosyo_zip(file).unwrap();
+        insta::assert_debug_snapshot!(records);
+    }
+
+    #[test]
+    fn test_parse_jigyosyo_csv() {
+        // テスト用にデータを削った partial_jigyosyo.csv を利用
+        let jigyosyo_csv: PathBuf = [
+            env!("CARGO_MANIFEST_DIR"),
+            "testdata",
+            "partial_jigyosyo.csv",
+        ]
+        .iter()
+        .collect();
+        let file = std::fs::File::open(jigyosyo_csv).unwrap();
+        let records = parse_jigyosyo_csv(file).unwrap();
+        insta::assert_debug_snapshot!(records);
+    }
+
+    #[test]
+    fn test_parse_ken_all_rome_detects_zip_and_csv() {
+        let testdata: PathBuf = [env!("CARGO_MANIFEST_DIR"), "testdata"].iter().collect();
+        let zip = std::fs::File::open(testdata.join("partial_ken_all_rome.zip")).unwrap();
+        let csv = std::fs::File::open(testdata.join("partial_ken_all_rome.csv")).unwrap();
+        let from_zip = parse_ken_all_rome(zip).unwrap();
+        let from_csv = parse_ken_all_rome(csv).unwrap();
+        assert_eq!(from_zip.len(), 4);
+        assert_eq!(from_zip, from_csv);
+    }
+
+    #[test]
+    fn test_parse_ken_all_rome_csv() {
+        // テスト用にデータを削った partial_ken_all_rome.csv を利用
+        let ken_all_rome_csv: PathBuf = [
+            env!("CARGO_MANIFEST_DIR"),
+            "testdata",
+            "partial_ken_all_rome.csv",
+        ]
+        .iter()
+        .collect();
+        let file = std::fs::File::open(ken_all_rome_csv).unwrap();
+        let records = parse_ken_all_rome_csv(file).unwrap();
+        insta::assert_debug_snapshot!(records);
+    }
 }
@@ -0,0 +1,104 @@
+use jp_postal_code_core::model::{KenAllRomeRecord, UtfKenAllRecord};
+use jp_postal_code_core::normalize::{normalize_address_query, to_hepburn};
+use std::collections::HashMap;
+
+/// 郵便番号レコードに `KEN_ALL_ROME.CSV` のローマ字表記を補う
+///
+/// 郵便番号と町域名でレコードを突き合わせる。`KEN_ALL_ROME.CSV` は `utf_ken_all.csv` と空白や
+/// 波ダッシュなどの表記が異なることがあるため、町域名は [normalize_address_query] で揃えてから比較する。
+/// 突き合わせられなかったレコードは、仮名から [to_hepburn] で変換したローマ字を使う。
+///
+/// # Example
+/// ```rust
+/// # use jp_postal_code_util::{join_ken_all_rome, parse_ken_all_rome_csv, parse_utf_ken_all_csv};
+/// # fn main() {
+/// # let testdata: std::path::PathBuf = [env!("CARGO_MANIFEST_DIR"), "testdata"].iter().collect();
+/// let records = parse_utf_ken_all_csv(
+///     std::fs::File::open(testdata.join("partial_utf_ken_all.csv")).unwrap(),
+/// )
+/// .unwrap();
+/// let rome_records = parse_ken_all_rome_csv(
+///     std::fs::File::open(testdata.join("partial_ken_all_rome.csv")).unwrap(),
+/// )
+/// .unwrap();
+/// let records = join_ken_all_rome(records, &rome_records);
+/// assert_eq!(records[1].town_roman, "ASAHIGAOKA");
+/// # }
+/// ```
+#[tracing::instrument(skip_all)]
+pub fn join_ken_all_rome(
+    records: Vec<UtfKenAllRecord>,
+    rome_records: &[KenAllRomeRecord],
+) -> Vec<UtfKenAllRecord> {
+    let mut index = HashMap::with_capacity(rome_records.len());
+    for r in rome_records {
+        // 同じ郵便番号・町域名のレコードが複数あれば先に現れたものを使う
+        index
+            .entry((r.postal_code.as_str(), normalize_address_query(&r.town)))
+            .or_insert(r);
+    }
+    let mut unmatched = 0;
+    let records = records
+        .into_iter()
+        .map(|r| {
+            let key = (r.postal_code.as_str(), normalize_address_query(&r.town));
+            match index.get(&key) {
+                Some(rome) => UtfKenAllRecord {
+                    prefecture_roman: rome.prefecture_roman.clone(),
+                    city_roman: rome.city_roman.clone(),
+                    town_roman: rome.town_roman.clone(),
+                    ..r
+                },
+                None => {
+                    unmatched += 1;
+                    UtfKenAllRecord {
+                        prefecture_roman: to_hepburn(&r.prefecture_kana),
+                        city_roman: to_hepburn(&r.city_kana),
+                        town_roman: to_hepburn(&r.town_kana),
+                        ..r
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    tracing::info!(
+        count = records.len(),
+        unmatched,
+        "Finish joining `KEN_ALL_ROME.CSV` to postal code records"
+    );
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_ken_all_rome_csv, parse_utf_ken_all_csv};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_join_ken_all_rome() {
+        // partial_ken_all_rome.csv には 0640820 のレコードがなく、仮名から変換される
+        let testdata: PathBuf = [env!("CARGO_MANIFEST_DIR"), "testdata"].iter().collect();
+        let records = parse_utf_ken_all_csv(
+            std::fs::File::open(testdata.join("partial_utf_ken_all.csv")).unwrap(),
+        )
+        .unwrap();
+        let rome_records = parse_ken_all_rome_csv(
+            std::fs::File::open(testdata.join("partial_ken_all_rome.csv")).unwrap(),
+        )
+        .unwrap();
+        let records = join_ken_all_rome(records, &rome_records);
+        let romans = records
+            .iter()
+            .map(|r| {
+                (
+                    r.postal_code.as_str(),
+                    r.prefecture_roman.as_str(),
+                    r.city_roman.as_str(),
+                    r.town_roman.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        insta::assert_debug_snapshot!(romans);
+    }
+}
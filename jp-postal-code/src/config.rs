@@ -8,6 +8,32 @@ pub struct Config {
     /// gRPC サーバーのアドレス
     #[serde(default = "default_grpc_server_addr")]
     pub grpc_server_addr: String,
+    /// 起動時に読み込む `utf_ken_all.zip`（または CSV）のパス
+    ///
+    /// データベースを使わずにメモリ上で動かす（`DATABASE_URL` が `memory:`）場合に使う。
+    #[serde(default)]
+    pub postal_code_file: Option<String>,
+    /// 起動時に `postal_code_file` と合わせて読み込む `KEN_ALL_ROME.zip`（または CSV）のパス
+    ///
+    /// 省略した場合、住所のローマ字表記は仮名から変換する。
+    #[serde(default)]
+    pub ken_all_rome_file: Option<String>,
+    /// 起動時に読み込む `jigyosyo.zip`（または CSV）のパス
+    ///
+    /// データベースを使わずにメモリ上で動かす（`DATABASE_URL` が `memory:`）場合に使う。
+    #[serde(default)]
+    pub jigyosyo_file: Option<String>,
+    /// ページトークンの署名に使う鍵
+    ///
+    /// 省略した場合はプロセスごとに乱数で生成するので、再起動するとそれまでのページトークンは
+    /// 使えなくなる。複数のプロセスで同じデータベースを使う場合は同じ値を指定する。
+    #[serde(default)]
+    pub page_token_secret: Option<String>,
+    /// 使用中のものを含めて保持する郵便番号データベースのバージョンの数
+    ///
+    /// 取り込むたびに新しいバージョンを作り、これを超えた古いバージョンを削除する。
+    #[serde(default = "default_dataset_versions_to_keep")]
+    pub dataset_versions_to_keep: usize,
 }
 
 fn default_http_server_addr() -> String {
@@ -18,6 +44,10 @@ fn default_grpc_server_addr() -> String {
     "localhost:50051".to_string()
 }
 
+fn default_dataset_versions_to_keep() -> usize {
+    crate::repo::DEFAULT_DATASET_VERSIONS_TO_KEEP
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self::new()
@@ -1,24 +1,257 @@
+use jp_postal_code_core::model::{self, JigyosyoRecord, PostalCodeRecord, UtfKenAllRecord};
 use jp_postal_code_proto::{
-    postal_address_service_server::PostalAddressService, search_postal_address_response,
-    PostalAddress, SearchPostalAddressRequest, SearchPostalAddressResponse,
+    batch_get_postal_addresses_response, get_postal_address_response,
+    list_postal_address_history_response, postal_address_service_server::PostalAddressService,
+    search_postal_address_by_address_response, search_postal_address_by_kana_response,
+    search_postal_address_response, suggest_response, BatchGetPostalAddressesRequest,
+    BatchGetPostalAddressesResponse, Business, BusinessType, City, GetPostalAddressRequest,
+    GetPostalAddressResponse, ListCitiesRequest, ListCitiesResponse,
+    ListPostalAddressHistoryRequest, ListPostalAddressHistoryResponse, ListPrefecturesRequest,
+    ListPrefecturesResponse, ListTownsRequest, ListTownsResponse, ParseAddressRequest,
+    ParseAddressResponse, PostalAddress, PostalAddressChange, PostalAddressKind, Prefecture,
+    SearchPostalAddressByAddressRequest, SearchPostalAddressByAddressResponse,
+    SearchPostalAddressByKanaRequest, SearchPostalAddressByKanaResponse,
+    SearchPostalAddressRequest, SearchPostalAddressResponse, SuggestRequest, SuggestResponse, Town,
+    UpdateCode, UpdateReason, ValidatePostalAddressRequest, ValidatePostalAddressResponse,
 };
 use tonic::{Request, Response, Status};
 
-use crate::{infra, usecase};
+use crate::{
+    repo::{PostalCodeChange, PostalCodeHistory, SuggestField, UtfKenAllRepository},
+    usecase,
+};
 
 #[derive(Debug)]
-pub struct PostalAddressServiceImpl {
-    repo: infra::postgres::UtfKenAllRepositoryPostgres,
+pub struct PostalAddressServiceImpl<R> {
+    repo: R,
 }
 
-impl PostalAddressServiceImpl {
-    pub fn new(repo: infra::postgres::UtfKenAllRepositoryPostgres) -> Self {
+impl<R> PostalAddressServiceImpl<R>
+where
+    R: UtfKenAllRepository,
+{
+    pub fn new(repo: R) -> Self {
         Self { repo }
     }
 }
 
+/// メタデータ `x-dataset-version` に、検索した郵便番号データベースのバージョンを付けた応答を作る
+fn versioned_response<T>(message: T, dataset_version: Option<i64>) -> Response<T> {
+    let mut response = Response::new(message);
+    if let Some(version) = dataset_version {
+        response
+            .metadata_mut()
+            .insert("x-dataset-version", version.into());
+    }
+    response
+}
+
+impl From<usecase::Error> for Status {
+    fn from(err: usecase::Error) -> Self {
+        match err {
+            usecase::Error::InvalidArgument { .. } => Status::invalid_argument(err.to_string()),
+            usecase::Error::NotFound { .. } => Status::not_found(err.to_string()),
+            usecase::Error::Unexpected(_) => Status::internal("Something went wrong"),
+        }
+    }
+}
+
+/// gRPC の `page_size`（`int32`）を検証して変換する
+fn page_size(page_size: Option<i32>) -> Result<Option<usize>, usecase::Error> {
+    page_size
+        .map(|size| {
+            usize::try_from(size).map_err(|_| usecase::Error::InvalidArgument {
+                field: "page_size",
+                message: "page size must not be negative".to_string(),
+            })
+        })
+        .transpose()
+}
+
+/// gRPC の `limit`（`int32`）を検証して変換する
+fn limit(limit: Option<i32>) -> Result<Option<usize>, usecase::Error> {
+    limit
+        .map(|limit| {
+            usize::try_from(limit).map_err(|_| usecase::Error::InvalidArgument {
+                field: "limit",
+                message: "limit must not be negative".to_string(),
+            })
+        })
+        .transpose()
+}
+
+fn suggest_field(field: SuggestField) -> jp_postal_code_proto::SuggestField {
+    match field {
+        SuggestField::PostalCode => jp_postal_code_proto::SuggestField::PostalCode,
+        SuggestField::Address => jp_postal_code_proto::SuggestField::Address,
+        SuggestField::Kana => jp_postal_code_proto::SuggestField::Kana,
+    }
+}
+
+fn address_field(field: usecase::AddressField) -> jp_postal_code_proto::AddressField {
+    match field {
+        usecase::AddressField::PostalCode => jp_postal_code_proto::AddressField::PostalCode,
+        usecase::AddressField::Prefecture => jp_postal_code_proto::AddressField::Prefecture,
+        usecase::AddressField::City => jp_postal_code_proto::AddressField::City,
+        usecase::AddressField::Town => jp_postal_code_proto::AddressField::Town,
+    }
+}
+
+fn validation_result(result: usecase::ValidationResult) -> jp_postal_code_proto::ValidationResult {
+    match result {
+        usecase::ValidationResult::Valid => jp_postal_code_proto::ValidationResult::Valid,
+        usecase::ValidationResult::Partial => jp_postal_code_proto::ValidationResult::Partial,
+        usecase::ValidationResult::Invalid => jp_postal_code_proto::ValidationResult::Invalid,
+    }
+}
+
+fn update_code(code: model::UpdateCode) -> UpdateCode {
+    match code {
+        model::UpdateCode::Unchanged => UpdateCode::Unchanged,
+        model::UpdateCode::Changed => UpdateCode::Changed,
+        model::UpdateCode::Abolished => UpdateCode::Abolished,
+    }
+}
+
+fn update_reason(reason: model::UpdateReason) -> UpdateReason {
+    match reason {
+        model::UpdateReason::Unchanged => UpdateReason::Unchanged,
+        model::UpdateReason::MunicipalityEnforcement => UpdateReason::MunicipalityEnforcement,
+        model::UpdateReason::ResidenceIndication => UpdateReason::ResidenceIndication,
+        model::UpdateReason::LandReadjustment => UpdateReason::LandReadjustment,
+        model::UpdateReason::PostalDistrictAdjustment => UpdateReason::PostalDistrictAdjustment,
+        model::UpdateReason::Correction => UpdateReason::Correction,
+        model::UpdateReason::Abolished => UpdateReason::Abolished,
+    }
+}
+
+fn postal_address(r: UtfKenAllRecord) -> PostalAddress {
+    PostalAddress {
+        postal_code: r.postal_code,
+        prefecture: r.prefecture,
+        city: r.city,
+        town: r.town,
+        prefecture_kana: r.prefecture_kana,
+        city_kana: r.city_kana,
+        town_kana: r.town_kana,
+        prefecture_roman: r.prefecture_roman,
+        city_roman: r.city_roman,
+        town_roman: r.town_roman,
+        kind: PostalAddressKind::Address.into(),
+        business: None,
+        local_government_code: r.local_government_code,
+        old_postal_code: r.old_postal_code,
+        has_multi_postal_code: r.has_multi_postal_code == 1,
+        has_chome: r.has_chome == 1,
+        has_multi_town: r.has_multi_town == 1,
+        update_code: update_code(r.update_code).into(),
+        update_reason: update_reason(r.update_reason).into(),
+    }
+}
+
+fn business_postal_address(r: JigyosyoRecord) -> PostalAddress {
+    let business_type = match r.business_type {
+        0 => BusinessType::Office,
+        1 => BusinessType::PoBox,
+        _ => BusinessType::Unspecified,
+    };
+    // 事業所の修正コードは 0: 修正なし、1: 新規追加、5: 廃止
+    let update_code = match r.update_code {
+        0 => UpdateCode::Unchanged,
+        1 => UpdateCode::Changed,
+        5 => UpdateCode::Abolished,
+        _ => UpdateCode::Unspecified,
+    };
+    PostalAddress {
+        postal_code: r.postal_code,
+        prefecture: r.prefecture,
+        city: r.city,
+        town: r.town,
+        prefecture_kana: "".to_string(),
+        city_kana: "".to_string(),
+        town_kana: "".to_string(),
+        prefecture_roman: "".to_string(),
+        city_roman: "".to_string(),
+        town_roman: "".to_string(),
+        kind: PostalAddressKind::Business.into(),
+        business: Some(Business {
+            name: r.business_name,
+            name_kana: r.business_name_kana,
+            block_address: r.block_address,
+            handling_office: r.handling_office,
+            business_type: business_type.into(),
+        }),
+        local_government_code: r.local_government_code,
+        old_postal_code: r.old_postal_code,
+        has_multi_postal_code: false,
+        has_chome: false,
+        has_multi_town: false,
+        update_code: update_code.into(),
+        update_reason: UpdateReason::Unspecified.into(),
+    }
+}
+
+fn prefecture(p: model::Prefecture) -> Prefecture {
+    Prefecture {
+        code: p.code,
+        name: p.name,
+        name_kana: p.name_kana,
+    }
+}
+
+fn city(c: model::City) -> City {
+    City {
+        local_government_code: c.local_government_code,
+        name: c.name,
+        name_kana: c.name_kana,
+    }
+}
+
+fn town(t: model::Town) -> Town {
+    Town {
+        local_government_code: t.local_government_code,
+        postal_code: t.postal_code,
+        name: t.name,
+        name_kana: t.name_kana,
+    }
+}
+
+fn postal_code_history(h: PostalCodeHistory) -> list_postal_address_history_response::Item {
+    let change = match h.change {
+        PostalCodeChange::Added => PostalAddressChange::Added,
+        PostalCodeChange::Removed => PostalAddressChange::Removed,
+        PostalCodeChange::Changed => PostalAddressChange::Changed,
+    };
+    list_postal_address_history_response::Item {
+        change: change.into(),
+        fields: h
+            .fields
+            .into_iter()
+            .map(|f| list_postal_address_history_response::FieldChange {
+                field: f.field,
+                old_value: f.old,
+                new_value: f.new,
+            })
+            .collect(),
+        update_code: update_code(h.update_code).into(),
+        update_reason: update_reason(h.update_reason).into(),
+        dataset_version: h.dataset_id,
+        imported_at: h.imported_at.to_rfc3339(),
+    }
+}
+
+fn postal_code_record(r: PostalCodeRecord) -> PostalAddress {
+    match r {
+        PostalCodeRecord::Address(r) => postal_address(r),
+        PostalCodeRecord::Business(r) => business_postal_address(r),
+    }
+}
+
 #[tonic::async_trait]
-impl PostalAddressService for PostalAddressServiceImpl {
+impl<R> PostalAddressService for PostalAddressServiceImpl<R>
+where
+    R: UtfKenAllRepository + 'static,
+{
     async fn search_postal_address(
         &self,
         request: Request<SearchPostalAddressRequest>,
@@ -31,35 +264,382 @@ impl PostalAddressService for PostalAddressServiceImpl {
             &self.repo,
             usecase::SearchPostalCodeRequest {
                 postal_code: req.postal_code,
-                page_size: req.page_size.map(|size| size as usize),
+                page_size: page_size(req.page_size)?,
                 page_token: req.page_token,
             },
         )
         .await
         .map_err(|e| {
             tracing::error!(?e, "Failed to search postal address via gRPC");
-            Status::internal("Failed to search postal address")
+            Status::from(e)
         })?;
 
         let items = response
             .records
             .into_iter()
             .map(|r| search_postal_address_response::Item {
-                address: Some(PostalAddress {
-                    postal_code: r.postal_code,
-                    prefecture: r.prefecture,
-                    city: r.city,
-                    town: r.town,
-                    prefecture_kana: r.prefecture_kana,
-                    city_kana: r.city_kana,
-                    town_kana: r.town_kana,
-                }),
+                address: Some(postal_code_record(r)),
             })
             .collect();
 
-        Ok(Response::new(SearchPostalAddressResponse {
-            items,
-            next_page_token: response.next_page_token,
+        Ok(versioned_response(
+            SearchPostalAddressResponse {
+                items,
+                next_page_token: response.next_page_token,
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn search_postal_address_by_address(
+        &self,
+        request: Request<SearchPostalAddressByAddressRequest>,
+    ) -> Result<Response<SearchPostalAddressByAddressResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(
+            ?req,
+            "Received gRPC search postal address by address request"
+        );
+
+        let response = usecase::search_postal_code_by_address(
+            &self.repo,
+            usecase::SearchPostalCodeByAddressRequest {
+                address: req.address,
+                page_size: page_size(req.page_size)?,
+                did_you_mean: req.did_you_mean,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to search postal address by address via gRPC");
+            Status::from(e)
+        })?;
+
+        let item = |r| search_postal_address_by_address_response::Item {
+            address: Some(postal_address(r)),
+        };
+        let items = response.records.into_iter().map(item).collect();
+        let did_you_mean = response.did_you_mean.into_iter().map(item).collect();
+
+        Ok(versioned_response(
+            SearchPostalAddressByAddressResponse {
+                items,
+                did_you_mean,
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn search_postal_address_by_kana(
+        &self,
+        request: Request<SearchPostalAddressByKanaRequest>,
+    ) -> Result<Response<SearchPostalAddressByKanaResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC search postal address by kana request");
+
+        let response = usecase::search_postal_code_by_kana(
+            &self.repo,
+            usecase::SearchPostalCodeByKanaRequest {
+                kana: req.kana,
+                page_size: page_size(req.page_size)?,
+                page_token: req.page_token,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to search postal address by kana via gRPC");
+            Status::from(e)
+        })?;
+
+        let items = response
+            .records
+            .into_iter()
+            .map(|r| search_postal_address_by_kana_response::Item {
+                address: Some(postal_address(r)),
+            })
+            .collect();
+
+        Ok(versioned_response(
+            SearchPostalAddressByKanaResponse {
+                items,
+                next_page_token: response.next_page_token,
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn suggest(
+        &self,
+        request: Request<SuggestRequest>,
+    ) -> Result<Response<SuggestResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC suggest request");
+
+        let response = usecase::suggest(
+            &self.repo,
+            usecase::SuggestRequest {
+                query: req.query,
+                limit: limit(req.limit)?,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to suggest via gRPC");
+            Status::from(e)
+        })?;
+
+        let items = response
+            .suggestions
+            .into_iter()
+            .map(|s| suggest_response::Item {
+                field: suggest_field(s.field).into(),
+                text: s.text,
+                // 候補の文字列は短いので `int32` に収まる
+                highlight_start: s.highlight.start as i32,
+                highlight_end: s.highlight.end as i32,
+                address: Some(postal_address(s.record)),
+            })
+            .collect();
+
+        Ok(versioned_response(
+            SuggestResponse { items },
+            response.dataset_version,
+        ))
+    }
+
+    async fn parse_address(
+        &self,
+        request: Request<ParseAddressRequest>,
+    ) -> Result<Response<ParseAddressResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC parse address request");
+
+        let parsed = usecase::parse_address(&self.repo, &req.address)
+            .await
+            .map_err(|e| {
+                tracing::error!(?e, "Failed to parse address via gRPC");
+                Status::from(e)
+            })?;
+
+        Ok(versioned_response(
+            ParseAddressResponse {
+                prefecture: parsed.address.prefecture,
+                city: parsed.address.city,
+                local_government_code: parsed.address.local_government_code,
+                town: parsed.address.town,
+                postal_code: parsed.address.postal_code,
+                chome: parsed.address.chome,
+                banchi: parsed.address.banchi,
+                building: parsed.address.building,
+            },
+            parsed.dataset_version,
+        ))
+    }
+
+    async fn validate_postal_address(
+        &self,
+        request: Request<ValidatePostalAddressRequest>,
+    ) -> Result<Response<ValidatePostalAddressResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC validate postal address request");
+
+        let response = usecase::validate_postal_address(
+            &self.repo,
+            usecase::ValidatePostalAddressRequest {
+                postal_code: req.postal_code,
+                prefecture: req.prefecture,
+                city: req.city,
+                town: req.town,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to validate postal address via gRPC");
+            Status::from(e)
+        })?;
+
+        Ok(versioned_response(
+            ValidatePostalAddressResponse {
+                result: validation_result(response.result).into(),
+                mismatched_fields: response
+                    .mismatched_fields
+                    .into_iter()
+                    .map(|f| address_field(f).into())
+                    .collect(),
+                suggestions: response
+                    .suggestions
+                    .into_iter()
+                    .map(postal_code_record)
+                    .collect(),
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn get_postal_address(
+        &self,
+        request: Request<GetPostalAddressRequest>,
+    ) -> Result<Response<GetPostalAddressResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC get postal address request");
+
+        let response = usecase::get_postal_code(
+            &self.repo,
+            usecase::GetPostalCodeRequest {
+                postal_code: req.postal_code,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to get postal address via gRPC");
+            Status::from(e)
+        })?;
+
+        let items = response
+            .records
+            .into_iter()
+            .map(|r| get_postal_address_response::Item {
+                address: Some(postal_code_record(r)),
+            })
+            .collect();
+
+        Ok(versioned_response(
+            GetPostalAddressResponse { items },
+            response.dataset_version,
+        ))
+    }
+
+    async fn batch_get_postal_addresses(
+        &self,
+        request: Request<BatchGetPostalAddressesRequest>,
+    ) -> Result<Response<BatchGetPostalAddressesResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(
+            count = req.postal_codes.len(),
+            "Received gRPC batch get postal addresses request"
+        );
+
+        let response = usecase::batch_get_postal_codes(
+            &self.repo,
+            usecase::BatchGetPostalCodesRequest {
+                postal_codes: req.postal_codes,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "Failed to batch get postal addresses via gRPC");
+            Status::from(e)
+        })?;
+
+        let results = response
+            .results
+            .into_iter()
+            .map(|r| {
+                let item = batch_get_postal_addresses_response::Item {
+                    addresses: r.records.into_iter().map(postal_code_record).collect(),
+                };
+                (r.postal_code, item)
+            })
+            .collect();
+
+        Ok(versioned_response(
+            BatchGetPostalAddressesResponse {
+                results,
+                not_found: response.not_found,
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn list_postal_address_history(
+        &self,
+        request: Request<ListPostalAddressHistoryRequest>,
+    ) -> Result<Response<ListPostalAddressHistoryResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC list postal address history request");
+
+        let history = usecase::list_postal_code_history(&self.repo, &req.postal_code)
+            .await
+            .map_err(|e| {
+                tracing::error!(?e, "Failed to list postal address history via gRPC");
+                Status::from(e)
+            })?;
+
+        Ok(Response::new(ListPostalAddressHistoryResponse {
+            items: history.into_iter().map(postal_code_history).collect(),
         }))
     }
+
+    async fn list_prefectures(
+        &self,
+        _request: Request<ListPrefecturesRequest>,
+    ) -> Result<Response<ListPrefecturesResponse>, Status> {
+        tracing::info!("Received gRPC list prefectures request");
+
+        let response = usecase::list_prefectures(&self.repo).await.map_err(|e| {
+            tracing::error!(?e, "Failed to list prefectures via gRPC");
+            Status::from(e)
+        })?;
+
+        Ok(versioned_response(
+            ListPrefecturesResponse {
+                prefectures: response.prefectures.into_iter().map(prefecture).collect(),
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn list_cities(
+        &self,
+        request: Request<ListCitiesRequest>,
+    ) -> Result<Response<ListCitiesResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC list cities request");
+
+        let response = usecase::list_cities(&self.repo, &req.prefecture_code)
+            .await
+            .map_err(|e| {
+                tracing::error!(?e, "Failed to list cities via gRPC");
+                Status::from(e)
+            })?;
+
+        Ok(versioned_response(
+            ListCitiesResponse {
+                cities: response.cities.into_iter().map(city).collect(),
+            },
+            response.dataset_version,
+        ))
+    }
+
+    async fn list_towns(
+        &self,
+        request: Request<ListTownsRequest>,
+    ) -> Result<Response<ListTownsResponse>, Status> {
+        let req = request.into_inner();
+
+        tracing::info!(?req, "Received gRPC list towns request");
+
+        let response = usecase::list_towns(&self.repo, &req.local_government_code)
+            .await
+            .map_err(|e| {
+                tracing::error!(?e, "Failed to list towns via gRPC");
+                Status::from(e)
+            })?;
+
+        Ok(versioned_response(
+            ListTownsResponse {
+                towns: response.towns.into_iter().map(town).collect(),
+            },
+            response.dataset_version,
+        ))
+    }
 }
@@ -1,2 +1,7 @@
+mod dataset;
 pub mod ephemeral;
+mod history;
+mod page_token;
 pub mod postgres;
+mod row;
+pub mod sqlite;
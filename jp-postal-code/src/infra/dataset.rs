@@ -0,0 +1,411 @@
+use super::history::{diff_records, RecordChange};
+use crate::repo::UtfKenAllRepositoryError;
+use jp_postal_code_core::model::{JigyosyoRecord, UtfKenAllRecord};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+
+/// 郵便番号データベースのバージョンに含まれるレコード数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DatasetCounts {
+    pub(crate) utf_ken_all: i64,
+    pub(crate) jigyosyo: i64,
+}
+
+/// 新しいバージョンを使用中にしてよいか検証する
+///
+/// 取り込めたレコード数 `actual` が取り込もうとしたレコード数 `expected` と一致しない場合や、
+/// 使用中のバージョン `active` で空でなかったテーブルが空になる場合（途中で切れたファイルを
+/// 取り込んだなど）は [`UtfKenAllRepositoryError::InvalidDataset`] を返す。
+pub(crate) fn validate_dataset(
+    active: Option<DatasetCounts>,
+    expected: DatasetCounts,
+    actual: DatasetCounts,
+) -> Result<(), UtfKenAllRepositoryError> {
+    if actual != expected {
+        return Err(UtfKenAllRepositoryError::InvalidDataset(format!(
+            "expected {} utf_ken_all and {} jigyosyo records, but found {} and {}",
+            expected.utf_ken_all, expected.jigyosyo, actual.utf_ken_all, actual.jigyosyo
+        )));
+    }
+    let Some(active) = active else {
+        return Ok(());
+    };
+    for (table, before, after) in [
+        ("utf_ken_all", active.utf_ken_all, actual.utf_ken_all),
+        ("jigyosyo", active.jigyosyo, actual.jigyosyo),
+    ] {
+        if before > 0 && after == 0 {
+            return Err(UtfKenAllRepositoryError::InvalidDataset(format!(
+                "{table} would become empty (currently {before} records)"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 置き換える前のレコード `current`（ID と内容）と置き換えた後のレコード `records` を内容で対応づける
+///
+/// `records` のレコードごとに、内容が同じレコードが `current` にあればその ID（同じ内容が複数あれば
+/// ID の小さい順に割り当てる）、なければ `None` を返す。どれにも対応づかなかった `current` の ID も
+/// 返す。変わらなかったレコードの ID を置き換えた後も使い続けるために使う。
+pub(crate) fn match_records<'a, T: Ord + 'a>(
+    current: impl IntoIterator<Item = (i64, &'a T)>,
+    records: &[T],
+) -> (Vec<Option<i64>>, Vec<i64>) {
+    let mut ids = BTreeMap::<&T, VecDeque<i64>>::new();
+    let mut current = current.into_iter().collect::<Vec<_>>();
+    current.sort_by_key(|(id, _)| *id);
+    for (id, record) in current {
+        ids.entry(record).or_default().push_back(id);
+    }
+    let matched = records
+        .iter()
+        .map(|r| ids.get_mut(r).and_then(|ids| ids.pop_front()))
+        .collect();
+    let mut unmatched = ids.into_values().flatten().collect::<Vec<_>>();
+    unmatched.sort();
+    (matched, unmatched)
+}
+
+/// バージョンごとに郵便番号データベースを保持するデータベースの操作
+///
+/// レコードはそれが含まれるバージョンの範囲 `[valid_from, valid_to)` を持ち、変わらないレコードは
+/// バージョン間で共有する。新しいバージョンでは追加したレコードを挿入し、削除したレコードの
+/// `valid_to` を新しいバージョンの ID にする（以下、レコードを「外す」）。
+///
+/// PostgreSQL と SQLite で SQL だけが異なる操作をまとめ、更新の手順（[`apply_diff`]・
+/// [`replace_dataset`]）はこれを使って共通に実装する。トランザクションの中で呼ぶ。
+pub(crate) trait DatasetStore: Send {
+    /// 使用中のバージョンの ID を取得する
+    fn active_dataset_id(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<i64>, sqlx::Error>> + Send;
+
+    /// 保持している最も古いバージョンの ID を取得する
+    fn oldest_dataset_id(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<i64>, sqlx::Error>> + Send;
+
+    /// 新しいバージョンを作る（レコード数は使用中にするときに記録する）
+    fn create_dataset(&mut self) -> impl Future<Output = Result<i64, sqlx::Error>> + Send;
+
+    /// 新しいバージョン `dataset_id` の内容をバージョン `base` と同じにする
+    ///
+    /// `base` より後のバージョンで追加したレコードを外し、`base` より後のバージョンで外した
+    /// レコードを新しいバージョンに複製する。`base` が最も新しいバージョンなら何もしない。
+    fn rebase(
+        &mut self,
+        base: Option<i64>,
+        dataset_id: i64,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` に含まれる住所の郵便番号を ID と一緒に取得する
+    fn list_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+    ) -> impl Future<Output = Result<Vec<(i64, UtfKenAllRecord)>, sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` に含まれる事業所の個別郵便番号を ID と一緒に取得する
+    fn list_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+    ) -> impl Future<Output = Result<Vec<(i64, JigyosyoRecord)>, sqlx::Error>> + Send;
+
+    /// 新しいバージョン `dataset_id` から、ID が `ids` の住所の郵便番号を外す
+    fn retire_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        ids: &[i64],
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// 新しいバージョン `dataset_id` から、ID が `ids` の事業所の個別郵便番号を外す
+    fn retire_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+        ids: &[i64],
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// 新しいバージョン `dataset_id` から `records` と一致する住所の郵便番号を外し、外した数を返す
+    ///
+    /// 全国地方公共団体コード・郵便番号・都道府県名・市区町村名・町域名で一致させる。
+    fn retire_matching_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        records: &[UtfKenAllRecord],
+    ) -> impl Future<Output = Result<u64, sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` に住所の郵便番号を挿入する
+    fn insert_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        records: &[UtfKenAllRecord],
+        updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` に事業所の個別郵便番号を挿入する
+    fn insert_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+        records: &[JigyosyoRecord],
+        updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` に記録したレコード数を取得する
+    fn recorded_counts(
+        &mut self,
+        dataset_id: i64,
+    ) -> impl Future<Output = Result<DatasetCounts, sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` に含まれるレコードを数える
+    fn count_records(
+        &mut self,
+        dataset_id: i64,
+    ) -> impl Future<Output = Result<DatasetCounts, sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` にレコード数を記録して使用中にする
+    fn activate_dataset(
+        &mut self,
+        dataset_id: i64,
+        counts: DatasetCounts,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// 新しい順に数えて `versions_to_keep` を超えた古いバージョンを削除し、その ID を返す
+    ///
+    /// 残ったどのバージョンにも含まれないレコードも削除する。
+    fn delete_expired_datasets(
+        &mut self,
+        versions_to_keep: usize,
+    ) -> impl Future<Output = Result<Vec<i64>, sqlx::Error>> + Send;
+
+    /// 新しいバージョン `dataset_id` で追加した・外した住所の郵便番号の郵便番号を求める
+    fn changed_postal_codes(
+        &mut self,
+        dataset_id: i64,
+    ) -> impl Future<Output = Result<Vec<String>, sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` の住所の郵便番号のうち、郵便番号が `postal_codes` に含まれるものを
+    /// ID の順に取得する
+    fn find_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        postal_codes: &[String],
+    ) -> impl Future<Output = Result<Vec<UtfKenAllRecord>, sqlx::Error>> + Send;
+
+    /// バージョン `dataset_id` で取り込んだ変更を変更履歴に挿入する
+    fn insert_history(
+        &mut self,
+        dataset_id: i64,
+        changes: &[RecordChange],
+        imported_at: chrono::DateTime<chrono::Utc>,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+}
+
+/// 使用中のバージョンに差分を適用した新しいバージョンを作って使用中にし、その ID を返す
+///
+/// 削除・追加するレコードだけを外す・挿入し、それ以外のレコードは ID を変えずに引き継ぐ。
+pub(crate) async fn apply_diff<S: DatasetStore>(
+    store: &mut S,
+    added: &[UtfKenAllRecord],
+    deleted: &[UtfKenAllRecord],
+    versions_to_keep: usize,
+) -> Result<i64, UtfKenAllRepositoryError> {
+    let updated_at = chrono::Utc::now();
+    let (active, dataset_id) = begin_dataset(store).await?;
+    // まず削除対象のレコードを外してから（変更されたレコードは削除と追加の両方に含まれる）、
+    // 追加対象のレコードを挿入する。事業所は使用中のバージョンから引き継ぐ
+    let retired = store
+        .retire_matching_utf_ken_all(dataset_id, deleted)
+        .await?;
+    tracing::info!(
+        count = retired,
+        "Retired deleted records in utf_ken_all table"
+    );
+    store
+        .insert_utf_ken_all(dataset_id, added, updated_at)
+        .await?;
+    let base = active.map(|(_, counts)| counts).unwrap_or_default();
+    let expected = DatasetCounts {
+        utf_ken_all: base.utf_ken_all - retired as i64 + added.len() as i64,
+        jigyosyo: base.jigyosyo,
+    };
+    // 公開時に古いバージョンが削除されるので、その前に記録する
+    let changes = record_history(store, active, dataset_id, updated_at).await?;
+    tracing::info!(changes, "Recorded postal code history");
+    publish_dataset(store, active, dataset_id, expected, versions_to_keep).await?;
+    Ok(dataset_id)
+}
+
+/// 住所の郵便番号・事業所の個別郵便番号を置き換えた新しいバージョンを作って使用中にし、その ID を
+/// 返す
+///
+/// `None` を指定したものは使用中のバージョンから引き継ぐ。置き換えるものも、使用中のバージョンと
+/// 内容が同じレコードは ID を変えずに引き継ぐ。
+pub(crate) async fn replace_dataset<S: DatasetStore>(
+    store: &mut S,
+    records: Option<&[UtfKenAllRecord]>,
+    jigyosyo_records: Option<&[JigyosyoRecord]>,
+    versions_to_keep: usize,
+) -> Result<i64, UtfKenAllRepositoryError> {
+    let updated_at = chrono::Utc::now();
+    let (active, dataset_id) = begin_dataset(store).await?;
+    let mut expected = active.map(|(_, counts)| counts).unwrap_or_default();
+    if let Some(records) = records {
+        let current = store.list_utf_ken_all(dataset_id).await?;
+        let (ids, retired) = match_records(current.iter().map(|(id, r)| (*id, r)), records);
+        let added = unmatched(records, &ids);
+        store.retire_utf_ken_all(dataset_id, &retired).await?;
+        store
+            .insert_utf_ken_all(dataset_id, &added, updated_at)
+            .await?;
+        tracing::info!(
+            retired = retired.len(),
+            added = added.len(),
+            "Replaced records in utf_ken_all table"
+        );
+        expected.utf_ken_all = records.len() as i64;
+    }
+    if let Some(records) = jigyosyo_records {
+        let current = store.list_jigyosyo(dataset_id).await?;
+        let (ids, retired) = match_records(current.iter().map(|(id, r)| (*id, r)), records);
+        let added = unmatched(records, &ids);
+        store.retire_jigyosyo(dataset_id, &retired).await?;
+        store
+            .insert_jigyosyo(dataset_id, &added, updated_at)
+            .await?;
+        tracing::info!(
+            retired = retired.len(),
+            added = added.len(),
+            "Replaced records in jigyosyo table"
+        );
+        expected.jigyosyo = records.len() as i64;
+    }
+    if records.is_some() {
+        // 公開時に古いバージョンが削除されるので、その前に記録する
+        let changes = record_history(store, active, dataset_id, updated_at).await?;
+        tracing::info!(changes, "Recorded postal code history");
+    }
+    publish_dataset(store, active, dataset_id, expected, versions_to_keep).await?;
+    Ok(dataset_id)
+}
+
+/// [`match_records`] で対応づかなかったレコードを元の順に集める
+fn unmatched<T: Clone>(records: &[T], ids: &[Option<i64>]) -> Vec<T> {
+    records
+        .iter()
+        .zip(ids)
+        .filter(|(_, id)| id.is_none())
+        .map(|(r, _)| r.clone())
+        .collect()
+}
+
+/// 新しいバージョンを作って使用中のバージョンと同じ内容にする
+///
+/// 使用中のバージョンの ID と記録したレコード数、新しいバージョンの ID を返す。
+async fn begin_dataset<S: DatasetStore>(
+    store: &mut S,
+) -> Result<(Option<(i64, DatasetCounts)>, i64), sqlx::Error> {
+    let active = match store.active_dataset_id().await? {
+        Some(active) => Some((active, store.recorded_counts(active).await?)),
+        None => None,
+    };
+    let dataset_id = store.create_dataset().await?;
+    store
+        .rebase(active.map(|(active, _)| active), dataset_id)
+        .await?;
+    Ok((active, dataset_id))
+}
+
+/// 使用中のバージョン `active` から新しいバージョン `dataset_id` で変わった住所の郵便番号を
+/// 変更履歴に記録する
+///
+/// 新しいバージョンで追加した・外したレコードの郵便番号だけを両方のバージョンから読み込んで比較する。
+/// 最初の取り込みでは全てが追加になるので記録しない。
+async fn record_history<S: DatasetStore>(
+    store: &mut S,
+    active: Option<(i64, DatasetCounts)>,
+    dataset_id: i64,
+    imported_at: chrono::DateTime<chrono::Utc>,
+) -> Result<usize, sqlx::Error> {
+    let Some((active, _)) = active else {
+        return Ok(0);
+    };
+    let postal_codes = store.changed_postal_codes(dataset_id).await?;
+    if postal_codes.is_empty() {
+        return Ok(0);
+    }
+    let old = store.find_utf_ken_all(active, &postal_codes).await?;
+    let new = store.find_utf_ken_all(dataset_id, &postal_codes).await?;
+    let changes = diff_records(&old, &new);
+    store
+        .insert_history(dataset_id, &changes, imported_at)
+        .await?;
+    Ok(changes.len())
+}
+
+/// 新しいバージョン `dataset_id` を検証して使用中にし、保持する数を超えた古いバージョンを削除する
+async fn publish_dataset<S: DatasetStore>(
+    store: &mut S,
+    active: Option<(i64, DatasetCounts)>,
+    dataset_id: i64,
+    expected: DatasetCounts,
+    versions_to_keep: usize,
+) -> Result<(), UtfKenAllRepositoryError> {
+    let actual = store.count_records(dataset_id).await?;
+    validate_dataset(active.map(|(_, counts)| counts), expected, actual)?;
+    store.activate_dataset(dataset_id, actual).await?;
+    // 新しいバージョンが最も新しいので、新しい順に数えて保持する数を超えたものを削除する
+    let expired = store
+        .delete_expired_datasets(versions_to_keep.max(1))
+        .await?;
+    if !expired.is_empty() {
+        tracing::info!(?expired, "Deleted expired dataset versions");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_records() {
+        // 同じ内容のレコードには ID の小さい順に割り当て、対応づかなかった ID も返す
+        let current = [(3, "a"), (1, "a"), (2, "b"), (4, "c")];
+        let (ids, unmatched) = match_records(
+            current.iter().map(|(id, r)| (*id, r)),
+            &["a", "d", "c", "a", "a"],
+        );
+        assert_eq!(ids, vec![Some(1), None, Some(4), Some(3), None]);
+        assert_eq!(unmatched, vec![2]);
+
+        let (ids, unmatched) = match_records(std::iter::empty(), &["a"]);
+        assert_eq!(ids, vec![None]);
+        assert!(unmatched.is_empty());
+    }
+
+    #[test]
+    fn test_validate_dataset() {
+        let counts = |utf_ken_all, jigyosyo| DatasetCounts {
+            utf_ken_all,
+            jigyosyo,
+        };
+        assert!(validate_dataset(None, counts(0, 0), counts(0, 0)).is_ok());
+        assert!(validate_dataset(Some(counts(10, 2)), counts(8, 2), counts(8, 2)).is_ok());
+        assert!(validate_dataset(Some(counts(0, 0)), counts(8, 0), counts(8, 0)).is_ok());
+
+        // 取り込めたレコード数が一致しない・空でなかったテーブルが空になる
+        for (active, expected, actual) in [
+            (None, counts(8, 2), counts(7, 2)),
+            (Some(counts(10, 2)), counts(0, 2), counts(0, 2)),
+            (Some(counts(10, 2)), counts(10, 0), counts(10, 0)),
+        ] {
+            assert!(
+                matches!(
+                    validate_dataset(active, expected, actual),
+                    Err(UtfKenAllRepositoryError::InvalidDataset(_))
+                ),
+                "{active:?} {expected:?} {actual:?}"
+            );
+        }
+    }
+}
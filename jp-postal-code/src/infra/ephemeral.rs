@@ -1,37 +1,494 @@
+use super::dataset::{match_records, validate_dataset, DatasetCounts};
+use super::history::diff_records;
+use super::page_token::{PageTokenSigner, SortKey};
 use crate::repo::{
-    UtfKenAllRepository, UtfKenAllRepositorySearchRequest, UtfKenAllRepositorySearchResponse,
-    DEFAULT_SEARCH_PAGE_SIZE,
+    DatasetVersion, PostalCodeHistory, SuggestField, UtfKenAllRepository, UtfKenAllRepositoryError,
+    UtfKenAllRepositoryGetResponse, UtfKenAllRepositoryListCitiesResponse,
+    UtfKenAllRepositoryListPrefecturesResponse, UtfKenAllRepositoryListTownsResponse,
+    UtfKenAllRepositorySearchByAddressRequest, UtfKenAllRepositorySearchByAddressResponse,
+    UtfKenAllRepositorySearchByKanaRequest, UtfKenAllRepositorySearchByKanaResponse,
+    UtfKenAllRepositorySearchRequest, UtfKenAllRepositorySearchResponse,
+    UtfKenAllRepositorySuggestRequest, UtfKenAllRepositorySuggestResponse,
+    DEFAULT_DATASET_VERSIONS_TO_KEEP, DEFAULT_SEARCH_PAGE_SIZE,
 };
-use jp_postal_code_core::model::UtfKenAllRecord;
+use arc_swap::ArcSwap;
+use jp_postal_code_core::model::{
+    City, JigyosyoRecord, PostalCodeRecord, Prefecture, Town, UtfKenAllRecord,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Default, Clone)]
+/// メモリ上に郵便番号データベースを保持するリポジトリ
+///
+/// データベースを用意せずに本番環境で使えるように、検索は読み取り専用のスナップショットに対して
+/// ロックを取らずに行う。更新時は新しいスナップショットを構築してから差し替える。
+/// 保持するバージョンの数だけスナップショットをメモリ上に残す。
+#[derive(Debug, Clone)]
 pub struct UtfKenAllRepositoryEphemeral {
-    records: Arc<Mutex<Vec<UtfKenAllRecord>>>,
+    /// 使用中のバージョンのスナップショット
+    snapshot: Arc<ArcSwap<Snapshot>>,
+    /// 保持しているバージョンのスナップショット（古い順、使用中のものを含む）
+    ///
+    /// 更新・切り替えはこのロックを取って直列に行う。
+    versions: Arc<Mutex<Vec<Arc<Snapshot>>>>,
+    /// 保持している最も古いバージョンの ID（ページトークンが古くなったかの判定に使う）
+    oldest_version: Arc<AtomicI64>,
+    /// 住所の郵便番号の変更履歴（記録した順）
+    history: Arc<Mutex<Vec<PostalCodeHistory>>>,
+    versions_to_keep: usize,
+    signer: PageTokenSigner,
+}
+
+impl Default for UtfKenAllRepositoryEphemeral {
+    fn default() -> Self {
+        Self {
+            snapshot: Default::default(),
+            versions: Default::default(),
+            oldest_version: Default::default(),
+            history: Default::default(),
+            versions_to_keep: DEFAULT_DATASET_VERSIONS_TO_KEEP,
+            signer: PageTokenSigner::default(),
+        }
+    }
+}
+
+/// ある時点の郵便番号データベースの内容と、郵便番号の検索用インデックス
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// 更新のたびに増えるバージョンの ID（まだ何も取り込んでいなければ 0）
+    version: i64,
+    /// バージョンを作成した日時
+    created_at: chrono::DateTime<chrono::Utc>,
+    records: Vec<UtfKenAllRecord>,
+    /// `records` のレコードの ID
+    ///
+    /// 他の実装と同じく、変わらなかったレコードはバージョン間で同じ ID を使う。
+    record_ids: Vec<i64>,
+    jigyosyo_records: Vec<JigyosyoRecord>,
+    /// `jigyosyo_records` のレコードの ID
+    jigyosyo_ids: Vec<i64>,
+    /// 住所と事業所のレコードを郵便番号順に並べたもの
+    ///
+    /// 並び順は PostgreSQL の実装と同じく、郵便番号・種別（住所が先）・町域名・町域名カナ・
+    /// ID の順とする。前方一致する範囲を二分探索で求められる。
+    index: Vec<IndexEntry>,
+    /// 入力補完用に住所（都道府県名から・市区町村名から・町域名から）を辞書順に並べたもの
+    ///
+    /// 住所での検索にも使い、前方一致する範囲を二分探索で求める。
+    address_suggest_index: Vec<SuggestEntry>,
+    /// 入力補完用に住所の読みを辞書順に並べたもの（読みでの検索にも使う）
+    kana_suggest_index: Vec<SuggestEntry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IndexEntry {
+    Address(usize),
+    Business(usize),
+}
+
+/// 入力補完用のインデックスの要素
+///
+/// 住所を都道府県名・市区町村名・町域名に分け、先頭から `skip` 個を除いて連結したものをキーとする。
+/// 全国分のキーの文字列を持たずに済むように、比較のたびにレコードから組み立てる。
+#[derive(Debug, Clone, Copy)]
+struct SuggestEntry {
+    record: usize,
+    skip: usize,
+}
+
+/// 分割された文字列を連結したものが `query` で始まるかどうか
+fn parts_start_with(parts: &[&str], query: &str) -> bool {
+    let mut rest = query.as_bytes();
+    for part in parts {
+        let len = part.len().min(rest.len());
+        if part.as_bytes()[..len] != rest[..len] {
+            return false;
+        }
+        rest = &rest[len..];
+    }
+    rest.is_empty()
+}
+
+/// 分割された文字列を連結したものと `other` を辞書順に比較する
+fn parts_cmp(parts: &[&str], other: &[&str]) -> std::cmp::Ordering {
+    parts
+        .iter()
+        .flat_map(|p| p.bytes())
+        .cmp(other.iter().flat_map(|p| p.bytes()))
+}
+
+impl Snapshot {
+    fn new(
+        version: i64,
+        records: Vec<(i64, UtfKenAllRecord)>,
+        jigyosyo_records: Vec<(i64, JigyosyoRecord)>,
+    ) -> Self {
+        let (record_ids, records): (Vec<_>, Vec<_>) = records.into_iter().unzip();
+        let (jigyosyo_ids, jigyosyo_records): (Vec<_>, Vec<_>) =
+            jigyosyo_records.into_iter().unzip();
+        let mut snapshot = Self {
+            version,
+            created_at: chrono::Utc::now(),
+            index: (0..records.len())
+                .map(IndexEntry::Address)
+                .chain((0..jigyosyo_records.len()).map(IndexEntry::Business))
+                .collect(),
+            address_suggest_index: vec![],
+            kana_suggest_index: vec![],
+            records,
+            record_ids,
+            jigyosyo_records,
+            jigyosyo_ids,
+        };
+        let mut index = std::mem::take(&mut snapshot.index);
+        index.sort_by(|a, b| snapshot.sort_key(*a).cmp(&snapshot.sort_key(*b)));
+        snapshot.index = index;
+        for field in [SuggestField::Address, SuggestField::Kana] {
+            let mut index = (0..snapshot.records.len())
+                .flat_map(|record| (0..3).map(move |skip| SuggestEntry { record, skip }))
+                .collect::<Vec<_>>();
+            index.sort_by(|a, b| {
+                parts_cmp(
+                    &snapshot.suggest_key(field, *a),
+                    &snapshot.suggest_key(field, *b),
+                )
+            });
+            match field {
+                SuggestField::Address => snapshot.address_suggest_index = index,
+                _ => snapshot.kana_suggest_index = index,
+            }
+        }
+        snapshot
+    }
+
+    /// 入力補完用のインデックスのキー
+    ///
+    /// 都道府県名・市区町村名・町域名（`field` が読みの場合はそれぞれのカナ）のうち、先頭から
+    /// `skip` 個を空文字列に置き換えたもので、連結したものがキーになる。
+    fn suggest_key(&self, field: SuggestField, entry: SuggestEntry) -> [&str; 3] {
+        let r = &self.records[entry.record];
+        let mut parts = match field {
+            SuggestField::Kana => [
+                r.prefecture_kana.as_str(),
+                r.city_kana.as_str(),
+                r.town_kana.as_str(),
+            ],
+            _ => [r.prefecture.as_str(), r.city.as_str(), r.town.as_str()],
+        };
+        for part in parts.iter_mut().take(entry.skip) {
+            *part = "";
+        }
+        parts
+    }
+
+    /// インデックスの並び順のキー（[`SortKey`] と同じ順に比較する）
+    fn sort_key(&self, entry: IndexEntry) -> (&str, i16, &str, &str, i64) {
+        match entry {
+            IndexEntry::Address(i) => {
+                let r = &self.records[i];
+                (&r.postal_code, 0, &r.town, &r.town_kana, self.record_ids[i])
+            }
+            IndexEntry::Business(i) => {
+                let r = &self.jigyosyo_records[i];
+                (&r.postal_code, 1, &r.town, "", self.jigyosyo_ids[i])
+            }
+        }
+    }
+
+    /// ページトークンに入れる並び順のキー
+    fn page_key(&self, entry: IndexEntry) -> SortKey {
+        let (postal_code, kind, town, town_kana, id) = self.sort_key(entry);
+        SortKey {
+            postal_code: postal_code.to_string(),
+            kind,
+            town: town.to_string(),
+            town_kana: town_kana.to_string(),
+            id,
+        }
+    }
+
+    /// 並び順に並んだ `entries` のうち、並び順が `after` より後ろになる最初の位置を二分探索で求める
+    fn position_after(&self, entries: &[IndexEntry], after: &SortKey) -> usize {
+        let after = (
+            after.postal_code.as_str(),
+            after.kind,
+            after.town.as_str(),
+            after.town_kana.as_str(),
+            after.id,
+        );
+        entries.partition_point(|e| self.sort_key(*e) <= after)
+    }
+
+    fn postal_code(&self, entry: IndexEntry) -> &str {
+        self.sort_key(entry).0
+    }
+
+    fn record(&self, entry: IndexEntry) -> PostalCodeRecord {
+        match entry {
+            IndexEntry::Address(i) => self.records[i].clone().into(),
+            IndexEntry::Business(i) => self.jigyosyo_records[i].clone().into(),
+        }
+    }
+
+    /// 郵便番号が `prefix` で始まるエントリの範囲を二分探索で求める
+    fn prefix_range(&self, prefix: &str) -> std::ops::Range<usize> {
+        let start = self
+            .index
+            .partition_point(|e| self.postal_code(*e) < prefix);
+        let len = self.index[start..].partition_point(|e| self.postal_code(*e).starts_with(prefix));
+        start..start + len
+    }
+
+    /// 郵便番号が `postal_code` と完全一致するエントリの範囲を二分探索で求める
+    fn exact_range(&self, postal_code: &str) -> std::ops::Range<usize> {
+        let range = self.prefix_range(postal_code);
+        let len =
+            self.index[range.clone()].partition_point(|e| self.postal_code(*e) == postal_code);
+        range.start..range.start + len
+    }
+
+    /// 入力補完用のインデックスで `query` に前方一致するレコードと、その順位を求める
+    ///
+    /// 完全一致を 0、都道府県から・市区町村から・町域からの前方一致を 1〜3 とし、同じレコードが
+    /// 複数のキーで一致した場合は順位の高い方を採る。`max_skip` 個以上を除いたキーは一致とみなさない。
+    fn suggest_matches(
+        &self,
+        field: SuggestField,
+        query: &str,
+        max_skip: usize,
+    ) -> HashMap<usize, usize> {
+        let index = match field {
+            SuggestField::Kana => &self.kana_suggest_index,
+            _ => &self.address_suggest_index,
+        };
+        let start =
+            index.partition_point(|e| parts_cmp(&self.suggest_key(field, *e), &[query]).is_lt());
+        let len = index[start..]
+            .partition_point(|e| parts_start_with(&self.suggest_key(field, *e), query));
+        let mut ranks = HashMap::<usize, usize>::new();
+        for entry in index[start..start + len]
+            .iter()
+            .filter(|e| e.skip < max_skip)
+        {
+            let key_len = self
+                .suggest_key(field, *entry)
+                .iter()
+                .map(|p| p.len())
+                .sum::<usize>();
+            let rank = if key_len == query.len() {
+                0
+            } else {
+                entry.skip + 1
+            };
+            ranks
+                .entry(entry.record)
+                .and_modify(|r| *r = (*r).min(rank))
+                .or_insert(rank);
+        }
+        ranks
+    }
+}
+
+impl Snapshot {
+    /// 検索の応答に含めるバージョンの ID（まだ何も取り込んでいなければ `None`）
+    fn dataset_version(&self) -> Option<i64> {
+        (self.version > 0).then_some(self.version)
+    }
+
+    fn counts(&self) -> DatasetCounts {
+        DatasetCounts {
+            utf_ken_all: self.records.len() as i64,
+            jigyosyo: self.jigyosyo_records.len() as i64,
+        }
+    }
 }
 
 impl UtfKenAllRepositoryEphemeral {
+    /// `records` を最初のバージョンとして持つリポジトリを作る
     pub fn new(records: Vec<UtfKenAllRecord>) -> Self {
+        Self::default().with_first_version(records, vec![])
+    }
+
+    pub fn with_jigyosyo_records(self, jigyosyo_records: Vec<JigyosyoRecord>) -> Self {
+        let records = self.snapshot.load().records.clone();
+        self.with_first_version(records, jigyosyo_records)
+    }
+
+    fn with_first_version(
+        self,
+        records: Vec<UtfKenAllRecord>,
+        jigyosyo_records: Vec<JigyosyoRecord>,
+    ) -> Self {
+        let snapshot = Arc::new(Snapshot::new(
+            1,
+            (1..).zip(records).collect(),
+            (1..).zip(jigyosyo_records).collect(),
+        ));
+        Self {
+            snapshot: Arc::new(ArcSwap::new(snapshot.clone())),
+            versions: Arc::new(Mutex::new(vec![snapshot])),
+            oldest_version: Arc::new(AtomicI64::new(1)),
+            ..self
+        }
+    }
+
+    /// 使用中のものを含めて保持するバージョンの数を指定する（1 未満は 1 とみなす）
+    pub fn with_versions_to_keep(self, versions_to_keep: usize) -> Self {
+        Self {
+            versions_to_keep,
+            ..self
+        }
+    }
+
+    /// ページトークンの署名に使う鍵を指定する
+    ///
+    /// 指定しない場合はプロセスごとに乱数で生成する。
+    pub fn with_page_token_secret(self, secret: &[u8]) -> Self {
         Self {
-            records: Arc::new(Mutex::new(records)),
+            signer: PageTokenSigner::new(secret),
+            ..self
+        }
+    }
+
+    /// 保持している最も古いバージョンの ID（まだ何も取り込んでいなければ 0）
+    fn oldest_version(&self) -> i64 {
+        self.oldest_version.load(Ordering::Relaxed)
+    }
+
+    pub fn records(&self) -> Vec<UtfKenAllRecord> {
+        self.snapshot.load().records.clone()
+    }
+
+    pub fn jigyosyo_records(&self) -> Vec<JigyosyoRecord> {
+        self.snapshot.load().jigyosyo_records.clone()
+    }
+
+    /// 使用中のバージョンから新しいバージョンを作り、検証してから使用中にする
+    ///
+    /// 追加したレコードには保持しているどのバージョンとも重ならない ID を振る。使用中のバージョンから
+    /// 変わった住所の郵便番号を変更履歴に記録し、保持する数を超えた古いバージョンは捨てる。
+    fn publish(
+        &self,
+        build: impl FnOnce(&Snapshot) -> (NewRecords<UtfKenAllRecord>, NewRecords<JigyosyoRecord>),
+    ) -> Result<(), UtfKenAllRepositoryError> {
+        let mut versions = self.versions.lock().expect("versions lock is poisoned");
+        let current = self.snapshot.load_full();
+        let (records, jigyosyo_records) = build(&current);
+        let id = versions.last().map_or(0, |v| v.version) + 1;
+        let max_record_id = versions.iter().flat_map(|v| v.record_ids.iter()).max();
+        let records = assign_ids(records, max_record_id.copied().unwrap_or(0));
+        let max_jigyosyo_id = versions.iter().flat_map(|v| v.jigyosyo_ids.iter()).max();
+        let jigyosyo_records = assign_ids(jigyosyo_records, max_jigyosyo_id.copied().unwrap_or(0));
+        let snapshot = Arc::new(Snapshot::new(id, records, jigyosyo_records));
+        validate_dataset(
+            (current.version > 0).then(|| current.counts()),
+            snapshot.counts(),
+            snapshot.counts(),
+        )?;
+        if current.version > 0 {
+            let mut history = self.history.lock().expect("history lock is poisoned");
+            let changes = diff_records(&current.records, &snapshot.records);
+            let first_id = history.last().map_or(0, |h| h.id) + 1;
+            history.extend(changes.into_iter().zip(first_id..).map(|(change, id)| {
+                change.into_history(id, snapshot.version, snapshot.created_at)
+            }));
         }
+        versions.push(snapshot.clone());
+        let excess = versions.len().saturating_sub(self.versions_to_keep.max(1));
+        versions.drain(..excess);
+        self.oldest_version
+            .store(versions[0].version, Ordering::Relaxed);
+        self.snapshot.store(snapshot);
+        Ok(())
     }
+}
+
+/// 新しいバージョンのレコード
+///
+/// 使用中のバージョンから引き継ぐレコードはその ID と、新しく追加するレコードは `None` と組にする。
+type NewRecords<T> = Vec<(Option<i64>, T)>;
+
+/// 新しく追加するレコードに `max_id` より後ろの ID を順に振る
+fn assign_ids<T>(records: NewRecords<T>, max_id: i64) -> Vec<(i64, T)> {
+    let mut next_ids = max_id + 1..;
+    records
+        .into_iter()
+        .map(|(id, r)| (id.unwrap_or_else(|| next_ids.next().unwrap()), r))
+        .collect()
+}
 
-    pub fn into_inner(self) -> Arc<Mutex<Vec<UtfKenAllRecord>>> {
-        self.records
+/// 使用中のバージョンのレコードを ID を変えずに引き継ぐ
+fn carry_over<T: Clone>(ids: &[i64], records: &[T]) -> NewRecords<T> {
+    ids.iter()
+        .map(|id| Some(*id))
+        .zip(records.iter().cloned())
+        .collect()
+}
+
+/// 使用中のバージョンのレコードを `records` で置き換える（`None` なら引き継ぐ）
+///
+/// 置き換えても、使用中のバージョンと内容が同じレコードは ID を引き継ぐ。
+fn replace_records<T: Ord + Clone>(
+    ids: &[i64],
+    current: &[T],
+    records: Option<&[T]>,
+) -> NewRecords<T> {
+    match records {
+        Some(records) => {
+            let (ids, _) = match_records(ids.iter().copied().zip(current), records);
+            ids.into_iter().zip(records.iter().cloned()).collect()
+        }
+        None => carry_over(ids, current),
     }
 }
 
+/// 差分の削除対象として同じレコードを指しているかを判定するためのキー
+fn record_key(r: &UtfKenAllRecord) -> (&str, &str, &str, &str, &str) {
+    (
+        &r.local_government_code,
+        &r.postal_code,
+        &r.prefecture,
+        &r.city,
+        &r.town,
+    )
+}
+
 impl UtfKenAllRepository for UtfKenAllRepositoryEphemeral {
-    type Error = std::convert::Infallible;
+    type Error = UtfKenAllRepositoryError;
 
-    #[tracing::instrument(skip(self, records))]
-    async fn replace(&mut self, records: &[UtfKenAllRecord]) -> Result<(), Self::Error> {
-        self.records
-            .lock()
-            .unwrap()
-            .splice(0.., records.iter().cloned());
-        Ok(())
+    #[tracing::instrument(skip(self, records, jigyosyo_records))]
+    async fn replace_dataset(
+        &mut self,
+        records: Option<&[UtfKenAllRecord]>,
+        jigyosyo_records: Option<&[JigyosyoRecord]>,
+    ) -> Result<(), Self::Error> {
+        self.publish(|current| {
+            (
+                replace_records(&current.record_ids, &current.records, records),
+                replace_records(
+                    &current.jigyosyo_ids,
+                    &current.jigyosyo_records,
+                    jigyosyo_records,
+                ),
+            )
+        })
+    }
+
+    #[tracing::instrument(skip(self, added, deleted))]
+    async fn apply_diff(
+        &mut self,
+        added: &[UtfKenAllRecord],
+        deleted: &[UtfKenAllRecord],
+    ) -> Result<(), Self::Error> {
+        let deleted = deleted.iter().map(record_key).collect::<HashSet<_>>();
+        self.publish(|current| {
+            let mut records = carry_over(&current.record_ids, &current.records);
+            records.retain(|(_, r)| !deleted.contains(&record_key(r)));
+            records.extend(added.iter().map(|r| (None, r.clone())));
+            let jigyosyo_records = carry_over(&current.jigyosyo_ids, &current.jigyosyo_records);
+            (records, jigyosyo_records)
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -40,40 +497,350 @@ impl UtfKenAllRepository for UtfKenAllRepositoryEphemeral {
         req: UtfKenAllRepositorySearchRequest<'_>,
     ) -> Result<UtfKenAllRepositorySearchResponse, Self::Error> {
         let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
-        let offset = req
-            .page_token
-            .and_then(|token| token.parse::<usize>().ok())
-            .unwrap_or(0);
-        let records = self
-            .records
-            .lock()
-            .unwrap()
+        let query = format!("postal_code:{}", req.postal_code);
+        let snapshot = self.snapshot.load();
+        let range = snapshot.prefix_range(req.postal_code);
+        let start = match self
+            .signer
+            .verify_after(req.page_token, &query, self.oldest_version())?
+        {
+            Some(after) => {
+                range.start + snapshot.position_after(&snapshot.index[range.clone()], &after)
+            }
+            None => range.start,
+        };
+        let end = (start + page_size).min(range.end);
+        let records = snapshot.index[start..end]
             .iter()
-            .filter(|r| r.postal_code.starts_with(req.postal_code))
-            .skip(offset)
-            .take(page_size)
-            .map(Clone::clone)
+            .map(|e| snapshot.record(*e))
             .collect::<Vec<_>>();
-        let next_page_token = if records.len() == page_size {
-            Some((offset + page_size).to_string())
-        } else {
-            None
+        // 他の実装と同じく、ページサイズが 0 のときは空のページを返してページトークンを発行しない
+        let next_page_token = match snapshot.index[start..end].last() {
+            Some(e) if end < range.end => Some(self.signer.sign(
+                &query,
+                snapshot.page_key(*e),
+                snapshot.version,
+            )),
+            _ => None,
         };
         Ok(UtfKenAllRepositorySearchResponse {
             records,
             next_page_token,
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_by_postal_code(
+        &self,
+        postal_code: &str,
+    ) -> Result<UtfKenAllRepositoryGetResponse, Self::Error> {
+        let snapshot = self.snapshot.load();
+        let records = snapshot.index[snapshot.exact_range(postal_code)]
+            .iter()
+            .map(|e| snapshot.record(*e))
+            .collect();
+        Ok(UtfKenAllRepositoryGetResponse {
+            records,
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self, postal_codes))]
+    async fn get_by_postal_codes(
+        &self,
+        postal_codes: &[String],
+    ) -> Result<UtfKenAllRepositoryGetResponse, Self::Error> {
+        let snapshot = self.snapshot.load();
+        // 他の実装と同じく郵便番号順に並べ、重複した郵便番号は一度だけ返す
+        let mut postal_codes = postal_codes.iter().collect::<Vec<_>>();
+        postal_codes.sort();
+        postal_codes.dedup();
+        let records = postal_codes
+            .into_iter()
+            .flat_map(|postal_code| snapshot.index[snapshot.exact_range(postal_code)].iter())
+            .map(|e| snapshot.record(*e))
+            .collect();
+        Ok(UtfKenAllRepositoryGetResponse {
+            records,
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search_by_address(
+        &self,
+        req: UtfKenAllRepositorySearchByAddressRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchByAddressResponse, Self::Error> {
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        let snapshot = self.snapshot.load();
+        // 都道府県名からまたは市区町村名からの住所に前方一致するレコードを入力補完用のインデックスで
+        // 求め、他の実装と同じく完全一致を 0、前方一致を 1 としてランク付けする
+        let mut ranked = snapshot
+            .suggest_matches(SuggestField::Address, req.address, 2)
+            .into_iter()
+            .map(|(i, rank)| {
+                let r = &snapshot.records[i];
+                (rank.min(1), &r.postal_code, &r.town, &r.town_kana, i)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort();
+        let records = ranked
+            .into_iter()
+            .take(page_size)
+            .map(|(.., i)| snapshot.records[i].clone())
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySearchByAddressResponse {
+            records,
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search_by_kana(
+        &self,
+        req: UtfKenAllRepositorySearchByKanaRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchByKanaResponse, Self::Error> {
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        let query = format!("kana:{}", req.kana);
+        let snapshot = self.snapshot.load();
+        // 都道府県名からまたは市区町村名からの読みに前方一致するレコードを入力補完用のインデックスで
+        // 求め、他の実装と同じ並び順に並べる
+        let mut matches = snapshot
+            .suggest_matches(SuggestField::Kana, req.kana, 2)
+            .into_keys()
+            .map(IndexEntry::Address)
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| snapshot.sort_key(*a).cmp(&snapshot.sort_key(*b)));
+        let start = match self
+            .signer
+            .verify_after(req.page_token, &query, self.oldest_version())?
+        {
+            Some(after) => snapshot.position_after(&matches, &after),
+            None => 0,
+        };
+        let mut entries = matches
+            .into_iter()
+            .skip(start)
+            .take(page_size + 1)
+            .collect::<Vec<_>>();
+        let next_page_token =
+            self.signer
+                .next_page_token(&mut entries, page_size, &query, snapshot.version, |e| {
+                    snapshot.page_key(*e)
+                });
+        let records = entries
+            .into_iter()
+            .filter_map(|e| match e {
+                IndexEntry::Address(i) => Some(snapshot.records[i].clone()),
+                IndexEntry::Business(_) => None,
+            })
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySearchByKanaResponse {
+            records,
+            next_page_token,
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn suggest(
+        &self,
+        req: UtfKenAllRepositorySuggestRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySuggestResponse, Self::Error> {
+        let snapshot = self.snapshot.load();
+        let query = req.query;
+        if req.field == SuggestField::PostalCode {
+            let records = snapshot.index[snapshot.prefix_range(query)]
+                .iter()
+                .filter_map(|e| match *e {
+                    IndexEntry::Address(i) => Some(snapshot.records[i].clone()),
+                    IndexEntry::Business(_) => None,
+                })
+                .take(req.limit)
+                .collect();
+            return Ok(UtfKenAllRepositorySuggestResponse {
+                records,
+                dataset_version: snapshot.dataset_version(),
+            });
+        }
+        // 他の実装と同じく、完全一致を 0、都道府県から・市区町村から・町域からの前方一致を 1〜3 として
+        // ランク付けする
+        let mut ranked = snapshot
+            .suggest_matches(req.field, query, 3)
+            .into_iter()
+            .map(|(i, rank)| {
+                let r = &snapshot.records[i];
+                let town = match req.field {
+                    SuggestField::Kana => &r.town_kana,
+                    _ => &r.town,
+                };
+                (
+                    rank,
+                    town.chars().count(),
+                    &r.postal_code,
+                    &r.town,
+                    &r.town_kana,
+                    i,
+                )
+            })
+            .collect::<Vec<_>>();
+        ranked.sort();
+        let records = ranked
+            .into_iter()
+            .take(req.limit)
+            .map(|(.., i)| snapshot.records[i].clone())
+            .collect();
+        Ok(UtfKenAllRepositorySuggestResponse {
+            records,
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_prefectures(
+        &self,
+    ) -> Result<UtfKenAllRepositoryListPrefecturesResponse, Self::Error> {
+        let snapshot = self.snapshot.load();
+        // 全国地方公共団体コードの上2桁が都道府県コード
+        let prefectures = snapshot
+            .records
+            .iter()
+            .map(|r| Prefecture {
+                code: r.local_government_code.chars().take(2).collect(),
+                name: r.prefecture.clone(),
+                name_kana: r.prefecture_kana.clone(),
+            })
+            .collect::<BTreeSet<_>>();
+        Ok(UtfKenAllRepositoryListPrefecturesResponse {
+            prefectures: prefectures.into_iter().collect(),
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_cities(
+        &self,
+        prefecture_code: Option<&str>,
+    ) -> Result<UtfKenAllRepositoryListCitiesResponse, Self::Error> {
+        let snapshot = self.snapshot.load();
+        let cities = snapshot
+            .records
+            .iter()
+            .filter(|r| {
+                r.local_government_code.len() == 5
+                    && prefecture_code.is_none_or(|code| r.local_government_code.starts_with(code))
+            })
+            .map(|r| City {
+                local_government_code: r.local_government_code.clone(),
+                name: r.city.clone(),
+                name_kana: r.city_kana.clone(),
+            })
+            .collect::<BTreeSet<_>>();
+        Ok(UtfKenAllRepositoryListCitiesResponse {
+            cities: cities.into_iter().collect(),
+            dataset_version: snapshot.dataset_version(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_towns(
+        &self,
+        local_government_code: &str,
+    ) -> Result<UtfKenAllRepositoryListTownsResponse, Self::Error> {
+        let snapshot = self.snapshot.load();
+        let mut towns = snapshot
+            .records
+            .iter()
+            .filter(|r| r.local_government_code == local_government_code && !r.town.is_empty())
+            .map(|r| Town {
+                local_government_code: r.local_government_code.clone(),
+                postal_code: r.postal_code.clone(),
+                name: r.town.clone(),
+                name_kana: r.town_kana.clone(),
+            })
+            .collect::<Vec<_>>();
+        // 他の実装と同じく郵便番号・町域名カナ・町域名の順に並べる
+        towns.sort_by(|a, b| {
+            (&a.postal_code, &a.name_kana, &a.name).cmp(&(&b.postal_code, &b.name_kana, &b.name))
+        });
+        towns.dedup();
+        Ok(UtfKenAllRepositoryListTownsResponse {
+            towns,
+            dataset_version: snapshot.dataset_version(),
         })
     }
 
     #[tracing::instrument(skip(self))]
     async fn count(&self) -> Result<usize, Self::Error> {
-        Ok(self.records.lock().unwrap().len())
+        Ok(self.snapshot.load().records.len())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_versions(&self) -> Result<Vec<DatasetVersion>, Self::Error> {
+        let versions = self.versions.lock().expect("versions lock is poisoned");
+        let active = self.snapshot.load().version;
+        Ok(versions
+            .iter()
+            .rev()
+            .map(|v| DatasetVersion {
+                id: v.version,
+                utf_ken_all_count: v.records.len(),
+                jigyosyo_count: v.jigyosyo_records.len(),
+                created_at: v.created_at,
+                active: v.version == active,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn active_version(&self) -> Result<Option<i64>, Self::Error> {
+        Ok(self.snapshot.load().dataset_version())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn activate_version(&mut self, id: i64) -> Result<(), Self::Error> {
+        let versions = self.versions.lock().expect("versions lock is poisoned");
+        let snapshot = versions
+            .iter()
+            .find(|v| v.version == id)
+            .ok_or(UtfKenAllRepositoryError::DatasetVersionNotFound(id))?;
+        self.snapshot.store(snapshot.clone());
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn rollback_version(&mut self) -> Result<i64, Self::Error> {
+        let versions = self.versions.lock().expect("versions lock is poisoned");
+        let active = self.snapshot.load().version;
+        let snapshot = versions
+            .iter()
+            .rev()
+            .find(|v| v.version < active)
+            .ok_or(UtfKenAllRepositoryError::NoPreviousDatasetVersion)?;
+        self.snapshot.store(snapshot.clone());
+        Ok(snapshot.version)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_history(&self, postal_code: &str) -> Result<Vec<PostalCodeHistory>, Self::Error> {
+        let history = self.history.lock().expect("history lock is poisoned");
+        let mut history = history
+            .iter()
+            .filter(|h| h.postal_code == postal_code)
+            .cloned()
+            .collect::<Vec<_>>();
+        // 取り込みの新しい順に並べ、同じ取り込みの中では記録した順を保つ
+        history.sort_by_key(|h| std::cmp::Reverse(h.dataset_id));
+        Ok(history)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::repo::{FieldChange, PostalCodeChange};
+    use jp_postal_code_core::model::{UpdateCode, UpdateReason};
 
     #[tokio::test]
     async fn utf_ken_all_repository_ephemeral_replace() {
@@ -95,8 +862,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 0,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -111,8 +882,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -127,8 +902,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -143,8 +922,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -159,13 +942,17 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
             .unwrap();
-        insta::assert_debug_snapshot!(repository.records.lock().unwrap());
+        insta::assert_debug_snapshot!(repository.records());
 
         // 正しく置き換えられるかチェック
         repository
@@ -183,8 +970,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -199,13 +990,17 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
             .unwrap();
-        insta::assert_debug_snapshot!(repository.records.lock().unwrap());
+        insta::assert_debug_snapshot!(repository.records());
     }
 
     #[tokio::test]
@@ -224,8 +1019,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 0,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -240,8 +1039,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -256,8 +1059,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -272,8 +1079,12 @@ mod tests {
                 has_multi_postal_code: 1,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -288,8 +1099,12 @@ mod tests {
                 has_multi_postal_code: 1,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
         ]);
 
@@ -316,6 +1131,172 @@ mod tests {
         insta::assert_debug_snapshot!(response);
     }
 
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_search_prefix_range() {
+        // 郵便番号順に並んでいないレコードを用意する
+        let record = |postal_code: &str, town: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            record("0640941", "旭ケ丘"),
+            record("0600042", "大通西２丁目"),
+            record("0600000", ""),
+            record("0600042", "大通西１丁目"),
+            record("0610000", ""),
+            record("0600041", "大通東"),
+        ]);
+        let search = |postal_code: &'static str, page_token: Option<String>| {
+            let repository = repository.clone();
+            async move {
+                repository
+                    .search(UtfKenAllRepositorySearchRequest {
+                        postal_code,
+                        page_size: Some(2),
+                        page_token: page_token.as_deref(),
+                    })
+                    .await
+                    .unwrap()
+            }
+        };
+        let towns = |response: &UtfKenAllRepositorySearchResponse| {
+            response
+                .records
+                .iter()
+                .map(|r| match r {
+                    PostalCodeRecord::Address(r) => format!("{} {}", r.postal_code, r.town),
+                    PostalCodeRecord::Business(r) => format!("{} {}", r.postal_code, r.town),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // 前方一致する範囲だけが郵便番号順に返る
+        let response = search("060", None).await;
+        assert_eq!(towns(&response), vec!["0600000 ", "0600041 大通東"]);
+        let response = search("060", response.next_page_token).await;
+        assert_eq!(
+            towns(&response),
+            vec!["0600042 大通西１丁目", "0600042 大通西２丁目"]
+        );
+        assert_eq!(response.next_page_token, None);
+
+        // 該当なし
+        let response = search("062", None).await;
+        assert!(response.records.is_empty());
+        assert_eq!(response.next_page_token, None);
+
+        // 空の郵便番号は全件が対象
+        let response = search("", None).await;
+        let response = search("", response.next_page_token).await;
+        let response = search("", response.next_page_token).await;
+        assert_eq!(towns(&response), vec!["0610000 ", "0640941 旭ケ丘"]);
+        assert_eq!(response.next_page_token, None);
+
+        // 別の検索条件で発行されたページトークンはエラー
+        let page_token = search("06", None).await.next_page_token.unwrap();
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(2),
+                page_token: Some(&page_token),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::PageTokenMismatch));
+
+        // 複製したリポジトリからも置き換えた内容が見える
+        let mut writer = repository.clone();
+        writer
+            .replace(&[
+                record("0600001", "北一条西"),
+                record("0600042", "大通西１丁目"),
+                record("0600042", "大通西１丁目"),
+            ])
+            .await
+            .unwrap();
+        let response = search("06", None).await;
+        assert_eq!(
+            towns(&response),
+            vec!["0600001 北一条西", "0600042 大通西１丁目"]
+        );
+
+        // 発行したバージョンが残っていれば、置き換える前に発行されたページトークンで続きから読める
+        let response = search("06", Some(page_token.clone())).await;
+        assert_eq!(
+            towns(&response),
+            vec!["0600042 大通西１丁目", "0600042 大通西１丁目"]
+        );
+
+        // 差分を適用しても残ったレコードの ID は変わらないので、同じ内容のレコードの途中からでも続きを読める
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "0600042",
+                page_size: Some(1),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        writer
+            .apply_diff(&[], &[record("0600001", "北一条西")])
+            .await
+            .unwrap();
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "0600042",
+                page_size: Some(1),
+                page_token: response.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(towns(&response), vec!["0600042 大通西１丁目"]);
+        assert_eq!(response.next_page_token, None);
+
+        // 発行したバージョンが削除されたページトークンはエラー
+        let mut writer = writer.with_versions_to_keep(1);
+        writer.apply_diff(&[], &[]).await.unwrap();
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "06",
+                page_size: Some(2),
+                page_token: Some(&page_token),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::StalePageToken));
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_search_invalid_page_token() {
+        let repository = UtfKenAllRepositoryEphemeral::default();
+
+        // 解釈できないページトークンはエラー
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: None,
+                page_token: Some("invalid"),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::InvalidPageToken));
+    }
+
     #[tokio::test]
     async fn utf_ken_all_repository_ephemeral_search_page_size_and_page_token() {
         let repository = UtfKenAllRepositoryEphemeral::new(vec![
@@ -332,8 +1313,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 0,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -348,8 +1333,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -364,8 +1353,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -380,8 +1373,12 @@ mod tests {
                 has_multi_postal_code: 1,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -396,10 +1393,15 @@ mod tests {
                 has_multi_postal_code: 1,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
-        ]);
+        ])
+        .with_page_token_secret(b"secret");
 
         // 1, 2 件目を取得
         let response = repository
@@ -422,6 +1424,1043 @@ mod tests {
             .await
             .unwrap();
         insta::assert_debug_snapshot!(response);
+
+        // ページサイズが 0 のときは空のページを返す
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(0),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        assert!(response.records.is_empty());
+        assert_eq!(response.next_page_token, None);
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_replace_jigyosyo() {
+        let mut repository = UtfKenAllRepositoryEphemeral::default();
+
+        // 正しくデータが挿入されているかチェック
+        repository
+            .replace_jigyosyo(&[
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                    business_name: "札幌市役所".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条西".to_string(),
+                    block_address: "２丁目".to_string(),
+                    postal_code: "0608611".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｶﾌﾞｼｷｶﾞｲｼﾔ ﾎﾂｶｲﾄﾞｳｼﾝﾌﾞﾝｼﾔ".to_string(),
+                    business_name: "株式会社　北海道新聞社".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    block_address: "３丁目６".to_string(),
+                    postal_code: "0608711".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+            ])
+            .await
+            .unwrap();
+        assert_eq!(repository.jigyosyo_records().len(), 2);
+
+        // 住所の郵便番号には影響しない
+        assert_eq!(repository.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_search_with_jigyosyo() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600041".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリヒガシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通東".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ])
+        .with_jigyosyo_records(vec![
+            JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                business_name: "札幌市役所".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条西".to_string(),
+                block_address: "２丁目".to_string(),
+                postal_code: "0608611".to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            },
+            JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "ｶﾌﾞｼｷｶﾞｲｼﾔ ﾎﾂｶｲﾄﾞｳｼﾝﾌﾞﾝｼﾔ".to_string(),
+                business_name: "株式会社　北海道新聞社".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通西".to_string(),
+                block_address: "３丁目６".to_string(),
+                postal_code: "0608711".to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            },
+        ])
+        .with_page_token_secret(b"secret");
+
+        // 住所と事業所が郵便番号順に混ざって返る
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(3),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 残りの事業所を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(3),
+                page_token: response.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_get_by_postal_code() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600041".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリヒガシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通東".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600041".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "キタ１ジョウヒガシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条東".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600042".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリニシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通西".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ])
+        .with_jigyosyo_records(vec![JigyosyoRecord {
+            local_government_code: "01101".to_string(),
+            business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+            business_name: "札幌市役所".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            block_address: "２丁目".to_string(),
+            postal_code: "0608611".to_string(),
+            old_postal_code: "060  ".to_string(),
+            handling_office: "札幌中央".to_string(),
+            business_type: 0,
+            multi_number: 0,
+            update_code: 0,
+        }]);
+
+        // 郵便番号と完全一致するレコードをすべて返す
+        let records = repository
+            .get_by_postal_code("0600041")
+            .await
+            .unwrap()
+            .records;
+        insta::assert_debug_snapshot!(records);
+
+        // 事業所の個別郵便番号も取得できる
+        let records = repository
+            .get_by_postal_code("0608611")
+            .await
+            .unwrap()
+            .records;
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0], PostalCodeRecord::Business(_)));
+
+        // 前方一致はしない
+        let records = repository.get_by_postal_code("060").await.unwrap().records;
+        assert!(records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_get_by_postal_codes() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600041".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリヒガシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通東".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600041".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "キタ１ジョウヒガシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条東".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600042".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリニシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通西".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 1,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ])
+        .with_jigyosyo_records(vec![JigyosyoRecord {
+            local_government_code: "01101".to_string(),
+            business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+            business_name: "札幌市役所".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            block_address: "２丁目".to_string(),
+            postal_code: "0608611".to_string(),
+            old_postal_code: "060  ".to_string(),
+            handling_office: "札幌中央".to_string(),
+            business_type: 0,
+            multi_number: 0,
+            update_code: 0,
+        }]);
+
+        // 郵便番号順にまとめて返し、存在しない郵便番号や重複は無視する
+        let records = repository
+            .get_by_postal_codes(&[
+                "0608611".to_string(),
+                "0600041".to_string(),
+                "060".to_string(),
+                "0600041".to_string(),
+            ])
+            .await
+            .unwrap()
+            .records;
+        insta::assert_debug_snapshot!(records);
+
+        let records = repository.get_by_postal_codes(&[]).await.unwrap().records;
+        assert!(records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_list_areas() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600042".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリニシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通西".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600041".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "オオドオリヒガシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "大通東".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01102".to_string(),
+                old_postal_code: "001  ".to_string(),
+                postal_code: "0600807".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシキタク".to_string(),
+                town_kana: "キタ７ジョウニシ".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市北区".to_string(),
+                town: "北七条西".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ]);
+
+        // 都道府県コード順に都道府県を返す
+        let prefectures = repository.list_prefectures().await.unwrap().prefectures;
+        insta::assert_debug_snapshot!(prefectures);
+
+        // 都道府県内の市区町村を全国地方公共団体コード順に返す
+        let cities = repository.list_cities(Some("01")).await.unwrap().cities;
+        insta::assert_debug_snapshot!(cities);
+        assert!(repository
+            .list_cities(Some("47"))
+            .await
+            .unwrap()
+            .cities
+            .is_empty());
+        // 都道府県を省略するとすべての市区町村を返す
+        let cities = repository.list_cities(None).await.unwrap().cities;
+        assert_eq!(
+            cities
+                .iter()
+                .map(|c| c.local_government_code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["01101", "01102", "13103"]
+        );
+
+        // 市区町村内の町域を郵便番号順に返し、町域名が空のものは除く
+        let towns = repository.list_towns("01101").await.unwrap().towns;
+        insta::assert_debug_snapshot!(towns);
+        assert!(repository
+            .list_towns("01103")
+            .await
+            .unwrap()
+            .towns
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_search_by_address() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050014".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝１丁目".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦１丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ２チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦２丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ]);
+
+        // 完全一致するレコードが先頭になる
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "東京都港区芝浦",
+                page_size: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 都道府県名を省略して前方一致で検索
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "港区芝",
+                page_size: Some(3),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_search_by_kana() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050014".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝１丁目".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦１丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ２チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦２丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ])
+        .with_page_token_secret(b"secret");
+
+        // 都道府県名カナを省略して前方一致で検索し、ページを辿る
+        let first = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトクシバウラ",
+                page_size: Some(2),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(first);
+        let second = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトクシバウラ",
+                page_size: Some(2),
+                page_token: first.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(second);
+
+        // 都道府県名カナから検索
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ホッカイドウサッポロ",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // ワイルドカード文字はエスケープされる
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "%",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        assert!(response.records.is_empty());
+
+        // 不正なページトークン
+        let result = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトク",
+                page_size: None,
+                page_token: Some("invalid"),
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(UtfKenAllRepositoryError::InvalidPageToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_suggest() {
+        let repository = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050014".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝１丁目".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦１丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ２チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦２丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "11203".to_string(),
+                old_postal_code: "333  ".to_string(),
+                postal_code: "3330866".to_string(),
+                prefecture_kana: "サイタマケン".to_string(),
+                city_kana: "カワグチシ".to_string(),
+                town_kana: "シバ".to_string(),
+                prefecture: "埼玉県".to_string(),
+                city: "川口市".to_string(),
+                town: "芝".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ]);
+
+        // 候補を郵便番号と町域名の組にして比較する
+        async fn suggest(
+            repository: &UtfKenAllRepositoryEphemeral,
+            field: SuggestField,
+            query: &str,
+            limit: usize,
+        ) -> Vec<(String, String)> {
+            repository
+                .suggest(UtfKenAllRepositorySuggestRequest {
+                    field,
+                    query,
+                    limit,
+                })
+                .await
+                .unwrap()
+                .records
+                .into_iter()
+                .map(|r| (r.postal_code, r.town))
+                .collect()
+        }
+        let pair = |postal_code: &str, town: &str| (postal_code.to_string(), town.to_string());
+
+        // 完全一致を優先し、町域名からの前方一致は町域名の短い順に並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "芝", 10).await,
+            vec![
+                pair("3330866", "芝"),
+                pair("1080023", "芝浦"),
+                pair("1050014", "芝１丁目"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "芝浦", 2).await,
+            vec![pair("1080023", "芝浦"), pair("1050023", "芝浦１丁目")]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "東京都港区芝浦", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "札幌市中央区", 10).await,
+            vec![pair("0600000", "")]
+        );
+
+        // 読みでも同じ規則で並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::Kana, "シバウ", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Kana, "ミナトクシバ", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050014", "芝１丁目"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+
+        // 郵便番号は郵便番号順に並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::PostalCode, "1050", 10).await,
+            vec![pair("1050014", "芝１丁目"), pair("1050023", "芝浦１丁目")]
+        );
+
+        // ワイルドカード文字はエスケープされる
+        assert!(suggest(&repository, SuggestField::Address, "%", 10)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_roman() {
+        let mut repository = UtfKenAllRepositoryEphemeral::default();
+        let record = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600041".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "オオドオリヒガシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "大通東".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "HOKKAIDO".to_string(),
+            city_roman: "SAPPORO SHI CHUO KU".to_string(),
+            town_roman: "ODORIHIGASHI".to_string(),
+            town_spec: Default::default(),
+        };
+        repository
+            .replace(std::slice::from_ref(&record))
+            .await
+            .unwrap();
+
+        // ローマ字表記も保存され、検索結果に含まれる
+        let records = repository
+            .get_by_postal_code("0600041")
+            .await
+            .unwrap()
+            .records;
+        assert_eq!(records, vec![PostalCodeRecord::Address(record.clone())]);
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ホッカイドウ",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.records, vec![record]);
     }
 
     #[tokio::test]
@@ -446,8 +2485,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 0,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -462,8 +2505,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -478,8 +2525,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -494,8 +2545,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -510,8 +2565,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
@@ -520,4 +2579,290 @@ mod tests {
         let count = repository.count().await.unwrap();
         assert_eq!(count, 5);
     }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_versions() {
+        let mut repository = UtfKenAllRepositoryEphemeral::default().with_versions_to_keep(2);
+        let record = |postal_code: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+
+        // まだ何も取り込んでいない
+        assert_eq!(repository.active_version().await.unwrap(), None);
+        assert!(repository.list_versions().await.unwrap().is_empty());
+
+        // 取り込むたびに新しいバージョンを使用中にし、保持する数を超えた古いバージョンは削除する
+        repository.replace(&[record("0600000")]).await.unwrap();
+        repository
+            .replace(&[record("0600000"), record("0600041")])
+            .await
+            .unwrap();
+        repository
+            .apply_diff(&[record("0600042")], &[])
+            .await
+            .unwrap();
+        let versions = repository.list_versions().await.unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| (v.id, v.utf_ken_all_count, v.jigyosyo_count, v.active))
+                .collect::<Vec<_>>(),
+            vec![(3, 3, 0, true), (2, 2, 0, false)]
+        );
+        assert_eq!(repository.active_version().await.unwrap(), Some(3));
+        assert_eq!(repository.count().await.unwrap(), 3);
+
+        // 一つ前のバージョンに戻す
+        assert_eq!(repository.rollback_version().await.unwrap(), 2);
+        assert_eq!(repository.count().await.unwrap(), 2);
+        assert!(matches!(
+            repository.rollback_version().await,
+            Err(UtfKenAllRepositoryError::NoPreviousDatasetVersion)
+        ));
+
+        // 指定したバージョンに切り替える
+        repository.activate_version(3).await.unwrap();
+        assert_eq!(repository.count().await.unwrap(), 3);
+        assert!(matches!(
+            repository.activate_version(1).await,
+            Err(UtfKenAllRepositoryError::DatasetVersionNotFound(1))
+        ));
+
+        // 空のデータでは使用中のバージョンを切り替えない
+        assert!(matches!(
+            repository.replace(&[]).await,
+            Err(UtfKenAllRepositoryError::InvalidDataset(_))
+        ));
+        assert_eq!(repository.active_version().await.unwrap(), Some(3));
+        assert_eq!(repository.list_versions().await.unwrap().len(), 2);
+        assert_eq!(repository.count().await.unwrap(), 3);
+
+        // 戻したバージョンに差分を適用した新しいバージョンは、その後のバージョンの変更を含まない
+        let postal_codes = ["0600000", "0600041", "0600042", "0600043"].map(String::from);
+        let reader = repository.clone();
+        let active_postal_codes = || async {
+            reader
+                .get_by_postal_codes(&postal_codes)
+                .await
+                .unwrap()
+                .records
+                .iter()
+                .map(|r| r.postal_code().to_string())
+                .collect::<Vec<_>>()
+        };
+        repository.rollback_version().await.unwrap();
+        repository
+            .apply_diff(&[record("0600043")], &[record("0600041")])
+            .await
+            .unwrap();
+        assert_eq!(active_postal_codes().await, vec!["0600000", "0600043"]);
+
+        // その後のバージョンの内容は変わらない
+        repository.activate_version(3).await.unwrap();
+        assert_eq!(
+            active_postal_codes().await,
+            vec!["0600000", "0600041", "0600042"]
+        );
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_replace_dataset() {
+        let mut repository = UtfKenAllRepositoryEphemeral::default();
+        let record = |postal_code: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let jigyosyo = |postal_code: &str| JigyosyoRecord {
+            local_government_code: "01101".to_string(),
+            business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+            business_name: "札幌市役所".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            block_address: "２丁目".to_string(),
+            postal_code: postal_code.to_string(),
+            old_postal_code: "060  ".to_string(),
+            handling_office: "札幌中央".to_string(),
+            business_type: 0,
+            multi_number: 0,
+            update_code: 0,
+        };
+        let versions = |versions: Vec<DatasetVersion>| {
+            versions
+                .iter()
+                .map(|v| (v.id, v.utf_ken_all_count, v.jigyosyo_count, v.active))
+                .collect::<Vec<_>>()
+        };
+
+        // 住所と事業所の個別郵便番号をまとめて一つのバージョンにする
+        repository
+            .replace_dataset(
+                Some(&[record("0600000"), record("0600041")]),
+                Some(&[jigyosyo("0608611")]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap()),
+            vec![(1, 2, 1, true)]
+        );
+
+        // 指定しなかったものは使用中のバージョンから引き継ぐ
+        repository
+            .replace_dataset(None, Some(&[jigyosyo("0608611"), jigyosyo("0608711")]))
+            .await
+            .unwrap();
+        repository
+            .replace_dataset(Some(&[record("0600000")]), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap()),
+            vec![(3, 1, 2, true), (2, 2, 2, false), (1, 2, 1, false)]
+        );
+
+        // 一つ前のバージョンに戻すと両方が戻る
+        assert_eq!(repository.rollback_version().await.unwrap(), 2);
+        assert_eq!(repository.rollback_version().await.unwrap(), 1);
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap())[2],
+            (1, 2, 1, true)
+        );
+    }
+
+    #[tokio::test]
+    async fn utf_ken_all_repository_ephemeral_history() {
+        let mut repository = UtfKenAllRepositoryEphemeral::default().with_versions_to_keep(1);
+        let record = |postal_code: &str, town: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+
+        // 最初の取り込みでは記録しない
+        repository
+            .replace(&[record("0600000", ""), record("0600041", "大通東")])
+            .await
+            .unwrap();
+        assert!(repository.list_history("0600041").await.unwrap().is_empty());
+
+        // 大通東の町域名カナが変わり、北一条西が追加される（更新の表示だけが変わったものは記録しない）
+        let changed = UtfKenAllRecord {
+            town_kana: "オオドオリヒガシ".to_string(),
+            update_code: UpdateCode::Changed,
+            update_reason: UpdateReason::Correction,
+            ..record("0600041", "大通東")
+        };
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    update_code: UpdateCode::Changed,
+                    ..record("0600000", "")
+                },
+                changed.clone(),
+                record("0600001", "北一条西"),
+            ])
+            .await
+            .unwrap();
+        // 大通東が削除される
+        repository.apply_diff(&[], &[changed]).await.unwrap();
+
+        // 取り込みの新しい順に返し、古いバージョンを削除しても変更履歴は残す
+        let history = repository.list_history("0600041").await.unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| (h.dataset_id, h.change, h.update_code, h.update_reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    PostalCodeChange::Removed,
+                    UpdateCode::Changed,
+                    UpdateReason::Correction
+                ),
+                (
+                    2,
+                    PostalCodeChange::Changed,
+                    UpdateCode::Changed,
+                    UpdateReason::Correction
+                ),
+            ]
+        );
+        assert_eq!(history[0].fields.len(), 16);
+        assert!(history[0].fields.contains(&FieldChange {
+            field: "town".to_string(),
+            old: Some("大通東".to_string()),
+            new: None,
+        }));
+        assert_eq!(
+            history[1].fields,
+            vec![FieldChange {
+                field: "town_kana".to_string(),
+                old: Some("".to_string()),
+                new: Some("オオドオリヒガシ".to_string()),
+            }]
+        );
+        assert!(history[0].id > history[1].id);
+        assert!(history[0].imported_at >= history[1].imported_at);
+        assert_eq!(repository.list_versions().await.unwrap().len(), 1);
+
+        let history = repository.list_history("0600001").await.unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| (h.dataset_id, h.change))
+                .collect::<Vec<_>>(),
+            vec![(2, PostalCodeChange::Added)]
+        );
+        assert!(repository.list_history("0600000").await.unwrap().is_empty());
+    }
 }
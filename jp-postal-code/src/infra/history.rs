@@ -0,0 +1,310 @@
+use crate::repo::{FieldChange, PostalCodeChange, PostalCodeHistory};
+use jp_postal_code_core::model::{UpdateCode, UpdateReason, UtfKenAllRecord};
+use std::collections::BTreeMap;
+
+/// 取り込みの前後で変わった住所の郵便番号のレコード
+///
+/// 変更履歴として記録する前のもので、ID と取り込んだバージョン・日時を持たない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RecordChange {
+    pub(crate) postal_code: String,
+    pub(crate) change: PostalCodeChange,
+    pub(crate) fields: Vec<FieldChange>,
+    pub(crate) update_code: UpdateCode,
+    pub(crate) update_reason: UpdateReason,
+}
+
+impl RecordChange {
+    pub(crate) fn into_history(
+        self,
+        id: i64,
+        dataset_id: i64,
+        imported_at: chrono::DateTime<chrono::Utc>,
+    ) -> PostalCodeHistory {
+        PostalCodeHistory {
+            id,
+            postal_code: self.postal_code,
+            change: self.change,
+            fields: self.fields,
+            update_code: self.update_code,
+            update_reason: self.update_reason,
+            dataset_id,
+            imported_at,
+        }
+    }
+}
+
+/// データベースに保存する変更の種類の名前
+pub(crate) fn change_name(change: PostalCodeChange) -> &'static str {
+    match change {
+        PostalCodeChange::Added => "added",
+        PostalCodeChange::Removed => "removed",
+        PostalCodeChange::Changed => "changed",
+    }
+}
+
+/// データベースに保存した変更の種類の名前を解釈する
+pub(crate) fn parse_change(name: &str) -> Result<PostalCodeChange, sqlx::Error> {
+    match name {
+        "added" => Ok(PostalCodeChange::Added),
+        "removed" => Ok(PostalCodeChange::Removed),
+        "changed" => Ok(PostalCodeChange::Changed),
+        _ => Err(sqlx::Error::Decode(
+            format!("unknown postal code change: {name}").into(),
+        )),
+    }
+}
+
+/// 変更を比較する項目の名前と値
+///
+/// 更新の表示・更新理由は変更の理由として変更履歴に持たせるので比較しない（翌月には元に戻るので、
+/// 比較すると同じ変更が二度記録される）。
+fn fields(r: &UtfKenAllRecord) -> [(&'static str, String); 16] {
+    [
+        ("local_government_code", r.local_government_code.clone()),
+        ("old_postal_code", r.old_postal_code.clone()),
+        ("postal_code", r.postal_code.clone()),
+        ("prefecture_kana", r.prefecture_kana.clone()),
+        ("city_kana", r.city_kana.clone()),
+        ("town_kana", r.town_kana.clone()),
+        ("prefecture", r.prefecture.clone()),
+        ("city", r.city.clone()),
+        ("town", r.town.clone()),
+        ("has_multi_postal_code", r.has_multi_postal_code.to_string()),
+        ("has_chome", r.has_chome.to_string()),
+        ("has_multi_town", r.has_multi_town.to_string()),
+        ("prefecture_roman", r.prefecture_roman.clone()),
+        ("city_roman", r.city_roman.clone()),
+        ("town_roman", r.town_roman.clone()),
+        (
+            "town_spec",
+            serde_json::to_string(&r.town_spec).expect("failed to JSON serialize TownSpec"),
+        ),
+    ]
+}
+
+/// 取り込む前のレコード `old` と取り込んだ後のレコード `new` を郵便番号ごとに比較する
+///
+/// 同じ郵便番号のレコードは、内容が同じもの、全国地方公共団体コードと町域名が同じもの、残りを並び順
+/// の順に組にする。組になったレコードは項目が変わっていれば変更、組にならなかったレコードは追加・
+/// 削除とする。郵便番号順に、同じ郵便番号では変更・削除・追加の順に返す。
+pub(crate) fn diff_records(old: &[UtfKenAllRecord], new: &[UtfKenAllRecord]) -> Vec<RecordChange> {
+    fn group(records: &[UtfKenAllRecord]) -> BTreeMap<&str, Vec<&UtfKenAllRecord>> {
+        let mut groups = BTreeMap::<&str, Vec<&UtfKenAllRecord>>::new();
+        for r in records {
+            groups.entry(&r.postal_code).or_default().push(r);
+        }
+        groups
+    }
+    let old = group(old);
+    let new = group(new);
+    let postal_codes = old
+        .keys()
+        .chain(new.keys())
+        .copied()
+        .collect::<std::collections::BTreeSet<_>>();
+
+    let mut changes = vec![];
+    for postal_code in postal_codes {
+        let olds = old.get(postal_code).cloned().unwrap_or_default();
+        let news = new.get(postal_code).cloned().unwrap_or_default();
+        if olds == news {
+            continue;
+        }
+        let mut olds = olds.into_iter().map(|r| (r, fields(r))).collect::<Vec<_>>();
+        let mut news = news.into_iter().map(|r| (r, fields(r))).collect::<Vec<_>>();
+        // 内容が同じレコードは変わっていない
+        olds.retain(|(_, o)| match news.iter().position(|(_, n)| n == o) {
+            Some(i) => {
+                news.remove(i);
+                false
+            }
+            None => true,
+        });
+        let mut pairs = vec![];
+        let mut i = 0;
+        while i < olds.len() {
+            let (o, _) = olds[i];
+            match news.iter().position(|(n, _)| {
+                n.local_government_code == o.local_government_code && n.town == o.town
+            }) {
+                Some(j) => pairs.push((olds.remove(i), news.remove(j))),
+                None => i += 1,
+            }
+        }
+        let len = olds.len().min(news.len());
+        pairs.extend(olds.drain(..len).zip(news.drain(..len)));
+
+        for ((_, old_fields), (new, new_fields)) in pairs {
+            changes.push(RecordChange {
+                postal_code: postal_code.to_string(),
+                change: PostalCodeChange::Changed,
+                fields: old_fields
+                    .into_iter()
+                    .zip(new_fields)
+                    .filter(|((_, old), (_, new))| old != new)
+                    .map(|((field, old), (_, new))| FieldChange {
+                        field: field.to_string(),
+                        old: Some(old),
+                        new: Some(new),
+                    })
+                    .collect(),
+                update_code: new.update_code,
+                update_reason: new.update_reason,
+            });
+        }
+        for (old, old_fields) in olds {
+            changes.push(RecordChange {
+                postal_code: postal_code.to_string(),
+                change: PostalCodeChange::Removed,
+                fields: old_fields
+                    .into_iter()
+                    .map(|(field, old)| FieldChange {
+                        field: field.to_string(),
+                        old: Some(old),
+                        new: None,
+                    })
+                    .collect(),
+                update_code: old.update_code,
+                update_reason: old.update_reason,
+            });
+        }
+        for (new, new_fields) in news {
+            changes.push(RecordChange {
+                postal_code: postal_code.to_string(),
+                change: PostalCodeChange::Added,
+                fields: new_fields
+                    .into_iter()
+                    .map(|(field, new)| FieldChange {
+                        field: field.to_string(),
+                        old: None,
+                        new: Some(new),
+                    })
+                    .collect(),
+                update_code: new.update_code,
+                update_reason: new.update_reason,
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_records() {
+        let record = |postal_code: &str, town: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let summary = |changes: Vec<RecordChange>| {
+            changes
+                .into_iter()
+                .map(|c| {
+                    let fields = c
+                        .fields
+                        .into_iter()
+                        .filter(|f| c.change == PostalCodeChange::Changed || f.field == "town")
+                        .map(|f| (f.field, f.old, f.new))
+                        .collect::<Vec<_>>();
+                    (c.postal_code, c.change, fields)
+                })
+                .collect::<Vec<_>>()
+        };
+        let some = |s: &str| Some(s.to_string());
+
+        // 並び順と更新の表示・更新理由だけが変わったものは変更としない
+        let old = vec![record("0600000", ""), record("0600041", "大通東")];
+        let new = vec![
+            UtfKenAllRecord {
+                update_code: UpdateCode::Changed,
+                update_reason: UpdateReason::Correction,
+                ..record("0600041", "大通東")
+            },
+            record("0600000", ""),
+        ];
+        assert_eq!(diff_records(&old, &new), vec![]);
+
+        let old = vec![
+            record("0600041", "大通東"),
+            record("0600042", "大通西（１～１９丁目）"),
+            record("0600042", "大通西（２０丁目）"),
+            record("0640941", "旭ケ丘"),
+        ];
+        let new = vec![
+            // 同じ町域名のレコードを組にする
+            record("0600041", "大通東"),
+            UtfKenAllRecord {
+                town_kana: "オオドオリニシ".to_string(),
+                update_code: UpdateCode::Changed,
+                update_reason: UpdateReason::Correction,
+                ..record("0600042", "大通西（２０丁目）")
+            },
+            // 残りは並び順に組にする
+            record("0600042", "大通西（１～１８丁目）"),
+            record("0600001", "北一条西"),
+        ];
+        let changes = diff_records(&old, &new);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|c| (c.update_code, c.update_reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (UpdateCode::Unchanged, UpdateReason::Unchanged),
+                (UpdateCode::Changed, UpdateReason::Correction),
+                (UpdateCode::Unchanged, UpdateReason::Unchanged),
+                (UpdateCode::Unchanged, UpdateReason::Unchanged),
+            ]
+        );
+        assert_eq!(changes[0].fields.len(), 16);
+        assert_eq!(changes[3].fields.len(), 16);
+        assert_eq!(
+            summary(changes),
+            vec![
+                (
+                    "0600001".to_string(),
+                    PostalCodeChange::Added,
+                    vec![("town".to_string(), None, some("北一条西"))]
+                ),
+                (
+                    "0600042".to_string(),
+                    PostalCodeChange::Changed,
+                    vec![("town_kana".to_string(), some(""), some("オオドオリニシ"))]
+                ),
+                (
+                    "0600042".to_string(),
+                    PostalCodeChange::Changed,
+                    vec![(
+                        "town".to_string(),
+                        some("大通西（１～１９丁目）"),
+                        some("大通西（１～１８丁目）")
+                    )]
+                ),
+                (
+                    "0640941".to_string(),
+                    PostalCodeChange::Removed,
+                    vec![("town".to_string(), some("旭ケ丘"), None)]
+                ),
+            ]
+        );
+    }
+}
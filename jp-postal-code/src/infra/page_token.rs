@@ -0,0 +1,201 @@
+use crate::repo::UtfKenAllRepositoryError;
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<sha2::Sha256>;
+
+/// リポジトリで共通のページトークンの中身
+///
+/// 検索条件・次のページの位置（[`SortKey`] など）・発行時のデータセットのバージョンを持つ。
+/// [`PageTokenSigner`] で署名して、`<JSON の base64>.<HMAC の base64>` の形式で返す。
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct PageToken<K> {
+    /// 検索の種類と検索条件（`postal_code:060` など）
+    query: String,
+    /// 次のページの位置
+    key: K,
+    /// トークンを発行したときのデータセットのバージョン
+    version: i64,
+}
+
+/// 郵便番号の検索結果の並び順のキー
+///
+/// 郵便番号・種別（住所が 0、事業所が 1）・町域名・町域名カナ・レコードの ID の順に比較する。
+/// ID は種別ごとに一意なので、同じ住所が複数あっても並び順が一つに決まる。ページトークンには
+/// 前のページの最後のレコードのキーを入れ、次のページはそれより後ろから返す。
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SortKey {
+    pub(crate) postal_code: String,
+    pub(crate) kind: i16,
+    pub(crate) town: String,
+    pub(crate) town_kana: String,
+    pub(crate) id: i64,
+}
+
+/// ページトークンを HMAC-SHA256 で署名・検証する
+///
+/// 複製しても同じ鍵を共有する。鍵を指定しない場合はプロセスごとに乱数で生成するので、再起動すると
+/// それまでに発行したページトークンは使えなくなる。
+#[derive(Clone)]
+pub(crate) struct PageTokenSigner {
+    key: Arc<[u8]>,
+}
+
+impl std::fmt::Debug for PageTokenSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PageTokenSigner").finish_non_exhaustive()
+    }
+}
+
+impl Default for PageTokenSigner {
+    fn default() -> Self {
+        Self::new(&rand::random::<[u8; 32]>())
+    }
+}
+
+impl PageTokenSigner {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        Self { key: key.into() }
+    }
+
+    fn mac(&self, payload: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any size");
+        mac.update(payload);
+        mac
+    }
+
+    /// 次のページの位置 `key` を、検索条件 `query` とデータセットのバージョンに結びつけて署名する
+    pub(crate) fn sign<K: Serialize>(&self, query: &str, key: K, version: i64) -> String {
+        let token = PageToken {
+            query: query.to_string(),
+            key,
+            version,
+        };
+        let payload = serde_json::to_vec(&token).expect("failed to JSON serialize PageToken");
+        let signature = self.mac(&payload).finalize().into_bytes();
+        format!(
+            "{}.{}",
+            base64_url::encode(&payload),
+            base64_url::encode(&signature)
+        )
+    }
+
+    /// ページトークンを検証して次のページの位置を取り出す
+    ///
+    /// 署名が一致しない（改ざんされた・別の鍵で署名された）場合は
+    /// [`UtfKenAllRepositoryError::InvalidPageToken`]、別の検索条件で発行された場合は
+    /// [`UtfKenAllRepositoryError::PageTokenMismatch`]、発行したバージョンが保持している最も古い
+    /// バージョン `oldest` より古い（削除された）場合は [`UtfKenAllRepositoryError::StalePageToken`]
+    /// を返す。レコードの ID はバージョン間で変わらないので、発行したバージョンが残っていれば更新後も
+    /// 続きから読める。
+    pub(crate) fn verify<K: DeserializeOwned>(
+        &self,
+        token: &str,
+        query: &str,
+        oldest: i64,
+    ) -> Result<K, UtfKenAllRepositoryError> {
+        let (payload, signature) = token
+            .split_once('.')
+            .ok_or(UtfKenAllRepositoryError::InvalidPageToken)?;
+        let payload =
+            base64_url::decode(payload).map_err(|_| UtfKenAllRepositoryError::InvalidPageToken)?;
+        let signature = base64_url::decode(signature)
+            .map_err(|_| UtfKenAllRepositoryError::InvalidPageToken)?;
+        self.mac(&payload)
+            .verify_slice(&signature)
+            .map_err(|_| UtfKenAllRepositoryError::InvalidPageToken)?;
+        let token = serde_json::from_slice::<PageToken<K>>(&payload)
+            .map_err(|_| UtfKenAllRepositoryError::InvalidPageToken)?;
+        if token.query != query {
+            return Err(UtfKenAllRepositoryError::PageTokenMismatch);
+        }
+        if token.version < oldest {
+            return Err(UtfKenAllRepositoryError::StalePageToken);
+        }
+        Ok(token.key)
+    }
+
+    /// ページトークンが指定されていれば検証して、前のページの最後のレコードのキーを取り出す
+    pub(crate) fn verify_after(
+        &self,
+        token: Option<&str>,
+        query: &str,
+        oldest: i64,
+    ) -> Result<Option<SortKey>, UtfKenAllRepositoryError> {
+        token
+            .map(|token| self.verify(token, query, oldest))
+            .transpose()
+    }
+
+    /// 1 件多く読んだ `records` を `page_size` 件に切り詰め、続きがあれば次のページのトークンを発行する
+    ///
+    /// 次のページの位置は、切り詰めた後の最後のレコードの並び順のキー（`key` で求める）にする。
+    pub(crate) fn next_page_token<T>(
+        &self,
+        records: &mut Vec<T>,
+        page_size: usize,
+        query: &str,
+        version: i64,
+        key: impl FnOnce(&T) -> SortKey,
+    ) -> Option<String> {
+        if records.len() <= page_size {
+            return None;
+        }
+        records.truncate(page_size);
+        records.last().map(|r| self.sign(query, key(r), version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_token_signer() {
+        let signer = PageTokenSigner::new(b"secret");
+        let token = signer.sign("postal_code:060", 10usize, 1);
+        assert_eq!(
+            signer
+                .verify::<usize>(&token, "postal_code:060", 1)
+                .unwrap(),
+            10
+        );
+
+        // 発行したバージョンが残っていれば更新後も使える
+        assert_eq!(
+            signer
+                .verify::<usize>(&token, "postal_code:060", 0)
+                .unwrap(),
+            10
+        );
+
+        // 別の検索条件・削除されたバージョンのページトークン
+        assert!(matches!(
+            signer.verify::<usize>(&token, "postal_code:061", 1),
+            Err(UtfKenAllRepositoryError::PageTokenMismatch)
+        ));
+        assert!(matches!(
+            signer.verify::<usize>(&token, "postal_code:060", 2),
+            Err(UtfKenAllRepositoryError::StalePageToken)
+        ));
+
+        // 改ざんされた・別の鍵で署名されたページトークン
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{signature}",
+            base64_url::encode(r#"{"query":"postal_code:060","key":20,"version":1}"#)
+        );
+        let other = PageTokenSigner::new(b"other").sign("postal_code:060", 10usize, 1);
+        for token in [forged.as_str(), other.as_str(), "invalid", "MTA"] {
+            assert!(
+                matches!(
+                    signer.verify::<usize>(token, "postal_code:060", 1),
+                    Err(UtfKenAllRepositoryError::InvalidPageToken)
+                ),
+                "{token}"
+            );
+        }
+    }
+}
@@ -1,62 +1,364 @@
+use super::dataset::{self, DatasetCounts, DatasetStore};
+use super::history::{change_name, RecordChange};
+use super::page_token::PageTokenSigner;
+use super::row::{
+    escape_like, DbJigyosyoRecord, DbPostalCodeHistory, DbPostalCodeRecord, DbUtfKenAllRecord,
+};
 use crate::repo::{
-    UtfKenAllRepository, UtfKenAllRepositorySearchRequest, UtfKenAllRepositorySearchResponse,
-    DEFAULT_SEARCH_PAGE_SIZE,
+    DatasetVersion, FieldChange, PostalCodeHistory, SuggestField, UtfKenAllRepository,
+    UtfKenAllRepositoryError, UtfKenAllRepositoryGetResponse,
+    UtfKenAllRepositoryListCitiesResponse, UtfKenAllRepositoryListPrefecturesResponse,
+    UtfKenAllRepositoryListTownsResponse, UtfKenAllRepositorySearchByAddressRequest,
+    UtfKenAllRepositorySearchByAddressResponse, UtfKenAllRepositorySearchByKanaRequest,
+    UtfKenAllRepositorySearchByKanaResponse, UtfKenAllRepositorySearchRequest,
+    UtfKenAllRepositorySearchResponse, UtfKenAllRepositorySuggestRequest,
+    UtfKenAllRepositorySuggestResponse, DEFAULT_DATASET_VERSIONS_TO_KEEP, DEFAULT_SEARCH_PAGE_SIZE,
+};
+use jp_postal_code_core::model::{
+    City, JigyosyoRecord, PostalCodeRecord, Prefecture, Town, TownSpec, UpdateCode, UpdateReason,
+    UtfKenAllRecord,
 };
-use jp_postal_code_core::model::UtfKenAllRecord;
+use sqlx::types::Json;
 use sqlx::Connection as _;
 
 #[derive(Debug, Clone)]
 pub struct UtfKenAllRepositoryPostgres {
     pool: sqlx::PgPool,
+    versions_to_keep: usize,
+    signer: PageTokenSigner,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct PageToken {
-    utf_ken_all_id: i64,
-}
+impl UtfKenAllRepositoryPostgres {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self {
+            pool,
+            versions_to_keep: DEFAULT_DATASET_VERSIONS_TO_KEEP,
+            signer: PageTokenSigner::default(),
+        }
+    }
 
-impl PageToken {
-    fn new(utf_ken_all_id: i64) -> Self {
-        Self { utf_ken_all_id }
+    /// 使用中のものを含めて保持するバージョンの数を指定する（1 未満は 1 とみなす）
+    pub fn with_versions_to_keep(self, versions_to_keep: usize) -> Self {
+        Self {
+            versions_to_keep,
+            ..self
+        }
     }
 
-    fn parse(token: &str) -> Option<Self> {
-        let decoded = base64_url::decode(token).ok()?;
-        serde_json::from_slice::<PageToken>(&decoded).ok()
+    /// ページトークンの署名に使う鍵を指定する
+    ///
+    /// 指定しない場合はプロセスごとに乱数で生成するので、複数のプロセスで同じデータベースを使う
+    /// 場合は同じ鍵を指定する。
+    pub fn with_page_token_secret(self, secret: &[u8]) -> Self {
+        Self {
+            signer: PageTokenSigner::new(secret),
+            ..self
+        }
     }
 }
 
-impl std::fmt::Display for PageToken {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let encoded = serde_json::to_vec(self).expect("failed to JSON serialize PageToken");
-        let token = base64_url::encode(&encoded);
-        write!(f, "{token}")
-    }
+/// 検索に使う読み取り専用のトランザクションを始め、同じスナップショットで使用中のバージョンの ID を読む
+///
+/// 検索結果、ページトークン、応答に含めるバージョンが同じ時点のデータベースを指すようにする。
+async fn begin_snapshot(
+    pool: &sqlx::PgPool,
+) -> Result<(sqlx::Transaction<'static, sqlx::Postgres>, Option<i64>), sqlx::Error> {
+    let mut tx = pool
+        .begin_with("BEGIN ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .await?;
+    let dataset_version = tx.active_dataset_id().await?;
+    Ok((tx, dataset_version))
 }
 
-impl UtfKenAllRepositoryPostgres {
-    pub fn new(pool: sqlx::PgPool) -> Self {
-        Self { pool }
+// https://github.com/launchbadge/sqlx/issues/3464
+const BIND_LIMIT: usize = u16::MAX as usize;
+
+impl DatasetStore for sqlx::PgConnection {
+    async fn active_dataset_id(&mut self) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT dataset_id FROM active_dataset")
+            .fetch_one(self)
+            .await
     }
-}
 
-impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
-    type Error = sqlx::Error;
+    async fn oldest_dataset_id(&mut self) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT min(dataset_id) FROM dataset")
+            .fetch_one(self)
+            .await
+    }
 
-    #[tracing::instrument(skip(self, records))]
-    async fn replace(&mut self, records: &[UtfKenAllRecord]) -> Result<(), Self::Error> {
-        tracing::info!(
-            count = records.len(),
-            "Start inserting records into utf_ken_all table"
-        );
-        let updated_at = chrono::Utc::now();
-        let mut conn = self.pool.acquire().await?;
-        let mut tx = conn.begin().await?;
-        // まず全レコードを削除
-        sqlx::query!("TRUNCATE TABLE utf_ken_all RESTART IDENTITY")
-            .execute(&mut *tx)
-            .await?;
-        // 次に指定されたレコードを全て挿入
+    async fn create_dataset(&mut self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO dataset (utf_ken_all_count, jigyosyo_count, created_at)
+            VALUES (0, 0, now())
+            RETURNING dataset_id
+            "#
+        )
+        .fetch_one(self)
+        .await
+    }
+
+    async fn rebase(&mut self, base: Option<i64>, dataset_id: i64) -> Result<(), sqlx::Error> {
+        let base = base.unwrap_or(0);
+        sqlx::query!(
+            "UPDATE utf_ken_all SET valid_to = $2 WHERE valid_to > $2 AND valid_from > $1",
+            base,
+            dataset_id,
+        )
+        .execute(&mut *self)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO utf_ken_all (
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec,
+                updated_at,
+                valid_from
+            )
+            SELECT
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec,
+                updated_at,
+                $2
+            FROM utf_ken_all
+            WHERE valid_from <= $1 AND valid_to > $1 AND valid_to <= $2
+            ORDER BY utf_ken_all_id
+            "#,
+            base,
+            dataset_id,
+        )
+        .execute(&mut *self)
+        .await?;
+        sqlx::query!(
+            "UPDATE jigyosyo SET valid_to = $2 WHERE valid_to > $2 AND valid_from > $1",
+            base,
+            dataset_id,
+        )
+        .execute(&mut *self)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO jigyosyo (
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code,
+                updated_at,
+                valid_from
+            )
+            SELECT
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code,
+                updated_at,
+                $2
+            FROM jigyosyo
+            WHERE valid_from <= $1 AND valid_to > $1 AND valid_to <= $2
+            ORDER BY jigyosyo_id
+            "#,
+            base,
+            dataset_id,
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+    ) -> Result<Vec<(i64, UtfKenAllRecord)>, sqlx::Error> {
+        let records = sqlx::query_as!(
+            DbUtfKenAllRecord,
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code AS "update_code: UpdateCode",
+                update_reason AS "update_reason: UpdateReason",
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec AS "town_spec: Json<TownSpec>"
+            FROM utf_ken_all
+            WHERE valid_from <= $1 AND valid_to > $1
+            ORDER BY utf_ken_all_id
+            "#,
+            dataset_id,
+        )
+        .fetch_all(self)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|r| (r.utf_ken_all_id, r.into()))
+            .collect())
+    }
+
+    async fn list_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+    ) -> Result<Vec<(i64, JigyosyoRecord)>, sqlx::Error> {
+        let records = sqlx::query_as!(
+            DbJigyosyoRecord,
+            r#"
+            SELECT
+                jigyosyo_id,
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code
+            FROM jigyosyo
+            WHERE valid_from <= $1 AND valid_to > $1
+            ORDER BY jigyosyo_id
+            "#,
+            dataset_id,
+        )
+        .fetch_all(self)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|r| (r.jigyosyo_id, r.into()))
+            .collect())
+    }
+
+    async fn retire_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        ids: &[i64],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE utf_ken_all SET valid_to = $1 WHERE utf_ken_all_id = ANY($2)",
+            dataset_id,
+            ids,
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn retire_jigyosyo(&mut self, dataset_id: i64, ids: &[i64]) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE jigyosyo SET valid_to = $1 WHERE jigyosyo_id = ANY($2)",
+            dataset_id,
+            ids,
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn retire_matching_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        records: &[UtfKenAllRecord],
+    ) -> Result<u64, sqlx::Error> {
+        let (local_government_codes, postal_codes, prefectures, cities, towns) = records
+            .iter()
+            .fold((vec![], vec![], vec![], vec![], vec![]), |mut acc, r| {
+                acc.0.push(r.local_government_code.to_owned());
+                acc.1.push(r.postal_code.to_owned());
+                acc.2.push(r.prefecture.to_owned());
+                acc.3.push(r.city.to_owned());
+                acc.4.push(r.town.to_owned());
+                acc
+            });
+        let result = sqlx::query!(
+            r#"
+            UPDATE utf_ken_all SET valid_to = $6
+            WHERE valid_to > $6
+                AND (local_government_code, postal_code, prefecture, city, town) IN (
+                    SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::text[], $5::text[])
+                )
+            "#,
+            &local_government_codes,
+            &postal_codes,
+            &prefectures,
+            &cities,
+            &towns,
+            dataset_id,
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        records: &[UtfKenAllRecord],
+        updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
         let mut query_builder: sqlx::QueryBuilder<sqlx::postgres::Postgres> =
             sqlx::QueryBuilder::new(
                 r#"
@@ -75,12 +377,15 @@ impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
                     has_multi_town,
                     update_code,
                     update_reason,
-                    updated_at
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    town_spec,
+                    updated_at,
+                    valid_from
                 ) "#,
             );
-        // https://github.com/launchbadge/sqlx/issues/3464
-        const BIND_LIMIT: usize = u16::MAX as usize;
-        for chunk in records.chunks(BIND_LIMIT / 15) {
+        for chunk in records.chunks(BIND_LIMIT / 20) {
             query_builder.reset();
             query_builder.push_values(chunk, |mut b, r| {
                 b.push_bind(r.local_government_code.to_owned())
@@ -97,12 +402,282 @@ impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
                     .push_bind(r.has_multi_town)
                     .push_bind(r.update_code)
                     .push_bind(r.update_reason)
-                    .push_bind(updated_at);
+                    .push_bind(r.prefecture_roman.to_owned())
+                    .push_bind(r.city_roman.to_owned())
+                    .push_bind(r.town_roman.to_owned())
+                    .push_bind(Json(r.town_spec.clone()))
+                    .push_bind(updated_at)
+                    .push_bind(dataset_id);
+            });
+            query_builder.build().execute(&mut *self).await?;
+        }
+        Ok(())
+    }
+
+    async fn insert_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+        records: &[JigyosyoRecord],
+        updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut query_builder: sqlx::QueryBuilder<sqlx::postgres::Postgres> =
+            sqlx::QueryBuilder::new(
+                r#"
+                INSERT INTO jigyosyo (
+                    local_government_code,
+                    business_name_kana,
+                    business_name,
+                    prefecture,
+                    city,
+                    town,
+                    block_address,
+                    postal_code,
+                    old_postal_code,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    update_code,
+                    updated_at,
+                    valid_from
+                ) "#,
+            );
+        for chunk in records.chunks(BIND_LIMIT / 15) {
+            query_builder.reset();
+            query_builder.push_values(chunk, |mut b, r| {
+                b.push_bind(r.local_government_code.to_owned())
+                    .push_bind(r.business_name_kana.to_owned())
+                    .push_bind(r.business_name.to_owned())
+                    .push_bind(r.prefecture.to_owned())
+                    .push_bind(r.city.to_owned())
+                    .push_bind(r.town.to_owned())
+                    .push_bind(r.block_address.to_owned())
+                    .push_bind(r.postal_code.to_owned())
+                    .push_bind(r.old_postal_code.to_owned())
+                    .push_bind(r.handling_office.to_owned())
+                    .push_bind(r.business_type)
+                    .push_bind(r.multi_number)
+                    .push_bind(r.update_code)
+                    .push_bind(updated_at)
+                    .push_bind(dataset_id);
+            });
+            query_builder.build().execute(&mut *self).await?;
+        }
+        Ok(())
+    }
+
+    async fn recorded_counts(&mut self, dataset_id: i64) -> Result<DatasetCounts, sqlx::Error> {
+        sqlx::query_as!(
+            DatasetCounts,
+            r#"
+            SELECT utf_ken_all_count AS utf_ken_all, jigyosyo_count AS jigyosyo
+            FROM dataset
+            WHERE dataset_id = $1
+            "#,
+            dataset_id,
+        )
+        .fetch_one(self)
+        .await
+    }
+
+    async fn count_records(&mut self, dataset_id: i64) -> Result<DatasetCounts, sqlx::Error> {
+        sqlx::query_as!(
+            DatasetCounts,
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM utf_ken_all WHERE valid_from <= $1 AND valid_to > $1)
+                    AS "utf_ken_all!",
+                (SELECT COUNT(*) FROM jigyosyo WHERE valid_from <= $1 AND valid_to > $1)
+                    AS "jigyosyo!"
+            "#,
+            dataset_id,
+        )
+        .fetch_one(self)
+        .await
+    }
+
+    async fn activate_dataset(
+        &mut self,
+        dataset_id: i64,
+        counts: DatasetCounts,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE dataset SET utf_ken_all_count = $2, jigyosyo_count = $3
+            WHERE dataset_id = $1
+            "#,
+            dataset_id,
+            counts.utf_ken_all,
+            counts.jigyosyo,
+        )
+        .execute(&mut *self)
+        .await?;
+        sqlx::query!("UPDATE active_dataset SET dataset_id = $1", dataset_id)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_expired_datasets(
+        &mut self,
+        versions_to_keep: usize,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let expired = sqlx::query_scalar!(
+            "SELECT dataset_id FROM dataset ORDER BY dataset_id DESC OFFSET $1",
+            versions_to_keep as i64,
+        )
+        .fetch_all(&mut *self)
+        .await?;
+        if !expired.is_empty() {
+            sqlx::query!("DELETE FROM dataset WHERE dataset_id = ANY($1)", &expired)
+                .execute(&mut *self)
+                .await?;
+            // 残ったどのバージョンにも含まれないレコード
+            sqlx::query!(
+                "DELETE FROM utf_ken_all WHERE valid_to <= (SELECT min(dataset_id) FROM dataset)"
+            )
+            .execute(&mut *self)
+            .await?;
+            sqlx::query!(
+                "DELETE FROM jigyosyo WHERE valid_to <= (SELECT min(dataset_id) FROM dataset)"
+            )
+            .execute(&mut *self)
+            .await?;
+        }
+        Ok(expired)
+    }
+
+    async fn changed_postal_codes(&mut self, dataset_id: i64) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT postal_code FROM utf_ken_all
+            WHERE valid_from = $1 OR valid_to = $1
+            "#,
+            dataset_id,
+        )
+        .fetch_all(self)
+        .await
+    }
+
+    async fn find_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        postal_codes: &[String],
+    ) -> Result<Vec<UtfKenAllRecord>, sqlx::Error> {
+        let records = sqlx::query_as!(
+            DbUtfKenAllRecord,
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code AS "update_code: UpdateCode",
+                update_reason AS "update_reason: UpdateReason",
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec AS "town_spec: Json<TownSpec>"
+            FROM utf_ken_all
+            WHERE valid_from <= $1 AND valid_to > $1 AND postal_code = ANY($2)
+            ORDER BY utf_ken_all_id
+            "#,
+            dataset_id,
+            postal_codes,
+        )
+        .fetch_all(self)
+        .await?;
+        Ok(records.into_iter().map(UtfKenAllRecord::from).collect())
+    }
+
+    async fn insert_history(
+        &mut self,
+        dataset_id: i64,
+        changes: &[RecordChange],
+        imported_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut query_builder: sqlx::QueryBuilder<sqlx::postgres::Postgres> =
+            sqlx::QueryBuilder::new(
+                r#"
+                INSERT INTO postal_code_history (
+                    dataset_id,
+                    postal_code,
+                    change,
+                    fields,
+                    update_code,
+                    update_reason,
+                    imported_at
+                ) "#,
+            );
+        for chunk in changes.chunks(BIND_LIMIT / 7) {
+            query_builder.reset();
+            query_builder.push_values(chunk, |mut b, c| {
+                b.push_bind(dataset_id)
+                    .push_bind(c.postal_code.to_owned())
+                    .push_bind(change_name(c.change))
+                    .push_bind(Json(c.fields.clone()))
+                    .push_bind(c.update_code)
+                    .push_bind(c.update_reason)
+                    .push_bind(imported_at);
             });
-            query_builder.build().execute(&mut *tx).await?;
+            query_builder.build().execute(&mut *self).await?;
         }
+        Ok(())
+    }
+}
+
+impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
+    type Error = UtfKenAllRepositoryError;
+
+    #[tracing::instrument(skip(self, added, deleted))]
+    async fn apply_diff(
+        &mut self,
+        added: &[UtfKenAllRecord],
+        deleted: &[UtfKenAllRecord],
+    ) -> Result<(), Self::Error> {
+        tracing::info!(
+            added = added.len(),
+            deleted = deleted.len(),
+            "Start applying diff to utf_ken_all table"
+        );
+        let mut conn = self.pool.acquire().await?;
+        let mut tx = conn.begin().await?;
+        let dataset_id =
+            dataset::apply_diff(&mut *tx, added, deleted, self.versions_to_keep).await?;
+        tx.commit().await?;
+        tracing::info!(dataset_id, "Finish applying diff to utf_ken_all table");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, records, jigyosyo_records))]
+    async fn replace_dataset(
+        &mut self,
+        records: Option<&[UtfKenAllRecord]>,
+        jigyosyo_records: Option<&[JigyosyoRecord]>,
+    ) -> Result<(), Self::Error> {
+        tracing::info!(
+            count = records.map(|r| r.len()),
+            jigyosyo_count = jigyosyo_records.map(|r| r.len()),
+            "Start inserting records into utf_ken_all and jigyosyo table"
+        );
+        let mut conn = self.pool.acquire().await?;
+        let mut tx = conn.begin().await?;
+        let dataset_id =
+            dataset::replace_dataset(&mut *tx, records, jigyosyo_records, self.versions_to_keep)
+                .await?;
         tx.commit().await?;
-        tracing::info!("Finish inserting records into utf_ken_all table");
+        tracing::info!(
+            dataset_id,
+            "Finish inserting records into utf_ken_all and jigyosyo table"
+        );
         Ok(())
     }
 
@@ -113,18 +688,50 @@ impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
     ) -> Result<UtfKenAllRepositorySearchResponse, Self::Error> {
         let postal_code = req.postal_code;
         let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
-        let page_token = req.page_token.and_then(PageToken::parse);
         tracing::info!(
             %postal_code,
-            "Start finding records from utf_ken_all table"
+            "Start finding records from utf_ken_all and jigyosyo table"
         );
-        let mut conn = self.pool.acquire().await?;
-        let mut records = if let Some(page_token) = page_token {
-            sqlx::query_as!(
-                DbUtfKenAllRecord,
-                r#"
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let version = dataset_version.unwrap_or(0);
+        let query = format!("postal_code:{}", postal_code);
+        let oldest = tx.oldest_dataset_id().await?.unwrap_or(0);
+        let after = self.signer.verify_after(req.page_token, &query, oldest)?;
+        // 住所と事業所を郵便番号順に混ぜる（同じ郵便番号なら住所が先）
+        let mut records = sqlx::query_as!(
+            DbPostalCodeRecord,
+            r#"
+            SELECT
+                kind AS "kind!",
+                record_id AS "record_id!",
+                local_government_code AS "local_government_code!",
+                old_postal_code AS "old_postal_code!",
+                postal_code AS "postal_code!",
+                prefecture_kana AS "prefecture_kana!",
+                city_kana AS "city_kana!",
+                town_kana AS "town_kana!",
+                prefecture AS "prefecture!",
+                city AS "city!",
+                town AS "town!",
+                has_multi_postal_code AS "has_multi_postal_code!",
+                has_chome AS "has_chome!",
+                has_multi_town AS "has_multi_town!",
+                update_code AS "update_code!",
+                update_reason AS "update_reason!",
+                prefecture_roman AS "prefecture_roman!",
+                city_roman AS "city_roman!",
+                town_roman AS "town_roman!",
+                business_name_kana AS "business_name_kana!",
+                business_name AS "business_name!",
+                block_address AS "block_address!",
+                handling_office AS "handling_office!",
+                business_type AS "business_type!",
+                multi_number AS "multi_number!",
+                town_spec AS "town_spec!: Json<TownSpec>"
+            FROM (
                 SELECT
-                    utf_ken_all_id,
+                    0::smallint AS kind,
+                    utf_ken_all_id AS record_id,
                     local_government_code,
                     old_postal_code,
                     postal_code,
@@ -138,24 +745,135 @@ impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
                     has_chome,
                     has_multi_town,
                     update_code,
-                    update_reason
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    '' AS business_name_kana,
+                    '' AS business_name,
+                    '' AS block_address,
+                    '' AS handling_office,
+                    0::smallint AS business_type,
+                    0::smallint AS multi_number,
+                    town_spec
                 FROM utf_ken_all
-                WHERE utf_ken_all_id >= $3 AND postal_code LIKE $1
-                ORDER BY postal_code, town, town_kana
-                LIMIT $2
-                "#,
-                format!("{}%", postal_code),
-                (page_size + 1) as i64,
-                page_token.utf_ken_all_id,
-            )
-            .fetch_all(&mut *conn)
-            .await?
-        } else {
-            sqlx::query_as!(
-                DbUtfKenAllRecord,
-                r#"
+                WHERE postal_code LIKE $1 AND valid_from <= $8 AND valid_to > $8
+                UNION ALL
+                SELECT
+                    1::smallint,
+                    jigyosyo_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    '',
+                    '',
+                    '',
+                    prefecture,
+                    city,
+                    town,
+                    0::smallint,
+                    0::smallint,
+                    0::smallint,
+                    update_code,
+                    0::smallint,
+                    '',
+                    '',
+                    '',
+                    business_name_kana,
+                    business_name,
+                    block_address,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    '{}'::jsonb
+                FROM jigyosyo
+                WHERE postal_code LIKE $1 AND valid_from <= $8 AND valid_to > $8
+            ) AS records
+            WHERE $3::text IS NULL
+                OR (postal_code, kind, town, town_kana, record_id)
+                    > ($3, $4::smallint, $5, $6, $7::bigint)
+            ORDER BY postal_code, kind, town, town_kana, record_id
+            LIMIT $2
+            "#,
+            format!("{}%", postal_code),
+            (page_size + 1) as i64,
+            after.as_ref().map(|k| k.postal_code.as_str()),
+            after.as_ref().map(|k| k.kind),
+            after.as_ref().map(|k| k.town.as_str()),
+            after.as_ref().map(|k| k.town_kana.as_str()),
+            after.as_ref().map(|k| k.id),
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding records from utf_ken_all and jigyosyo table"
+        );
+        let next_page_token = self.signer.next_page_token(
+            &mut records,
+            page_size,
+            &query,
+            version,
+            DbPostalCodeRecord::sort_key,
+        );
+        let records = records
+            .into_iter()
+            .map(PostalCodeRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UtfKenAllRepositorySearchResponse {
+            next_page_token,
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_by_postal_code(
+        &self,
+        postal_code: &str,
+    ) -> Result<UtfKenAllRepositoryGetResponse, Self::Error> {
+        tracing::info!(
+            %postal_code,
+            "Start getting records from utf_ken_all and jigyosyo table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        // 住所を先に、事業所を後に返す
+        let records = sqlx::query_as!(
+            DbPostalCodeRecord,
+            r#"
+            SELECT
+                kind AS "kind!",
+                record_id AS "record_id!",
+                local_government_code AS "local_government_code!",
+                old_postal_code AS "old_postal_code!",
+                postal_code AS "postal_code!",
+                prefecture_kana AS "prefecture_kana!",
+                city_kana AS "city_kana!",
+                town_kana AS "town_kana!",
+                prefecture AS "prefecture!",
+                city AS "city!",
+                town AS "town!",
+                has_multi_postal_code AS "has_multi_postal_code!",
+                has_chome AS "has_chome!",
+                has_multi_town AS "has_multi_town!",
+                update_code AS "update_code!",
+                update_reason AS "update_reason!",
+                prefecture_roman AS "prefecture_roman!",
+                city_roman AS "city_roman!",
+                town_roman AS "town_roman!",
+                business_name_kana AS "business_name_kana!",
+                business_name AS "business_name!",
+                block_address AS "block_address!",
+                handling_office AS "handling_office!",
+                business_type AS "business_type!",
+                multi_number AS "multi_number!",
+                town_spec AS "town_spec!: Json<TownSpec>"
+            FROM (
                 SELECT
-                    utf_ken_all_id,
+                    0::smallint AS kind,
+                    utf_ken_all_id AS record_id,
                     local_government_code,
                     old_postal_code,
                     postal_code,
@@ -169,100 +887,737 @@ impl UtfKenAllRepository for UtfKenAllRepositoryPostgres {
                     has_chome,
                     has_multi_town,
                     update_code,
-                    update_reason
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    '' AS business_name_kana,
+                    '' AS business_name,
+                    '' AS block_address,
+                    '' AS handling_office,
+                    0::smallint AS business_type,
+                    0::smallint AS multi_number,
+                    town_spec
                 FROM utf_ken_all
-                WHERE postal_code LIKE $1
-                ORDER BY postal_code, town, town_kana
-                LIMIT $2
-                "#,
-                format!("{}%", postal_code),
-                (page_size + 1) as i64,
-            )
-            .fetch_all(&mut *conn)
-            .await?
-        };
+                WHERE postal_code = $1 AND valid_from <= $2 AND valid_to > $2
+                UNION ALL
+                SELECT
+                    1::smallint,
+                    jigyosyo_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    '',
+                    '',
+                    '',
+                    prefecture,
+                    city,
+                    town,
+                    0::smallint,
+                    0::smallint,
+                    0::smallint,
+                    update_code,
+                    0::smallint,
+                    '',
+                    '',
+                    '',
+                    business_name_kana,
+                    business_name,
+                    block_address,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    '{}'::jsonb
+                FROM jigyosyo
+                WHERE postal_code = $1 AND valid_from <= $2 AND valid_to > $2
+            ) AS records
+            ORDER BY kind, town, town_kana, record_id
+            "#,
+            postal_code,
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
         tracing::info!(
             count = records.len(),
-            "Finish finding records from utf_ken_all table"
-        );
-        let next_page_token = if records.len() > page_size {
-            let utf_ken_all_id = records
-                .pop()
-                .expect("records should not be empty")
-                .utf_ken_all_id;
-            Some(PageToken::new(utf_ken_all_id).to_string())
-        } else {
-            None
-        };
+            "Finish getting records from utf_ken_all and jigyosyo table"
+        );
         let records = records
             .into_iter()
-            .map(UtfKenAllRecord::from)
-            .collect::<Vec<_>>();
-        Ok(UtfKenAllRepositorySearchResponse {
-            next_page_token,
+            .map(PostalCodeRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UtfKenAllRepositoryGetResponse {
             records,
+            dataset_version,
         })
     }
 
-    #[tracing::instrument(skip(self))]
-    async fn count(&self) -> Result<usize, Self::Error> {
-        let mut conn = self.pool.acquire().await?;
-        let count: i64 = sqlx::query_scalar!(r#"SELECT COUNT(*) FROM utf_ken_all"#)
-            .fetch_one(&mut *conn)
-            .await?
-            .unwrap_or(0);
-        Ok(count as usize)
+    #[tracing::instrument(skip(self, postal_codes))]
+    async fn get_by_postal_codes(
+        &self,
+        postal_codes: &[String],
+    ) -> Result<UtfKenAllRepositoryGetResponse, Self::Error> {
+        tracing::info!(
+            count = postal_codes.len(),
+            "Start getting records of postal codes from utf_ken_all and jigyosyo table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        // 郵便番号ごとに住所を先に、事業所を後に返す
+        let records = sqlx::query_as!(
+            DbPostalCodeRecord,
+            r#"
+            SELECT
+                kind AS "kind!",
+                record_id AS "record_id!",
+                local_government_code AS "local_government_code!",
+                old_postal_code AS "old_postal_code!",
+                postal_code AS "postal_code!",
+                prefecture_kana AS "prefecture_kana!",
+                city_kana AS "city_kana!",
+                town_kana AS "town_kana!",
+                prefecture AS "prefecture!",
+                city AS "city!",
+                town AS "town!",
+                has_multi_postal_code AS "has_multi_postal_code!",
+                has_chome AS "has_chome!",
+                has_multi_town AS "has_multi_town!",
+                update_code AS "update_code!",
+                update_reason AS "update_reason!",
+                prefecture_roman AS "prefecture_roman!",
+                city_roman AS "city_roman!",
+                town_roman AS "town_roman!",
+                business_name_kana AS "business_name_kana!",
+                business_name AS "business_name!",
+                block_address AS "block_address!",
+                handling_office AS "handling_office!",
+                business_type AS "business_type!",
+                multi_number AS "multi_number!",
+                town_spec AS "town_spec!: Json<TownSpec>"
+            FROM (
+                SELECT
+                    0::smallint AS kind,
+                    utf_ken_all_id AS record_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    prefecture_kana,
+                    city_kana,
+                    town_kana,
+                    prefecture,
+                    city,
+                    town,
+                    has_multi_postal_code,
+                    has_chome,
+                    has_multi_town,
+                    update_code,
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    '' AS business_name_kana,
+                    '' AS business_name,
+                    '' AS block_address,
+                    '' AS handling_office,
+                    0::smallint AS business_type,
+                    0::smallint AS multi_number,
+                    town_spec
+                FROM utf_ken_all
+                WHERE postal_code = ANY($1) AND valid_from <= $2 AND valid_to > $2
+                UNION ALL
+                SELECT
+                    1::smallint,
+                    jigyosyo_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    '',
+                    '',
+                    '',
+                    prefecture,
+                    city,
+                    town,
+                    0::smallint,
+                    0::smallint,
+                    0::smallint,
+                    update_code,
+                    0::smallint,
+                    '',
+                    '',
+                    '',
+                    business_name_kana,
+                    business_name,
+                    block_address,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    '{}'::jsonb
+                FROM jigyosyo
+                WHERE postal_code = ANY($1) AND valid_from <= $2 AND valid_to > $2
+            ) AS records
+            ORDER BY postal_code, kind, town, town_kana, record_id
+            "#,
+            postal_codes,
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish getting records of postal codes from utf_ken_all and jigyosyo table"
+        );
+        let records = records
+            .into_iter()
+            .map(PostalCodeRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UtfKenAllRepositoryGetResponse {
+            records,
+            dataset_version,
+        })
     }
-}
 
-#[derive(Debug, Clone, sqlx::FromRow)]
-struct DbUtfKenAllRecord {
-    utf_ken_all_id: i64,
-    local_government_code: String,
-    old_postal_code: String,
-    postal_code: String,
-    prefecture_kana: String,
-    city_kana: String,
-    town_kana: String,
-    prefecture: String,
-    city: String,
-    town: String,
-    has_multi_postal_code: i16,
-    has_chome: i16,
-    has_multi_town: i16,
-    update_code: i16,
-    update_reason: i16,
-}
+    #[tracing::instrument(skip(self))]
+    async fn search_by_address(
+        &self,
+        req: UtfKenAllRepositorySearchByAddressRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchByAddressResponse, Self::Error> {
+        let address = req.address;
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        tracing::info!(
+            %address,
+            "Start finding records by address from utf_ken_all table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let records = sqlx::query_as!(
+            DbUtfKenAllRecord,
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code AS "update_code: UpdateCode",
+                update_reason AS "update_reason: UpdateReason",
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec AS "town_spec: Json<TownSpec>"
+            FROM utf_ken_all
+            WHERE ((prefecture || city || town) LIKE $1 OR (city || town) LIKE $1)
+                AND valid_from <= $4 AND valid_to > $4
+            ORDER BY
+                CASE WHEN (prefecture || city || town) = $2 OR (city || town) = $2 THEN 0 ELSE 1 END,
+                postal_code,
+                town,
+                town_kana
+            LIMIT $3
+            "#,
+            format!("{}%", escape_like(address)),
+            address,
+            page_size as i64,
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding records by address from utf_ken_all table"
+        );
+        let records = records
+            .into_iter()
+            .map(UtfKenAllRecord::from)
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySearchByAddressResponse {
+            records,
+            dataset_version,
+        })
+    }
 
-impl From<DbUtfKenAllRecord> for UtfKenAllRecord {
-    fn from(record: DbUtfKenAllRecord) -> Self {
-        UtfKenAllRecord {
-            local_government_code: record.local_government_code,
-            old_postal_code: record.old_postal_code,
-            postal_code: record.postal_code,
-            prefecture_kana: record.prefecture_kana,
-            city_kana: record.city_kana,
-            town_kana: record.town_kana,
-            prefecture: record.prefecture,
-            city: record.city,
-            town: record.town,
-            has_multi_postal_code: record.has_multi_postal_code,
-            has_chome: record.has_chome,
-            has_multi_town: record.has_multi_town,
-            update_code: record.update_code,
-            update_reason: record.update_reason,
-        }
+    #[tracing::instrument(skip(self))]
+    async fn search_by_kana(
+        &self,
+        req: UtfKenAllRepositorySearchByKanaRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchByKanaResponse, Self::Error> {
+        let kana = req.kana;
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        tracing::info!(%kana, "Start finding records by kana from utf_ken_all table");
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let version = dataset_version.unwrap_or(0);
+        let query = format!("kana:{}", kana);
+        let oldest = tx.oldest_dataset_id().await?.unwrap_or(0);
+        let after = self.signer.verify_after(req.page_token, &query, oldest)?;
+        let mut records = sqlx::query_as!(
+            DbUtfKenAllRecord,
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code AS "update_code: UpdateCode",
+                update_reason AS "update_reason: UpdateReason",
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec AS "town_spec: Json<TownSpec>"
+            FROM utf_ken_all
+            WHERE ((prefecture_kana || city_kana || town_kana) LIKE $1
+                    OR (city_kana || town_kana) LIKE $1)
+                AND ($3::text IS NULL
+                    OR (postal_code, town, town_kana, utf_ken_all_id) > ($3, $4, $5, $6::bigint))
+                AND valid_from <= $7 AND valid_to > $7
+            ORDER BY postal_code, town, town_kana, utf_ken_all_id
+            LIMIT $2
+            "#,
+            format!("{}%", escape_like(kana)),
+            (page_size + 1) as i64,
+            after.as_ref().map(|k| k.postal_code.as_str()),
+            after.as_ref().map(|k| k.town.as_str()),
+            after.as_ref().map(|k| k.town_kana.as_str()),
+            after.as_ref().map(|k| k.id),
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding records by kana from utf_ken_all table"
+        );
+        let next_page_token = self.signer.next_page_token(
+            &mut records,
+            page_size,
+            &query,
+            version,
+            DbUtfKenAllRecord::sort_key,
+        );
+        let records = records
+            .into_iter()
+            .map(UtfKenAllRecord::from)
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySearchByKanaResponse {
+            records,
+            next_page_token,
+            dataset_version,
+        })
     }
-}
 
-#[cfg(test)]
-mod tests {
+    #[tracing::instrument(skip(self))]
+    async fn suggest(
+        &self,
+        req: UtfKenAllRepositorySuggestRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySuggestResponse, Self::Error> {
+        let query = req.query;
+        let pattern = format!("{}%", escape_like(query));
+        let limit = req.limit as i64;
+        tracing::info!(%query, field = ?req.field, "Start finding suggestions from utf_ken_all table");
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let records = match req.field {
+            SuggestField::PostalCode => {
+                sqlx::query_as!(
+                    DbUtfKenAllRecord,
+                    r#"
+                    SELECT
+                        utf_ken_all_id,
+                        local_government_code,
+                        old_postal_code,
+                        postal_code,
+                        prefecture_kana,
+                        city_kana,
+                        town_kana,
+                        prefecture,
+                        city,
+                        town,
+                        has_multi_postal_code,
+                        has_chome,
+                        has_multi_town,
+                        update_code AS "update_code: UpdateCode",
+                        update_reason AS "update_reason: UpdateReason",
+                        prefecture_roman,
+                        city_roman,
+                        town_roman,
+                        town_spec AS "town_spec: Json<TownSpec>"
+                    FROM utf_ken_all
+                    WHERE postal_code LIKE $1
+                        AND valid_from <= $3 AND valid_to > $3
+                    ORDER BY postal_code, town, town_kana, utf_ken_all_id
+                    LIMIT $2
+                    "#,
+                    pattern,
+                    limit,
+                    dataset_version,
+                )
+                .fetch_all(&mut *tx)
+                .await?
+            }
+            SuggestField::Address => {
+                sqlx::query_as!(
+                    DbUtfKenAllRecord,
+                    r#"
+                    SELECT
+                        utf_ken_all_id,
+                        local_government_code,
+                        old_postal_code,
+                        postal_code,
+                        prefecture_kana,
+                        city_kana,
+                        town_kana,
+                        prefecture,
+                        city,
+                        town,
+                        has_multi_postal_code,
+                        has_chome,
+                        has_multi_town,
+                        update_code AS "update_code: UpdateCode",
+                        update_reason AS "update_reason: UpdateReason",
+                        prefecture_roman,
+                        city_roman,
+                        town_roman,
+                        town_spec AS "town_spec: Json<TownSpec>"
+                    FROM utf_ken_all
+                    WHERE ((prefecture || city || town) LIKE $1
+                        OR (city || town) LIKE $1
+                        OR town LIKE $1)
+                        AND valid_from <= $4 AND valid_to > $4
+                    ORDER BY
+                        CASE
+                            WHEN (prefecture || city || town) = $2
+                                OR (city || town) = $2
+                                OR town = $2 THEN 0
+                            WHEN (prefecture || city || town) LIKE $1 THEN 1
+                            WHEN (city || town) LIKE $1 THEN 2
+                            ELSE 3
+                        END,
+                        char_length(town),
+                        postal_code,
+                        town,
+                        town_kana,
+                        utf_ken_all_id
+                    LIMIT $3
+                    "#,
+                    pattern,
+                    query,
+                    limit,
+                    dataset_version,
+                )
+                .fetch_all(&mut *tx)
+                .await?
+            }
+            SuggestField::Kana => {
+                sqlx::query_as!(
+                    DbUtfKenAllRecord,
+                    r#"
+                    SELECT
+                        utf_ken_all_id,
+                        local_government_code,
+                        old_postal_code,
+                        postal_code,
+                        prefecture_kana,
+                        city_kana,
+                        town_kana,
+                        prefecture,
+                        city,
+                        town,
+                        has_multi_postal_code,
+                        has_chome,
+                        has_multi_town,
+                        update_code AS "update_code: UpdateCode",
+                        update_reason AS "update_reason: UpdateReason",
+                        prefecture_roman,
+                        city_roman,
+                        town_roman,
+                        town_spec AS "town_spec: Json<TownSpec>"
+                    FROM utf_ken_all
+                    WHERE ((prefecture_kana || city_kana || town_kana) LIKE $1
+                        OR (city_kana || town_kana) LIKE $1
+                        OR town_kana LIKE $1)
+                        AND valid_from <= $4 AND valid_to > $4
+                    ORDER BY
+                        CASE
+                            WHEN (prefecture_kana || city_kana || town_kana) = $2
+                                OR (city_kana || town_kana) = $2
+                                OR town_kana = $2 THEN 0
+                            WHEN (prefecture_kana || city_kana || town_kana) LIKE $1 THEN 1
+                            WHEN (city_kana || town_kana) LIKE $1 THEN 2
+                            ELSE 3
+                        END,
+                        char_length(town_kana),
+                        postal_code,
+                        town,
+                        town_kana,
+                        utf_ken_all_id
+                    LIMIT $3
+                    "#,
+                    pattern,
+                    query,
+                    limit,
+                    dataset_version,
+                )
+                .fetch_all(&mut *tx)
+                .await?
+            }
+        };
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding suggestions from utf_ken_all table"
+        );
+        let records = records
+            .into_iter()
+            .map(UtfKenAllRecord::from)
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySuggestResponse {
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_prefectures(
+        &self,
+    ) -> Result<UtfKenAllRepositoryListPrefecturesResponse, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        // 全国地方公共団体コードの上2桁が都道府県コード
+        let prefectures = sqlx::query_as!(
+            Prefecture,
+            r#"
+            SELECT
+                left(local_government_code, 2) AS "code!",
+                prefecture AS "name!",
+                prefecture_kana AS "name_kana!"
+            FROM utf_ken_all
+            WHERE valid_from <= $1 AND valid_to > $1
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2, 3
+            "#,
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(UtfKenAllRepositoryListPrefecturesResponse {
+            prefectures,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_cities(
+        &self,
+        prefecture_code: Option<&str>,
+    ) -> Result<UtfKenAllRepositoryListCitiesResponse, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let cities = sqlx::query_as!(
+            City,
+            r#"
+            SELECT
+                local_government_code AS "local_government_code!",
+                city AS "name!",
+                city_kana AS "name_kana!"
+            FROM utf_ken_all
+            WHERE ($1::text IS NULL OR local_government_code BETWEEN $1 || '000' AND $1 || '999')
+                AND valid_from <= $2 AND valid_to > $2
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2, 3
+            "#,
+            prefecture_code,
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(UtfKenAllRepositoryListCitiesResponse {
+            cities,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_towns(
+        &self,
+        local_government_code: &str,
+    ) -> Result<UtfKenAllRepositoryListTownsResponse, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let towns = sqlx::query_as!(
+            Town,
+            r#"
+            SELECT
+                local_government_code AS "local_government_code!",
+                postal_code AS "postal_code!",
+                town AS "name!",
+                town_kana AS "name_kana!"
+            FROM utf_ken_all
+            WHERE local_government_code = $1 AND town <> ''
+                AND valid_from <= $2 AND valid_to > $2
+            GROUP BY 1, 2, 3, 4
+            ORDER BY 2, 4, 3
+            "#,
+            local_government_code,
+            dataset_version,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(UtfKenAllRepositoryListTownsResponse {
+            towns,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count(&self) -> Result<usize, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let count: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM utf_ken_all
+            WHERE valid_from <= $1 AND valid_to > $1
+            "#,
+            dataset_version,
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .unwrap_or(0);
+        tx.commit().await?;
+        Ok(count as usize)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_versions(&self) -> Result<Vec<DatasetVersion>, Self::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let versions = sqlx::query!(
+            r#"
+            SELECT
+                dataset.dataset_id,
+                dataset.utf_ken_all_count,
+                dataset.jigyosyo_count,
+                dataset.created_at,
+                COALESCE(dataset.dataset_id = active_dataset.dataset_id, false) AS "active!"
+            FROM dataset
+            CROSS JOIN active_dataset
+            ORDER BY dataset.dataset_id DESC
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        Ok(versions
+            .into_iter()
+            .map(|v| DatasetVersion {
+                id: v.dataset_id,
+                utf_ken_all_count: v.utf_ken_all_count as usize,
+                jigyosyo_count: v.jigyosyo_count as usize,
+                created_at: v.created_at,
+                active: v.active,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn active_version(&self) -> Result<Option<i64>, Self::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Ok(conn.active_dataset_id().await?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn activate_version(&mut self, id: i64) -> Result<(), Self::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let result = sqlx::query!(
+            r#"
+            UPDATE active_dataset SET dataset_id = $1
+            WHERE EXISTS (SELECT 1 FROM dataset WHERE dataset_id = $1)
+            "#,
+            id,
+        )
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(UtfKenAllRepositoryError::DatasetVersionNotFound(id));
+        }
+        tracing::info!(dataset_id = id, "Activated dataset version");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn rollback_version(&mut self) -> Result<i64, Self::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let id = sqlx::query_scalar!(
+            r#"
+            UPDATE active_dataset SET dataset_id = (
+                SELECT max(dataset.dataset_id) FROM dataset
+                WHERE dataset.dataset_id < active_dataset.dataset_id
+            )
+            WHERE EXISTS (
+                SELECT 1 FROM dataset WHERE dataset.dataset_id < active_dataset.dataset_id
+            )
+            RETURNING dataset_id AS "dataset_id!"
+            "#
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(UtfKenAllRepositoryError::NoPreviousDatasetVersion)?;
+        tracing::info!(dataset_id = id, "Rolled back dataset version");
+        Ok(id)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_history(&self, postal_code: &str) -> Result<Vec<PostalCodeHistory>, Self::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let history = sqlx::query_as!(
+            DbPostalCodeHistory,
+            r#"
+            SELECT
+                postal_code_history_id,
+                dataset_id,
+                postal_code,
+                change,
+                fields AS "fields: Json<Vec<FieldChange>>",
+                update_code AS "update_code: UpdateCode",
+                update_reason AS "update_reason: UpdateReason",
+                imported_at
+            FROM postal_code_history
+            WHERE postal_code = $1
+            ORDER BY dataset_id DESC, postal_code_history_id
+            "#,
+            postal_code,
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(PostalCodeHistory::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
+    use crate::repo::PostalCodeChange;
     use crate::MIGRATOR;
 
     #[sqlx::test(migrator = "MIGRATOR")]
     async fn utf_ken_all_repository_postgres_replace(pool: sqlx::PgPool) {
-        let mut repository = UtfKenAllRepositoryPostgres { pool: pool.clone() };
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
 
         // 正しくデータが挿入されているかチェック
         repository
@@ -280,8 +1635,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 0,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -296,8 +1655,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -312,8 +1675,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -328,8 +1695,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -344,8 +1715,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
@@ -368,8 +1743,14 @@ mod tests {
                     has_chome,
                     has_multi_town,
                     update_code,
-                    update_reason
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    town_spec AS "town_spec: Json<TownSpec>"
                 FROM utf_ken_all
+                JOIN active_dataset
+                    ON valid_from <= active_dataset.dataset_id AND valid_to > active_dataset.dataset_id
                 "#,
             )
             .fetch_all(&mut *conn)
@@ -394,8 +1775,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -410,8 +1795,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
@@ -434,8 +1823,14 @@ mod tests {
                     has_chome,
                     has_multi_town,
                     update_code,
-                    update_reason
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    town_spec AS "town_spec: Json<TownSpec>"
                 FROM utf_ken_all
+                JOIN active_dataset
+                    ON valid_from <= active_dataset.dataset_id AND valid_to > active_dataset.dataset_id
                 "#,
             )
             .fetch_all(&mut *conn)
@@ -447,7 +1842,7 @@ mod tests {
 
     #[sqlx::test(migrator = "MIGRATOR")]
     async fn utf_ken_all_repository_postgres_search(pool: sqlx::PgPool) {
-        let mut repository = UtfKenAllRepositoryPostgres { pool: pool.clone() };
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
 
         // サンプルデータを入力
         repository
@@ -465,8 +1860,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 0,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -481,8 +1880,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -497,8 +1900,168 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640820".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（２０〜２８丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 完全一致で検索
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "0640820",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 前方一致で検索
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_search_invalid_page_token(pool: sqlx::PgPool) {
+        let repository = UtfKenAllRepositoryPostgres::new(pool);
+
+        // 解釈できないページトークンはエラー
+        for page_token in ["invalid", "eyJvZmZzZXQiOiJhIn0", "MTA"] {
+            let err = repository
+                .search(UtfKenAllRepositorySearchRequest {
+                    postal_code: "060",
+                    page_size: None,
+                    page_token: Some(page_token),
+                })
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, UtfKenAllRepositoryError::InvalidPageToken),
+                "{page_token}: {err:?}"
+            );
+        }
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_search_page_size_and_page_token(pool: sqlx::PgPool) {
+        let mut repository =
+            UtfKenAllRepositoryPostgres::new(pool.clone()).with_page_token_secret(b"secret");
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "イカニケイサイガナイバアイ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "以下に掲載がない場合".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640941".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "アサヒガオカ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "旭ケ丘".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -509,61 +2072,941 @@ mod tests {
                     town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
                     prefecture: "北海道".to_string(),
                     city: "札幌市中央区".to_string(),
-                    town: "大通西（１〜１９丁目）".to_string(),
-                    has_multi_postal_code: 1,
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640820".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（２０〜２８丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 1, 2 件目を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(2),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 3 件目を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(2),
+                page_token: response.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 別の検索条件で発行されたページトークンはエラー
+        let page_token = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(1),
+                page_token: None,
+            })
+            .await
+            .unwrap()
+            .next_page_token
+            .unwrap();
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "06",
+                page_size: Some(1),
+                page_token: Some(&page_token),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::PageTokenMismatch));
+
+        // 発行したバージョンが残っていれば、データベースを更新した後も続きから読める
+        let request = UtfKenAllRepositorySearchRequest {
+            postal_code: "060",
+            page_size: Some(1),
+            page_token: Some(&page_token),
+        };
+        let expected = repository.search(request.clone()).await.unwrap();
+        repository.apply_diff(&[], &[]).await.unwrap();
+        let response = repository.search(request).await.unwrap();
+        assert_eq!(response.records, expected.records);
+
+        // 発行したバージョンが削除されたページトークンはエラー
+        let mut repository = repository.with_versions_to_keep(1);
+        repository.apply_diff(&[], &[]).await.unwrap();
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(1),
+                page_token: Some(&page_token),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::StalePageToken));
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_search_by_address(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050014".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝１丁目".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦１丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ２チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦２丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 完全一致するレコードが先頭になる
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "東京都港区芝浦",
+                page_size: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 都道府県名を省略して前方一致で検索
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "港区芝",
+                page_size: Some(3),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // ワイルドカード文字はエスケープされる
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "%",
+                page_size: None,
+            })
+            .await
+            .unwrap();
+        assert!(response.records.is_empty());
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_search_by_kana(pool: sqlx::PgPool) {
+        let mut repository =
+            UtfKenAllRepositoryPostgres::new(pool.clone()).with_page_token_secret(b"secret");
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050014".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝１丁目".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦１丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ２チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦２丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 都道府県名カナを省略して前方一致で検索し、ページを辿る
+        let first = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトクシバウラ",
+                page_size: Some(2),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(first);
+        let second = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトクシバウラ",
+                page_size: Some(2),
+                page_token: first.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(second);
+
+        // 都道府県名カナから検索
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ホッカイドウサッポロ",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // ワイルドカード文字はエスケープされる
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "%",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        assert!(response.records.is_empty());
+
+        // 不正なページトークン
+        let result = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトク",
+                page_size: None,
+                page_token: Some("invalid"),
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(UtfKenAllRepositoryError::InvalidPageToken)
+        ));
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_apply_diff(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
+        let ikani = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600000".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let asahigaoka = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "064  ".to_string(),
+            postal_code: "0640941".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "アサヒガオカ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "旭ケ丘".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let odorihigashi = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600041".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "オオドオリヒガシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "大通東".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let kita1jonishi = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600001".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "キタ１ジョウニシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            has_multi_postal_code: 1,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Changed,
+            update_reason: UpdateReason::MunicipalityEnforcement,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        repository
+            .replace(&[ikani, asahigaoka.clone(), odorihigashi.clone()])
+            .await
+            .unwrap();
+
+        // 旭ケ丘を削除し、大通東を変更し、北一条西を新設する
+        repository
+            .apply_diff(
+                &[
+                    UtfKenAllRecord {
+                        update_code: UpdateCode::Changed,
+                        update_reason: UpdateReason::Correction,
+                        has_multi_town: 0,
+                        ..odorihigashi.clone()
+                    },
+                    kita1jonishi.clone(),
+                ],
+                &[
+                    UtfKenAllRecord {
+                        update_code: UpdateCode::Abolished,
+                        update_reason: UpdateReason::Abolished,
+                        ..asahigaoka.clone()
+                    },
+                    UtfKenAllRecord {
+                        update_code: UpdateCode::Changed,
+                        update_reason: UpdateReason::Correction,
+                        ..odorihigashi.clone()
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        let active_rows = || async {
+            sqlx::query_as::<_, (i64, String, String, i16, i16)>(
+                r#"
+                SELECT utf_ken_all_id, postal_code, town, has_multi_town, update_code
+                FROM utf_ken_all
+                JOIN active_dataset
+                    ON valid_from <= active_dataset.dataset_id AND valid_to > active_dataset.dataset_id
+                ORDER BY utf_ken_all_id
+                "#,
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+        let rows = active_rows().await;
+        // 変更のないレコードは ID を変えずに引き継ぎ、挿入したレコードはその後ろに並ぶ
+        assert_eq!(
+            rows.iter()
+                .map(|r| (r.0, r.1.as_str(), r.2.as_str(), r.3, r.4))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "0600000", "", 0, 0),
+                (4, "0600041", "大通東", 0, 1),
+                (5, "0600001", "北一条西", 1, 1),
+            ]
+        );
+
+        // 差分が空ならば内容も ID も変わらない
+        repository.apply_diff(&[], &[]).await.unwrap();
+        assert_eq!(repository.count().await.unwrap(), 3);
+        assert_eq!(active_rows().await, rows);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_replace_jigyosyo(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
+
+        // 正しくデータが挿入されているかチェック
+        repository
+            .replace_jigyosyo(&[
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                    business_name: "札幌市役所".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条西".to_string(),
+                    block_address: "２丁目".to_string(),
+                    postal_code: "0608611".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｶﾌﾞｼｷｶﾞｲｼﾔ ﾎﾂｶｲﾄﾞｳｼﾝﾌﾞﾝｼﾔ".to_string(),
+                    business_name: "株式会社　北海道新聞社".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    block_address: "３丁目６".to_string(),
+                    postal_code: "0608711".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+            ])
+            .await
+            .unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jigyosyo")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // 住所の郵便番号には影響しない
+        assert_eq!(repository.count().await.unwrap(), 0);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_search_with_jigyosyo(pool: sqlx::PgPool) {
+        let mut repository =
+            UtfKenAllRepositoryPostgres::new(pool).with_page_token_secret(b"secret");
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        repository
+            .replace_jigyosyo(&[
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                    business_name: "札幌市役所".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条西".to_string(),
+                    block_address: "２丁目".to_string(),
+                    postal_code: "0608611".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｶﾌﾞｼｷｶﾞｲｼﾔ ﾎﾂｶｲﾄﾞｳｼﾝﾌﾞﾝｼﾔ".to_string(),
+                    business_name: "株式会社　北海道新聞社".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    block_address: "３丁目６".to_string(),
+                    postal_code: "0608711".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 住所と事業所が郵便番号順に混ざって返る
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(3),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 残りの事業所を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(3),
+                page_token: response.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_get_by_postal_code(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool);
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "キタ１ジョウヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        repository
+            .replace_jigyosyo(&[JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                business_name: "札幌市役所".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条西".to_string(),
+                block_address: "２丁目".to_string(),
+                postal_code: "0608611".to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            }])
+            .await
+            .unwrap();
+
+        // 郵便番号と完全一致するレコードをすべて返す
+        let records = repository
+            .get_by_postal_code("0600041")
+            .await
+            .unwrap()
+            .records;
+        insta::assert_debug_snapshot!(records);
+
+        // 事業所の個別郵便番号も取得できる
+        let records = repository
+            .get_by_postal_code("0608611")
+            .await
+            .unwrap()
+            .records;
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0], PostalCodeRecord::Business(_)));
+
+        // 前方一致はしない
+        let records = repository.get_by_postal_code("060").await.unwrap().records;
+        assert!(records.is_empty());
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_get_by_postal_codes(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool);
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "キタ１ジョウヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条東".to_string(),
+                    has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
-                    old_postal_code: "064  ".to_string(),
-                    postal_code: "0640820".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
                     prefecture_kana: "ホッカイドウ".to_string(),
                     city_kana: "サッポロシチュウオウク".to_string(),
-                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    town_kana: "オオドオリニシ".to_string(),
                     prefecture: "北海道".to_string(),
                     city: "札幌市中央区".to_string(),
-                    town: "大通西（２０〜２８丁目）".to_string(),
-                    has_multi_postal_code: 1,
+                    town: "大通西".to_string(),
+                    has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
             .unwrap();
-
-        // 完全一致で検索
-        let response = repository
-            .search(UtfKenAllRepositorySearchRequest {
-                postal_code: "0640820",
-                page_size: None,
-                page_token: None,
-            })
+        repository
+            .replace_jigyosyo(&[JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                business_name: "札幌市役所".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条西".to_string(),
+                block_address: "２丁目".to_string(),
+                postal_code: "0608611".to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            }])
             .await
             .unwrap();
-        insta::assert_debug_snapshot!(response);
 
-        // 前方一致で検索
-        let response = repository
-            .search(UtfKenAllRepositorySearchRequest {
-                postal_code: "060",
-                page_size: None,
-                page_token: None,
-            })
+        // 郵便番号順にまとめて返し、存在しない郵便番号や重複は無視する
+        let records = repository
+            .get_by_postal_codes(&[
+                "0608611".to_string(),
+                "0600041".to_string(),
+                "060".to_string(),
+                "0600041".to_string(),
+            ])
             .await
-            .unwrap();
-        insta::assert_debug_snapshot!(response);
+            .unwrap()
+            .records;
+        insta::assert_debug_snapshot!(records);
+
+        let records = repository.get_by_postal_codes(&[]).await.unwrap().records;
+        assert!(records.is_empty());
     }
 
     #[sqlx::test(migrator = "MIGRATOR")]
-    async fn utf_ken_all_repository_postgres_search_page_size_and_page_token(pool: sqlx::PgPool) {
-        let mut repository = UtfKenAllRepositoryPostgres { pool: pool.clone() };
-
-        // サンプルデータを入力
+    async fn utf_ken_all_repository_postgres_list_areas(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool);
         repository
             .replace(&[
                 UtfKenAllRecord {
@@ -572,31 +3015,39 @@ mod tests {
                     postal_code: "0600000".to_string(),
                     prefecture_kana: "ホッカイドウ".to_string(),
                     city_kana: "サッポロシチュウオウク".to_string(),
-                    town_kana: "イカニケイサイガナイバアイ".to_string(),
+                    town_kana: "".to_string(),
                     prefecture: "北海道".to_string(),
                     city: "札幌市中央区".to_string(),
-                    town: "以下に掲載がない場合".to_string(),
+                    town: "".to_string(),
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 0,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
-                    old_postal_code: "064  ".to_string(),
-                    postal_code: "0640941".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
                     prefecture_kana: "ホッカイドウ".to_string(),
                     city_kana: "サッポロシチュウオウク".to_string(),
-                    town_kana: "アサヒガオカ".to_string(),
+                    town_kana: "オオドオリニシ".to_string(),
                     prefecture: "北海道".to_string(),
                     city: "札幌市中央区".to_string(),
-                    town: "旭ケ丘".to_string(),
+                    town: "大通西".to_string(),
                     has_multi_postal_code: 0,
                     has_chome: 0,
-                    has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -610,72 +3061,353 @@ mod tests {
                     town: "大通東".to_string(),
                     has_multi_postal_code: 0,
                     has_chome: 0,
-                    has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
-                    local_government_code: "01101".to_string(),
-                    old_postal_code: "060  ".to_string(),
-                    postal_code: "0600042".to_string(),
+                    local_government_code: "01102".to_string(),
+                    old_postal_code: "001  ".to_string(),
+                    postal_code: "0600807".to_string(),
                     prefecture_kana: "ホッカイドウ".to_string(),
-                    city_kana: "サッポロシチュウオウク".to_string(),
-                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    city_kana: "サッポロシキタク".to_string(),
+                    town_kana: "キタ７ジョウニシ".to_string(),
                     prefecture: "北海道".to_string(),
-                    city: "札幌市中央区".to_string(),
-                    town: "大通西（１〜１９丁目）".to_string(),
-                    has_multi_postal_code: 1,
+                    city: "札幌市北区".to_string(),
+                    town: "北七条西".to_string(),
+                    has_multi_postal_code: 0,
                     has_chome: 0,
-                    has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 都道府県コード順に都道府県を返す
+        let prefectures = repository.list_prefectures().await.unwrap().prefectures;
+        insta::assert_debug_snapshot!(prefectures);
+
+        // 都道府県内の市区町村を全国地方公共団体コード順に返す
+        let cities = repository.list_cities(Some("01")).await.unwrap().cities;
+        insta::assert_debug_snapshot!(cities);
+        assert!(repository
+            .list_cities(Some("47"))
+            .await
+            .unwrap()
+            .cities
+            .is_empty());
+        // 都道府県を省略するとすべての市区町村を返す
+        let cities = repository.list_cities(None).await.unwrap().cities;
+        assert_eq!(
+            cities
+                .iter()
+                .map(|c| c.local_government_code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["01101", "01102", "13103"]
+        );
+
+        // 市区町村内の町域を郵便番号順に返し、町域名が空のものは除く
+        let towns = repository.list_towns("01101").await.unwrap().towns;
+        insta::assert_debug_snapshot!(towns);
+        assert!(repository
+            .list_towns("01103")
+            .await
+            .unwrap()
+            .towns
+            .is_empty());
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_suggest(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050014".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝１丁目".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦１丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ２チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦２丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
-                    old_postal_code: "064  ".to_string(),
-                    postal_code: "0640820".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
                     prefecture_kana: "ホッカイドウ".to_string(),
                     city_kana: "サッポロシチュウオウク".to_string(),
-                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    town_kana: "".to_string(),
                     prefecture: "北海道".to_string(),
                     city: "札幌市中央区".to_string(),
-                    town: "大通西（２０〜２８丁目）".to_string(),
-                    has_multi_postal_code: 1,
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
                     has_chome: 0,
-                    has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "11203".to_string(),
+                    old_postal_code: "333  ".to_string(),
+                    postal_code: "3330866".to_string(),
+                    prefecture_kana: "サイタマケン".to_string(),
+                    city_kana: "カワグチシ".to_string(),
+                    town_kana: "シバ".to_string(),
+                    prefecture: "埼玉県".to_string(),
+                    city: "川口市".to_string(),
+                    town: "芝".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
             .unwrap();
 
-        // 1, 2 件目を取得
-        let response = repository
-            .search(UtfKenAllRepositorySearchRequest {
-                postal_code: "060",
-                page_size: Some(2),
-                page_token: None,
-            })
+        // 候補を郵便番号と町域名の組にして比較する
+        async fn suggest(
+            repository: &UtfKenAllRepositoryPostgres,
+            field: SuggestField,
+            query: &str,
+            limit: usize,
+        ) -> Vec<(String, String)> {
+            repository
+                .suggest(UtfKenAllRepositorySuggestRequest {
+                    field,
+                    query,
+                    limit,
+                })
+                .await
+                .unwrap()
+                .records
+                .into_iter()
+                .map(|r| (r.postal_code, r.town))
+                .collect()
+        }
+        let pair = |postal_code: &str, town: &str| (postal_code.to_string(), town.to_string());
+
+        // 完全一致を優先し、町域名からの前方一致は町域名の短い順に並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "芝", 10).await,
+            vec![
+                pair("3330866", "芝"),
+                pair("1080023", "芝浦"),
+                pair("1050014", "芝１丁目"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "芝浦", 2).await,
+            vec![pair("1080023", "芝浦"), pair("1050023", "芝浦１丁目")]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "東京都港区芝浦", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "札幌市中央区", 10).await,
+            vec![pair("0600000", "")]
+        );
+
+        // 読みでも同じ規則で並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::Kana, "シバウ", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Kana, "ミナトクシバ", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050014", "芝１丁目"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+
+        // 郵便番号は郵便番号順に並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::PostalCode, "1050", 10).await,
+            vec![pair("1050014", "芝１丁目"), pair("1050023", "芝浦１丁目")]
+        );
+
+        // ワイルドカード文字はエスケープされる
+        assert!(suggest(&repository, SuggestField::Address, "%", 10)
+            .await
+            .is_empty());
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_roman(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool);
+        let record = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600041".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "オオドオリヒガシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "大通東".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "HOKKAIDO".to_string(),
+            city_roman: "SAPPORO SHI CHUO KU".to_string(),
+            town_roman: "ODORIHIGASHI".to_string(),
+            town_spec: Default::default(),
+        };
+        repository
+            .replace(std::slice::from_ref(&record))
             .await
             .unwrap();
-        insta::assert_debug_snapshot!(response);
 
-        // 3 件目を取得
+        // ローマ字表記も保存され、検索結果に含まれる
+        let records = repository
+            .get_by_postal_code("0600041")
+            .await
+            .unwrap()
+            .records;
+        assert_eq!(records, vec![PostalCodeRecord::Address(record.clone())]);
         let response = repository
-            .search(UtfKenAllRepositorySearchRequest {
-                postal_code: "060",
-                page_size: Some(2),
-                page_token: response.next_page_token.as_deref(),
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ホッカイドウ",
+                page_size: None,
+                page_token: None,
             })
             .await
             .unwrap();
-        insta::assert_debug_snapshot!(response);
+        assert_eq!(response.records, vec![record]);
     }
 
     #[sqlx::test(migrator = "MIGRATOR")]
     async fn utf_ken_all_repository_postgres_count(pool: sqlx::PgPool) {
-        let mut repository = UtfKenAllRepositoryPostgres { pool: pool.clone() };
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool.clone());
 
         let count = repository.count().await.unwrap();
         assert_eq!(count, 0);
@@ -696,8 +3428,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 0,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -712,8 +3448,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -728,8 +3468,12 @@ mod tests {
                     has_multi_postal_code: 0,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -744,8 +3488,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
                 UtfKenAllRecord {
                     local_government_code: "01101".to_string(),
@@ -760,8 +3508,12 @@ mod tests {
                     has_multi_postal_code: 1,
                     has_chome: 0,
                     has_multi_town: 1,
-                    update_code: 0,
-                    update_reason: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
                 },
             ])
             .await
@@ -770,4 +3522,454 @@ mod tests {
         let count = repository.count().await.unwrap();
         assert_eq!(count, 5);
     }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_versions(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool).with_versions_to_keep(2);
+        let record = |postal_code: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+
+        // まだ何も取り込んでいない
+        assert_eq!(repository.active_version().await.unwrap(), None);
+        assert!(repository.list_versions().await.unwrap().is_empty());
+
+        // 取り込むたびに新しいバージョンを使用中にし、保持する数を超えた古いバージョンは削除する
+        repository.replace(&[record("0600000")]).await.unwrap();
+        repository
+            .replace(&[record("0600000"), record("0600041")])
+            .await
+            .unwrap();
+        repository
+            .apply_diff(&[record("0600042")], &[])
+            .await
+            .unwrap();
+        let versions = repository.list_versions().await.unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| (v.id, v.utf_ken_all_count, v.jigyosyo_count, v.active))
+                .collect::<Vec<_>>(),
+            vec![(3, 3, 0, true), (2, 2, 0, false)]
+        );
+        assert_eq!(repository.active_version().await.unwrap(), Some(3));
+        assert_eq!(repository.count().await.unwrap(), 3);
+
+        // 一つ前のバージョンに戻す
+        assert_eq!(repository.rollback_version().await.unwrap(), 2);
+        assert_eq!(repository.count().await.unwrap(), 2);
+        assert!(matches!(
+            repository.rollback_version().await,
+            Err(UtfKenAllRepositoryError::NoPreviousDatasetVersion)
+        ));
+
+        // 指定したバージョンに切り替える
+        repository.activate_version(3).await.unwrap();
+        assert_eq!(repository.count().await.unwrap(), 3);
+        assert!(matches!(
+            repository.activate_version(1).await,
+            Err(UtfKenAllRepositoryError::DatasetVersionNotFound(1))
+        ));
+
+        // 空のデータでは使用中のバージョンを切り替えない
+        assert!(matches!(
+            repository.replace(&[]).await,
+            Err(UtfKenAllRepositoryError::InvalidDataset(_))
+        ));
+        assert_eq!(repository.active_version().await.unwrap(), Some(3));
+        assert_eq!(repository.list_versions().await.unwrap().len(), 2);
+        assert_eq!(repository.count().await.unwrap(), 3);
+
+        // 戻したバージョンに差分を適用した新しいバージョンは、その後のバージョンの変更を含まない
+        let postal_codes = ["0600000", "0600041", "0600042", "0600043"].map(String::from);
+        let reader = repository.clone();
+        let active_postal_codes = || async {
+            reader
+                .get_by_postal_codes(&postal_codes)
+                .await
+                .unwrap()
+                .records
+                .iter()
+                .map(|r| r.postal_code().to_string())
+                .collect::<Vec<_>>()
+        };
+        repository.rollback_version().await.unwrap();
+        repository
+            .apply_diff(&[record("0600043")], &[record("0600041")])
+            .await
+            .unwrap();
+        assert_eq!(active_postal_codes().await, vec!["0600000", "0600043"]);
+
+        // その後のバージョンの内容は変わらない
+        repository.activate_version(3).await.unwrap();
+        assert_eq!(
+            active_postal_codes().await,
+            vec!["0600000", "0600041", "0600042"]
+        );
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_replace_dataset(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool);
+        let record = |postal_code: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let jigyosyo = |postal_code: &str| JigyosyoRecord {
+            local_government_code: "01101".to_string(),
+            business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+            business_name: "札幌市役所".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            block_address: "２丁目".to_string(),
+            postal_code: postal_code.to_string(),
+            old_postal_code: "060  ".to_string(),
+            handling_office: "札幌中央".to_string(),
+            business_type: 0,
+            multi_number: 0,
+            update_code: 0,
+        };
+        let versions = |versions: Vec<DatasetVersion>| {
+            versions
+                .iter()
+                .map(|v| (v.id, v.utf_ken_all_count, v.jigyosyo_count, v.active))
+                .collect::<Vec<_>>()
+        };
+
+        // 住所と事業所の個別郵便番号をまとめて一つのバージョンにする
+        repository
+            .replace_dataset(
+                Some(&[record("0600000"), record("0600041")]),
+                Some(&[jigyosyo("0608611")]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap()),
+            vec![(1, 2, 1, true)]
+        );
+
+        // 指定しなかったものは使用中のバージョンから引き継ぐ
+        repository
+            .replace_dataset(None, Some(&[jigyosyo("0608611"), jigyosyo("0608711")]))
+            .await
+            .unwrap();
+        repository
+            .replace_dataset(Some(&[record("0600000")]), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap()),
+            vec![(3, 1, 2, true), (2, 2, 2, false), (1, 2, 1, false)]
+        );
+
+        // 一つ前のバージョンに戻すと両方が戻る
+        assert_eq!(repository.rollback_version().await.unwrap(), 2);
+        assert_eq!(repository.rollback_version().await.unwrap(), 1);
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap())[2],
+            (1, 2, 1, true)
+        );
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_history(pool: sqlx::PgPool) {
+        let mut repository = UtfKenAllRepositoryPostgres::new(pool).with_versions_to_keep(1);
+        let record = |postal_code: &str, town: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+
+        // 最初の取り込みでは記録しない
+        repository
+            .replace(&[record("0600000", ""), record("0600041", "大通東")])
+            .await
+            .unwrap();
+        assert!(repository.list_history("0600041").await.unwrap().is_empty());
+
+        // 大通東の町域名カナが変わり、北一条西が追加される（更新の表示だけが変わったものは記録しない）
+        let changed = UtfKenAllRecord {
+            town_kana: "オオドオリヒガシ".to_string(),
+            update_code: UpdateCode::Changed,
+            update_reason: UpdateReason::Correction,
+            ..record("0600041", "大通東")
+        };
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    update_code: UpdateCode::Changed,
+                    ..record("0600000", "")
+                },
+                changed.clone(),
+                record("0600001", "北一条西"),
+            ])
+            .await
+            .unwrap();
+        // 大通東が削除される
+        repository.apply_diff(&[], &[changed]).await.unwrap();
+
+        // 取り込みの新しい順に返し、古いバージョンを削除しても変更履歴は残す
+        let history = repository.list_history("0600041").await.unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| (h.dataset_id, h.change, h.update_code, h.update_reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    PostalCodeChange::Removed,
+                    UpdateCode::Changed,
+                    UpdateReason::Correction
+                ),
+                (
+                    2,
+                    PostalCodeChange::Changed,
+                    UpdateCode::Changed,
+                    UpdateReason::Correction
+                ),
+            ]
+        );
+        assert_eq!(history[0].fields.len(), 16);
+        assert!(history[0].fields.contains(&FieldChange {
+            field: "town".to_string(),
+            old: Some("大通東".to_string()),
+            new: None,
+        }));
+        assert_eq!(
+            history[1].fields,
+            vec![FieldChange {
+                field: "town_kana".to_string(),
+                old: Some("".to_string()),
+                new: Some("オオドオリヒガシ".to_string()),
+            }]
+        );
+        assert!(history[0].id > history[1].id);
+        assert!(history[0].imported_at >= history[1].imported_at);
+        assert_eq!(repository.list_versions().await.unwrap().len(), 1);
+
+        let history = repository.list_history("0600001").await.unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| (h.dataset_id, h.change))
+                .collect::<Vec<_>>(),
+            vec![(2, PostalCodeChange::Added)]
+        );
+        assert!(repository.list_history("0600000").await.unwrap().is_empty());
+    }
+
+    /// ページをたどって `prefix` に前方一致するレコードをすべて集める
+    async fn search_all_pages<R: UtfKenAllRepository>(
+        repository: &R,
+        prefix: &str,
+        page_size: usize,
+    ) -> Vec<PostalCodeRecord> {
+        let mut records = vec![];
+        let mut page_token = None;
+        loop {
+            let response = repository
+                .search(UtfKenAllRepositorySearchRequest {
+                    postal_code: prefix,
+                    page_size: Some(page_size),
+                    page_token: page_token.as_deref(),
+                })
+                .await
+                .unwrap();
+            assert!(response.records.len() <= page_size);
+            records.extend(response.records);
+            match response.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => return records,
+            }
+        }
+    }
+
+    /// ページをたどって読みが `kana` に前方一致するレコードをすべて集める
+    async fn search_by_kana_all_pages<R: UtfKenAllRepository>(
+        repository: &R,
+        kana: &str,
+        page_size: usize,
+    ) -> Vec<UtfKenAllRecord> {
+        let mut records = vec![];
+        let mut page_token = None;
+        loop {
+            let response = repository
+                .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                    kana,
+                    page_size: Some(page_size),
+                    page_token: page_token.as_deref(),
+                })
+                .await
+                .unwrap();
+            assert!(response.records.len() <= page_size);
+            records.extend(response.records);
+            match response.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => return records,
+            }
+        }
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn utf_ken_all_repository_postgres_pagination_matches_ephemeral(pool: sqlx::PgPool) {
+        use crate::infra::ephemeral::UtfKenAllRepositoryEphemeral;
+        use rand::seq::SliceRandom;
+        use rand::{Rng, SeedableRng};
+
+        // 並び順のキー（郵便番号・町域名・町域名カナ）が重複しやすいように少ない候補から選び、
+        // 市区町村名でレコードを区別できるようにする
+        let mut rng = rand::rngs::StdRng::seed_from_u64(20251017);
+        let postal_codes = ["0600000", "0600001", "0600042", "0610000", "0640941"];
+        let towns = [
+            ("", ""),
+            ("大通西", "オオドオリニシ"),
+            ("旭ケ丘", "アサヒガオカ"),
+        ];
+        let mut records = (0..120)
+            .map(|i| {
+                let (town, town_kana) = *towns.choose(&mut rng).unwrap();
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: postal_codes.choose(&mut rng).unwrap().to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシ".to_string(),
+                    town_kana: town_kana.to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: format!("札幌市{i}"),
+                    town: town.to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                }
+            })
+            .collect::<Vec<_>>();
+        records.shuffle(&mut rng);
+        let jigyosyo_records = (0..30)
+            .map(|i| JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "".to_string(),
+                business_name: format!("事業所{i}"),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: towns.choose(&mut rng).unwrap().0.to_string(),
+                block_address: "".to_string(),
+                postal_code: postal_codes.choose(&mut rng).unwrap().to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            })
+            .collect::<Vec<_>>();
+
+        let mut postgres = UtfKenAllRepositoryPostgres::new(pool);
+        postgres.replace(&records).await.unwrap();
+        postgres.replace_jigyosyo(&jigyosyo_records).await.unwrap();
+        let mut ephemeral = UtfKenAllRepositoryEphemeral::default();
+        ephemeral.replace(&records).await.unwrap();
+        ephemeral.replace_jigyosyo(&jigyosyo_records).await.unwrap();
+
+        for prefix in ["", "06", "060", "0600042", "062"] {
+            // 1ページで全件を取得したものを基準にする
+            let expected =
+                search_all_pages(&ephemeral, prefix, records.len() + jigyosyo_records.len()).await;
+            let mut sorted = expected.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), expected.len(), "{prefix}: duplicated records");
+            for page_size in [1, 2, 3, 7, 10, 64] {
+                for _ in 0..2 {
+                    let page_size = page_size + rng.gen_range(0..2);
+                    assert_eq!(
+                        search_all_pages(&postgres, prefix, page_size).await,
+                        expected,
+                        "postgres: {prefix} {page_size}"
+                    );
+                    assert_eq!(
+                        search_all_pages(&ephemeral, prefix, page_size).await,
+                        expected,
+                        "ephemeral: {prefix} {page_size}"
+                    );
+                }
+            }
+        }
+
+        for kana in [
+            "サッポロシ",
+            "ホッカイドウサッポロシオオドオリ",
+            "サッポロシアサヒ",
+        ] {
+            let expected = search_by_kana_all_pages(&ephemeral, kana, records.len()).await;
+            for page_size in [1, 2, 5, 13, 64] {
+                assert_eq!(
+                    search_by_kana_all_pages(&postgres, kana, page_size).await,
+                    expected,
+                    "postgres: {kana} {page_size}"
+                );
+                assert_eq!(
+                    search_by_kana_all_pages(&ephemeral, kana, page_size).await,
+                    expected,
+                    "ephemeral: {kana} {page_size}"
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,250 @@
+use super::history::parse_change;
+use super::page_token::SortKey;
+use crate::repo::{FieldChange, PostalCodeHistory};
+use jp_postal_code_core::model::{
+    JigyosyoRecord, OutOfRangeError, PostalCodeRecord, TownSpec, UpdateCode, UpdateReason,
+    UtfKenAllRecord,
+};
+use sqlx::types::Json;
+
+/// `utf_ken_all` テーブルの行
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct DbUtfKenAllRecord {
+    pub(crate) utf_ken_all_id: i64,
+    pub(crate) local_government_code: String,
+    pub(crate) old_postal_code: String,
+    pub(crate) postal_code: String,
+    pub(crate) prefecture_kana: String,
+    pub(crate) city_kana: String,
+    pub(crate) town_kana: String,
+    pub(crate) prefecture: String,
+    pub(crate) city: String,
+    pub(crate) town: String,
+    pub(crate) has_multi_postal_code: i16,
+    pub(crate) has_chome: i16,
+    pub(crate) has_multi_town: i16,
+    pub(crate) update_code: UpdateCode,
+    pub(crate) update_reason: UpdateReason,
+    pub(crate) prefecture_roman: String,
+    pub(crate) city_roman: String,
+    pub(crate) town_roman: String,
+    pub(crate) town_spec: Json<TownSpec>,
+}
+
+impl DbUtfKenAllRecord {
+    /// 検索結果の並び順のキー
+    pub(crate) fn sort_key(&self) -> SortKey {
+        SortKey {
+            postal_code: self.postal_code.clone(),
+            kind: 0,
+            town: self.town.clone(),
+            town_kana: self.town_kana.clone(),
+            id: self.utf_ken_all_id,
+        }
+    }
+}
+
+impl From<DbUtfKenAllRecord> for UtfKenAllRecord {
+    fn from(record: DbUtfKenAllRecord) -> Self {
+        UtfKenAllRecord {
+            local_government_code: record.local_government_code,
+            old_postal_code: record.old_postal_code,
+            postal_code: record.postal_code,
+            prefecture_kana: record.prefecture_kana,
+            city_kana: record.city_kana,
+            town_kana: record.town_kana,
+            prefecture: record.prefecture,
+            city: record.city,
+            town: record.town,
+            has_multi_postal_code: record.has_multi_postal_code,
+            has_chome: record.has_chome,
+            has_multi_town: record.has_multi_town,
+            update_code: record.update_code,
+            update_reason: record.update_reason,
+            prefecture_roman: record.prefecture_roman,
+            city_roman: record.city_roman,
+            town_roman: record.town_roman,
+            town_spec: record.town_spec.0,
+        }
+    }
+}
+
+/// `jigyosyo` テーブルの行
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct DbJigyosyoRecord {
+    pub(crate) jigyosyo_id: i64,
+    pub(crate) local_government_code: String,
+    pub(crate) business_name_kana: String,
+    pub(crate) business_name: String,
+    pub(crate) prefecture: String,
+    pub(crate) city: String,
+    pub(crate) town: String,
+    pub(crate) block_address: String,
+    pub(crate) postal_code: String,
+    pub(crate) old_postal_code: String,
+    pub(crate) handling_office: String,
+    pub(crate) business_type: i16,
+    pub(crate) multi_number: i16,
+    pub(crate) update_code: i16,
+}
+
+impl From<DbJigyosyoRecord> for JigyosyoRecord {
+    fn from(record: DbJigyosyoRecord) -> Self {
+        JigyosyoRecord {
+            local_government_code: record.local_government_code,
+            business_name_kana: record.business_name_kana,
+            business_name: record.business_name,
+            prefecture: record.prefecture,
+            city: record.city,
+            town: record.town,
+            block_address: record.block_address,
+            postal_code: record.postal_code,
+            old_postal_code: record.old_postal_code,
+            handling_office: record.handling_office,
+            business_type: record.business_type,
+            multi_number: record.multi_number,
+            update_code: record.update_code,
+        }
+    }
+}
+
+/// 住所の郵便番号と事業所の個別郵便番号をまとめて検索した結果
+///
+/// `kind` が 0 なら住所、1 なら事業所のレコードを表す。
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct DbPostalCodeRecord {
+    pub(crate) kind: i16,
+    pub(crate) record_id: i64,
+    pub(crate) local_government_code: String,
+    pub(crate) old_postal_code: String,
+    pub(crate) postal_code: String,
+    pub(crate) prefecture_kana: String,
+    pub(crate) city_kana: String,
+    pub(crate) town_kana: String,
+    pub(crate) prefecture: String,
+    pub(crate) city: String,
+    pub(crate) town: String,
+    pub(crate) has_multi_postal_code: i16,
+    pub(crate) has_chome: i16,
+    pub(crate) has_multi_town: i16,
+    pub(crate) update_code: i16,
+    pub(crate) update_reason: i16,
+    pub(crate) prefecture_roman: String,
+    pub(crate) city_roman: String,
+    pub(crate) town_roman: String,
+    pub(crate) business_name_kana: String,
+    pub(crate) business_name: String,
+    pub(crate) block_address: String,
+    pub(crate) handling_office: String,
+    pub(crate) business_type: i16,
+    pub(crate) multi_number: i16,
+    pub(crate) town_spec: Json<TownSpec>,
+}
+
+impl DbPostalCodeRecord {
+    /// 検索結果の並び順のキー
+    pub(crate) fn sort_key(&self) -> SortKey {
+        SortKey {
+            postal_code: self.postal_code.clone(),
+            kind: self.kind,
+            town: self.town.clone(),
+            town_kana: self.town_kana.clone(),
+            id: self.record_id,
+        }
+    }
+}
+
+impl TryFrom<DbPostalCodeRecord> for PostalCodeRecord {
+    type Error = sqlx::Error;
+
+    /// 住所のレコードの更新の表示・更新理由が範囲外なら [sqlx::Error::Decode] を返す
+    fn try_from(record: DbPostalCodeRecord) -> Result<Self, Self::Error> {
+        let decode_error = |e: OutOfRangeError| sqlx::Error::Decode(Box::new(e));
+        let record = if record.kind == 0 {
+            PostalCodeRecord::Address(UtfKenAllRecord {
+                local_government_code: record.local_government_code,
+                old_postal_code: record.old_postal_code,
+                postal_code: record.postal_code,
+                prefecture_kana: record.prefecture_kana,
+                city_kana: record.city_kana,
+                town_kana: record.town_kana,
+                prefecture: record.prefecture,
+                city: record.city,
+                town: record.town,
+                has_multi_postal_code: record.has_multi_postal_code,
+                has_chome: record.has_chome,
+                has_multi_town: record.has_multi_town,
+                update_code: UpdateCode::try_from(record.update_code).map_err(decode_error)?,
+                update_reason: UpdateReason::try_from(record.update_reason)
+                    .map_err(decode_error)?,
+                prefecture_roman: record.prefecture_roman,
+                city_roman: record.city_roman,
+                town_roman: record.town_roman,
+                town_spec: record.town_spec.0,
+            })
+        } else {
+            PostalCodeRecord::Business(JigyosyoRecord {
+                local_government_code: record.local_government_code,
+                business_name_kana: record.business_name_kana,
+                business_name: record.business_name,
+                prefecture: record.prefecture,
+                city: record.city,
+                town: record.town,
+                block_address: record.block_address,
+                postal_code: record.postal_code,
+                old_postal_code: record.old_postal_code,
+                handling_office: record.handling_office,
+                business_type: record.business_type,
+                multi_number: record.multi_number,
+                update_code: record.update_code,
+            })
+        };
+        Ok(record)
+    }
+}
+
+/// `postal_code_history` テーブルの行
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct DbPostalCodeHistory {
+    pub(crate) postal_code_history_id: i64,
+    pub(crate) dataset_id: i64,
+    pub(crate) postal_code: String,
+    pub(crate) change: String,
+    pub(crate) fields: Json<Vec<FieldChange>>,
+    pub(crate) update_code: UpdateCode,
+    pub(crate) update_reason: UpdateReason,
+    pub(crate) imported_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<DbPostalCodeHistory> for PostalCodeHistory {
+    type Error = sqlx::Error;
+
+    /// 変更の種類が解釈できなければ [sqlx::Error::Decode] を返す
+    fn try_from(history: DbPostalCodeHistory) -> Result<Self, Self::Error> {
+        Ok(PostalCodeHistory {
+            id: history.postal_code_history_id,
+            postal_code: history.postal_code,
+            change: parse_change(&history.change)?,
+            fields: history.fields.0,
+            update_code: history.update_code,
+            update_reason: history.update_reason,
+            dataset_id: history.dataset_id,
+            imported_at: history.imported_at,
+        })
+    }
+}
+
+/// PostgreSQL の `LIKE` 句のワイルドカード文字をエスケープする
+pub(crate) fn escape_like(s: &str) -> String {
+    s.replace('\\', r"\\")
+        .replace('%', r"\%")
+        .replace('_', r"\_")
+}
+
+/// 前方一致を `prefix <= 列 AND 列 < 上限` の範囲の条件に置き換えるための上限を返す
+///
+/// SQLite は `LIKE` の前方一致に索引を使わないので、索引の使える範囲の条件で絞り込む。
+/// U+10FFFF（私用面の最後の文字）が続く文字列は範囲から外れるが、郵便番号データには現れない。
+pub(crate) fn prefix_upper_bound(prefix: &str) -> String {
+    format!("{prefix}{}", char::MAX)
+}
@@ -0,0 +1,3643 @@
+use crate::repo::{
+    DatasetVersion, PostalCodeHistory, SuggestField, UtfKenAllRepository, UtfKenAllRepositoryError,
+    UtfKenAllRepositoryGetResponse, UtfKenAllRepositoryListCitiesResponse,
+    UtfKenAllRepositoryListPrefecturesResponse, UtfKenAllRepositoryListTownsResponse,
+    UtfKenAllRepositorySearchByAddressRequest, UtfKenAllRepositorySearchByAddressResponse,
+    UtfKenAllRepositorySearchByKanaRequest, UtfKenAllRepositorySearchByKanaResponse,
+    UtfKenAllRepositorySearchRequest, UtfKenAllRepositorySearchResponse,
+    UtfKenAllRepositorySuggestRequest, UtfKenAllRepositorySuggestResponse,
+    DEFAULT_DATASET_VERSIONS_TO_KEEP, DEFAULT_SEARCH_PAGE_SIZE,
+};
+use jp_postal_code_core::model::{
+    City, JigyosyoRecord, PostalCodeRecord, Prefecture, Town, UtfKenAllRecord,
+};
+use sqlx::types::Json;
+
+use super::dataset::{self, DatasetCounts, DatasetStore};
+use super::history::{change_name, RecordChange};
+use super::page_token::PageTokenSigner;
+use super::row::{
+    prefix_upper_bound, DbJigyosyoRecord, DbPostalCodeHistory, DbPostalCodeRecord,
+    DbUtfKenAllRecord,
+};
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySqlite {
+    pool: sqlx::SqlitePool,
+    versions_to_keep: usize,
+    signer: PageTokenSigner,
+}
+
+impl UtfKenAllRepositorySqlite {
+    pub fn new(pool: sqlx::SqlitePool) -> Self {
+        Self {
+            pool,
+            versions_to_keep: DEFAULT_DATASET_VERSIONS_TO_KEEP,
+            signer: PageTokenSigner::default(),
+        }
+    }
+
+    /// 使用中のものを含めて保持するバージョンの数を指定する（1 未満は 1 とみなす）
+    pub fn with_versions_to_keep(self, versions_to_keep: usize) -> Self {
+        Self {
+            versions_to_keep,
+            ..self
+        }
+    }
+
+    /// ページトークンの署名に使う鍵を指定する
+    ///
+    /// 指定しない場合はプロセスごとに乱数で生成する。
+    pub fn with_page_token_secret(self, secret: &[u8]) -> Self {
+        Self {
+            signer: PageTokenSigner::new(secret),
+            ..self
+        }
+    }
+}
+
+/// 検索に使うトランザクションを始め、同じトランザクションで使用中のバージョンの ID を読む
+///
+/// 検索結果、ページトークン、応答に含めるバージョンが同じ時点のデータベースを指すようにする。
+async fn begin_snapshot(
+    pool: &sqlx::SqlitePool,
+) -> Result<(sqlx::Transaction<'static, sqlx::Sqlite>, Option<i64>), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let dataset_version = tx.active_dataset_id().await?;
+    Ok((tx, dataset_version))
+}
+
+// https://www.sqlite.org/limits.html#max_variable_number
+const BIND_LIMIT: usize = 32766;
+
+impl DatasetStore for sqlx::SqliteConnection {
+    async fn active_dataset_id(&mut self) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar("SELECT dataset_id FROM active_dataset")
+            .fetch_one(self)
+            .await
+    }
+
+    async fn oldest_dataset_id(&mut self) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar("SELECT min(dataset_id) FROM dataset")
+            .fetch_one(self)
+            .await
+    }
+
+    async fn create_dataset(&mut self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO dataset (utf_ken_all_count, jigyosyo_count, created_at)
+            VALUES (0, 0, ?1)
+            RETURNING dataset_id
+            "#,
+        )
+        .bind(chrono::Utc::now())
+        .fetch_one(self)
+        .await
+    }
+
+    async fn rebase(&mut self, base: Option<i64>, dataset_id: i64) -> Result<(), sqlx::Error> {
+        let base = base.unwrap_or(0);
+        sqlx::query("UPDATE utf_ken_all SET valid_to = ?2 WHERE valid_to > ?2 AND valid_from > ?1")
+            .bind(base)
+            .bind(dataset_id)
+            .execute(&mut *self)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO utf_ken_all (
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec,
+                updated_at,
+                valid_from
+            )
+            SELECT
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec,
+                updated_at,
+                ?2
+            FROM utf_ken_all
+            WHERE valid_from <= ?1 AND valid_to > ?1 AND valid_to <= ?2
+            ORDER BY utf_ken_all_id
+            "#,
+        )
+        .bind(base)
+        .bind(dataset_id)
+        .execute(&mut *self)
+        .await?;
+        sqlx::query("UPDATE jigyosyo SET valid_to = ?2 WHERE valid_to > ?2 AND valid_from > ?1")
+            .bind(base)
+            .bind(dataset_id)
+            .execute(&mut *self)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO jigyosyo (
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code,
+                updated_at,
+                valid_from
+            )
+            SELECT
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code,
+                updated_at,
+                ?2
+            FROM jigyosyo
+            WHERE valid_from <= ?1 AND valid_to > ?1 AND valid_to <= ?2
+            ORDER BY jigyosyo_id
+            "#,
+        )
+        .bind(base)
+        .bind(dataset_id)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+    ) -> Result<Vec<(i64, UtfKenAllRecord)>, sqlx::Error> {
+        let records = sqlx::query_as::<_, DbUtfKenAllRecord>(
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec
+            FROM utf_ken_all
+            WHERE valid_from <= ?1 AND valid_to > ?1
+            ORDER BY utf_ken_all_id
+            "#,
+        )
+        .bind(dataset_id)
+        .fetch_all(self)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|r| (r.utf_ken_all_id, r.into()))
+            .collect())
+    }
+
+    async fn list_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+    ) -> Result<Vec<(i64, JigyosyoRecord)>, sqlx::Error> {
+        let records = sqlx::query_as::<_, DbJigyosyoRecord>(
+            r#"
+            SELECT
+                jigyosyo_id,
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code
+            FROM jigyosyo
+            WHERE valid_from <= ?1 AND valid_to > ?1
+            ORDER BY jigyosyo_id
+            "#,
+        )
+        .bind(dataset_id)
+        .fetch_all(self)
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(|r| (r.jigyosyo_id, r.into()))
+            .collect())
+    }
+
+    async fn retire_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        ids: &[i64],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE utf_ken_all SET valid_to = ?1
+            WHERE utf_ken_all_id IN (SELECT value FROM json_each(?2))
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(Json(ids))
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn retire_jigyosyo(&mut self, dataset_id: i64, ids: &[i64]) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE jigyosyo SET valid_to = ?1
+            WHERE jigyosyo_id IN (SELECT value FROM json_each(?2))
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(Json(ids))
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn retire_matching_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        records: &[UtfKenAllRecord],
+    ) -> Result<u64, sqlx::Error> {
+        let records = records
+            .iter()
+            .map(|r| {
+                [
+                    &r.local_government_code,
+                    &r.postal_code,
+                    &r.prefecture,
+                    &r.city,
+                    &r.town,
+                ]
+            })
+            .collect::<Vec<_>>();
+        let result = sqlx::query(
+            r#"
+            UPDATE utf_ken_all SET valid_to = ?1
+            WHERE valid_to > ?1
+                AND EXISTS (
+                    SELECT 1 FROM json_each(?2) AS deleted
+                    WHERE deleted.value ->> 0 = local_government_code
+                        AND deleted.value ->> 1 = postal_code
+                        AND deleted.value ->> 2 = prefecture
+                        AND deleted.value ->> 3 = city
+                        AND deleted.value ->> 4 = town
+                )
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(Json(records))
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        records: &[UtfKenAllRecord],
+        updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut query_builder: sqlx::QueryBuilder<sqlx::Sqlite> = sqlx::QueryBuilder::new(
+            r#"
+            INSERT INTO utf_ken_all (
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec,
+                updated_at,
+                valid_from
+            ) "#,
+        );
+        for chunk in records.chunks(BIND_LIMIT / 20) {
+            query_builder.reset();
+            query_builder.push_values(chunk, |mut b, r| {
+                b.push_bind(r.local_government_code.to_owned())
+                    .push_bind(r.old_postal_code.to_owned())
+                    .push_bind(r.postal_code.to_owned())
+                    .push_bind(r.prefecture_kana.to_owned())
+                    .push_bind(r.city_kana.to_owned())
+                    .push_bind(r.town_kana.to_owned())
+                    .push_bind(r.prefecture.to_owned())
+                    .push_bind(r.city.to_owned())
+                    .push_bind(r.town.to_owned())
+                    .push_bind(r.has_multi_postal_code)
+                    .push_bind(r.has_chome)
+                    .push_bind(r.has_multi_town)
+                    .push_bind(r.update_code)
+                    .push_bind(r.update_reason)
+                    .push_bind(r.prefecture_roman.to_owned())
+                    .push_bind(r.city_roman.to_owned())
+                    .push_bind(r.town_roman.to_owned())
+                    .push_bind(Json(r.town_spec.clone()))
+                    .push_bind(updated_at)
+                    .push_bind(dataset_id);
+            });
+            query_builder.build().execute(&mut *self).await?;
+        }
+        Ok(())
+    }
+
+    async fn insert_jigyosyo(
+        &mut self,
+        dataset_id: i64,
+        records: &[JigyosyoRecord],
+        updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut query_builder: sqlx::QueryBuilder<sqlx::Sqlite> = sqlx::QueryBuilder::new(
+            r#"
+            INSERT INTO jigyosyo (
+                local_government_code,
+                business_name_kana,
+                business_name,
+                prefecture,
+                city,
+                town,
+                block_address,
+                postal_code,
+                old_postal_code,
+                handling_office,
+                business_type,
+                multi_number,
+                update_code,
+                updated_at,
+                valid_from
+            ) "#,
+        );
+        for chunk in records.chunks(BIND_LIMIT / 15) {
+            query_builder.reset();
+            query_builder.push_values(chunk, |mut b, r| {
+                b.push_bind(r.local_government_code.to_owned())
+                    .push_bind(r.business_name_kana.to_owned())
+                    .push_bind(r.business_name.to_owned())
+                    .push_bind(r.prefecture.to_owned())
+                    .push_bind(r.city.to_owned())
+                    .push_bind(r.town.to_owned())
+                    .push_bind(r.block_address.to_owned())
+                    .push_bind(r.postal_code.to_owned())
+                    .push_bind(r.old_postal_code.to_owned())
+                    .push_bind(r.handling_office.to_owned())
+                    .push_bind(r.business_type)
+                    .push_bind(r.multi_number)
+                    .push_bind(r.update_code)
+                    .push_bind(updated_at)
+                    .push_bind(dataset_id);
+            });
+            query_builder.build().execute(&mut *self).await?;
+        }
+        Ok(())
+    }
+
+    async fn recorded_counts(&mut self, dataset_id: i64) -> Result<DatasetCounts, sqlx::Error> {
+        let (utf_ken_all, jigyosyo) = sqlx::query_as(
+            "SELECT utf_ken_all_count, jigyosyo_count FROM dataset WHERE dataset_id = ?1",
+        )
+        .bind(dataset_id)
+        .fetch_one(self)
+        .await?;
+        Ok(DatasetCounts {
+            utf_ken_all,
+            jigyosyo,
+        })
+    }
+
+    async fn count_records(&mut self, dataset_id: i64) -> Result<DatasetCounts, sqlx::Error> {
+        let (utf_ken_all, jigyosyo) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM utf_ken_all WHERE valid_from <= ?1 AND valid_to > ?1),
+                (SELECT COUNT(*) FROM jigyosyo WHERE valid_from <= ?1 AND valid_to > ?1)
+            "#,
+        )
+        .bind(dataset_id)
+        .fetch_one(self)
+        .await?;
+        Ok(DatasetCounts {
+            utf_ken_all,
+            jigyosyo,
+        })
+    }
+
+    async fn activate_dataset(
+        &mut self,
+        dataset_id: i64,
+        counts: DatasetCounts,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE dataset SET utf_ken_all_count = ?2, jigyosyo_count = ?3 WHERE dataset_id = ?1",
+        )
+        .bind(dataset_id)
+        .bind(counts.utf_ken_all)
+        .bind(counts.jigyosyo)
+        .execute(&mut *self)
+        .await?;
+        sqlx::query("UPDATE active_dataset SET dataset_id = ?1")
+            .bind(dataset_id)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_expired_datasets(
+        &mut self,
+        versions_to_keep: usize,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let expired: Vec<i64> = sqlx::query_scalar(
+            "SELECT dataset_id FROM dataset ORDER BY dataset_id DESC LIMIT -1 OFFSET ?1",
+        )
+        .bind(versions_to_keep as i64)
+        .fetch_all(&mut *self)
+        .await?;
+        sqlx::query("DELETE FROM dataset WHERE dataset_id IN (SELECT value FROM json_each(?1))")
+            .bind(Json(&expired))
+            .execute(&mut *self)
+            .await?;
+        // 残ったどのバージョンにも含まれないレコード
+        for table in ["utf_ken_all", "jigyosyo"] {
+            sqlx::query(&format!(
+                "DELETE FROM {table} WHERE valid_to <= (SELECT min(dataset_id) FROM dataset)"
+            ))
+            .execute(&mut *self)
+            .await?;
+        }
+        Ok(expired)
+    }
+
+    async fn changed_postal_codes(&mut self, dataset_id: i64) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT DISTINCT postal_code FROM utf_ken_all WHERE valid_from = ?1 OR valid_to = ?1",
+        )
+        .bind(dataset_id)
+        .fetch_all(self)
+        .await
+    }
+
+    async fn find_utf_ken_all(
+        &mut self,
+        dataset_id: i64,
+        postal_codes: &[String],
+    ) -> Result<Vec<UtfKenAllRecord>, sqlx::Error> {
+        let records = sqlx::query_as::<_, DbUtfKenAllRecord>(
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec
+            FROM utf_ken_all
+            WHERE valid_from <= ?1 AND valid_to > ?1
+                AND postal_code IN (SELECT value FROM json_each(?2))
+            ORDER BY utf_ken_all_id
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(Json(postal_codes))
+        .fetch_all(self)
+        .await?;
+        Ok(records.into_iter().map(UtfKenAllRecord::from).collect())
+    }
+
+    async fn insert_history(
+        &mut self,
+        dataset_id: i64,
+        changes: &[RecordChange],
+        imported_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut query_builder: sqlx::QueryBuilder<sqlx::Sqlite> = sqlx::QueryBuilder::new(
+            r#"
+            INSERT INTO postal_code_history (
+                dataset_id,
+                postal_code,
+                change,
+                fields,
+                update_code,
+                update_reason,
+                imported_at
+            ) "#,
+        );
+        for chunk in changes.chunks(BIND_LIMIT / 7) {
+            query_builder.reset();
+            query_builder.push_values(chunk, |mut b, c| {
+                b.push_bind(dataset_id)
+                    .push_bind(c.postal_code.to_owned())
+                    .push_bind(change_name(c.change))
+                    .push_bind(Json(c.fields.clone()))
+                    .push_bind(c.update_code)
+                    .push_bind(c.update_reason)
+                    .push_bind(imported_at);
+            });
+            query_builder.build().execute(&mut *self).await?;
+        }
+        Ok(())
+    }
+}
+
+impl UtfKenAllRepository for UtfKenAllRepositorySqlite {
+    type Error = UtfKenAllRepositoryError;
+
+    #[tracing::instrument(skip(self, added, deleted))]
+    async fn apply_diff(
+        &mut self,
+        added: &[UtfKenAllRecord],
+        deleted: &[UtfKenAllRecord],
+    ) -> Result<(), Self::Error> {
+        tracing::info!(
+            added = added.len(),
+            deleted = deleted.len(),
+            "Start applying diff to utf_ken_all table"
+        );
+        let mut tx = self.pool.begin().await?;
+        let dataset_id =
+            dataset::apply_diff(&mut *tx, added, deleted, self.versions_to_keep).await?;
+        tx.commit().await?;
+        tracing::info!(dataset_id, "Finish applying diff to utf_ken_all table");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, records, jigyosyo_records))]
+    async fn replace_dataset(
+        &mut self,
+        records: Option<&[UtfKenAllRecord]>,
+        jigyosyo_records: Option<&[JigyosyoRecord]>,
+    ) -> Result<(), Self::Error> {
+        tracing::info!(
+            count = records.map(|r| r.len()),
+            jigyosyo_count = jigyosyo_records.map(|r| r.len()),
+            "Start inserting records into utf_ken_all and jigyosyo table"
+        );
+        let mut tx = self.pool.begin().await?;
+        let dataset_id =
+            dataset::replace_dataset(&mut *tx, records, jigyosyo_records, self.versions_to_keep)
+                .await?;
+        tx.commit().await?;
+        tracing::info!(
+            dataset_id,
+            "Finish inserting records into utf_ken_all and jigyosyo table"
+        );
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search(
+        &self,
+        req: UtfKenAllRepositorySearchRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchResponse, Self::Error> {
+        let postal_code = req.postal_code;
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        tracing::info!(
+            %postal_code,
+            "Start finding records from utf_ken_all and jigyosyo table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let version = dataset_version.unwrap_or(0);
+        let query = format!("postal_code:{}", postal_code);
+        let oldest = tx.oldest_dataset_id().await?.unwrap_or(0);
+        let after = self.signer.verify_after(req.page_token, &query, oldest)?;
+        // 住所と事業所を郵便番号順に混ぜる（同じ郵便番号なら住所が先）
+        let mut records = sqlx::query_as::<_, DbPostalCodeRecord>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    0 AS kind,
+                    utf_ken_all_id AS record_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    prefecture_kana,
+                    city_kana,
+                    town_kana,
+                    prefecture,
+                    city,
+                    town,
+                    has_multi_postal_code,
+                    has_chome,
+                    has_multi_town,
+                    update_code,
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    '' AS business_name_kana,
+                    '' AS business_name,
+                    '' AS block_address,
+                    '' AS handling_office,
+                    0 AS business_type,
+                    0 AS multi_number,
+                    town_spec
+                FROM utf_ken_all
+                WHERE postal_code >= ?1 AND postal_code < ?9
+                    AND valid_from <= ?8 AND valid_to > ?8
+                UNION ALL
+                SELECT
+                    1,
+                    jigyosyo_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    '',
+                    '',
+                    '',
+                    prefecture,
+                    city,
+                    town,
+                    0,
+                    0,
+                    0,
+                    update_code,
+                    0,
+                    '',
+                    '',
+                    '',
+                    business_name_kana,
+                    business_name,
+                    block_address,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    '{}'
+                FROM jigyosyo
+                WHERE postal_code >= ?1 AND postal_code < ?9
+                    AND valid_from <= ?8 AND valid_to > ?8
+            ) AS records
+            WHERE ?3 IS NULL
+                OR (postal_code, kind, town, town_kana, record_id) > (?3, ?4, ?5, ?6, ?7)
+            ORDER BY postal_code, kind, town, town_kana, record_id
+            LIMIT ?2
+            "#,
+        )
+        .bind(postal_code)
+        .bind((page_size + 1) as i64)
+        .bind(after.as_ref().map(|k| k.postal_code.as_str()))
+        .bind(after.as_ref().map(|k| k.kind))
+        .bind(after.as_ref().map(|k| k.town.as_str()))
+        .bind(after.as_ref().map(|k| k.town_kana.as_str()))
+        .bind(after.as_ref().map(|k| k.id))
+        .bind(dataset_version)
+        .bind(prefix_upper_bound(postal_code))
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding records from utf_ken_all and jigyosyo table"
+        );
+        let next_page_token = self.signer.next_page_token(
+            &mut records,
+            page_size,
+            &query,
+            version,
+            DbPostalCodeRecord::sort_key,
+        );
+        let records = records
+            .into_iter()
+            .map(PostalCodeRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UtfKenAllRepositorySearchResponse {
+            next_page_token,
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_by_postal_code(
+        &self,
+        postal_code: &str,
+    ) -> Result<UtfKenAllRepositoryGetResponse, Self::Error> {
+        tracing::info!(
+            %postal_code,
+            "Start getting records from utf_ken_all and jigyosyo table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        // 住所を先に、事業所を後に返す
+        let records = sqlx::query_as::<_, DbPostalCodeRecord>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    0 AS kind,
+                    utf_ken_all_id AS record_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    prefecture_kana,
+                    city_kana,
+                    town_kana,
+                    prefecture,
+                    city,
+                    town,
+                    has_multi_postal_code,
+                    has_chome,
+                    has_multi_town,
+                    update_code,
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    '' AS business_name_kana,
+                    '' AS business_name,
+                    '' AS block_address,
+                    '' AS handling_office,
+                    0 AS business_type,
+                    0 AS multi_number,
+                    town_spec
+                FROM utf_ken_all
+                WHERE postal_code = ?1
+                    AND valid_from <= ?2 AND valid_to > ?2
+                UNION ALL
+                SELECT
+                    1,
+                    jigyosyo_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    '',
+                    '',
+                    '',
+                    prefecture,
+                    city,
+                    town,
+                    0,
+                    0,
+                    0,
+                    update_code,
+                    0,
+                    '',
+                    '',
+                    '',
+                    business_name_kana,
+                    business_name,
+                    block_address,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    '{}'
+                FROM jigyosyo
+                WHERE postal_code = ?1
+                    AND valid_from <= ?2 AND valid_to > ?2
+            ) AS records
+            ORDER BY kind, town, town_kana, record_id
+            "#,
+        )
+        .bind(postal_code)
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish getting records from utf_ken_all and jigyosyo table"
+        );
+        let records = records
+            .into_iter()
+            .map(PostalCodeRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UtfKenAllRepositoryGetResponse {
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self, postal_codes))]
+    async fn get_by_postal_codes(
+        &self,
+        postal_codes: &[String],
+    ) -> Result<UtfKenAllRepositoryGetResponse, Self::Error> {
+        tracing::info!(
+            count = postal_codes.len(),
+            "Start getting records of postal codes from utf_ken_all and jigyosyo table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        // 郵便番号の一覧は JSON 配列として1つのパラメータで渡す
+        // 郵便番号ごとに住所を先に、事業所を後に返す
+        let records = sqlx::query_as::<_, DbPostalCodeRecord>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    0 AS kind,
+                    utf_ken_all_id AS record_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    prefecture_kana,
+                    city_kana,
+                    town_kana,
+                    prefecture,
+                    city,
+                    town,
+                    has_multi_postal_code,
+                    has_chome,
+                    has_multi_town,
+                    update_code,
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    '' AS business_name_kana,
+                    '' AS business_name,
+                    '' AS block_address,
+                    '' AS handling_office,
+                    0 AS business_type,
+                    0 AS multi_number,
+                    town_spec
+                FROM utf_ken_all
+                WHERE postal_code IN (SELECT value FROM json_each(?1))
+                    AND valid_from <= ?2 AND valid_to > ?2
+                UNION ALL
+                SELECT
+                    1,
+                    jigyosyo_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    '',
+                    '',
+                    '',
+                    prefecture,
+                    city,
+                    town,
+                    0,
+                    0,
+                    0,
+                    update_code,
+                    0,
+                    '',
+                    '',
+                    '',
+                    business_name_kana,
+                    business_name,
+                    block_address,
+                    handling_office,
+                    business_type,
+                    multi_number,
+                    '{}'
+                FROM jigyosyo
+                WHERE postal_code IN (SELECT value FROM json_each(?1))
+                    AND valid_from <= ?2 AND valid_to > ?2
+            ) AS records
+            ORDER BY postal_code, kind, town, town_kana, record_id
+            "#,
+        )
+        .bind(serde_json::to_string(postal_codes).unwrap())
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish getting records of postal codes from utf_ken_all and jigyosyo table"
+        );
+        let records = records
+            .into_iter()
+            .map(PostalCodeRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UtfKenAllRepositoryGetResponse {
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search_by_address(
+        &self,
+        req: UtfKenAllRepositorySearchByAddressRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchByAddressResponse, Self::Error> {
+        let address = req.address;
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        tracing::info!(
+            %address,
+            "Start finding records by address from utf_ken_all table"
+        );
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let records = sqlx::query_as::<_, DbUtfKenAllRecord>(
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec
+            FROM utf_ken_all
+            WHERE ((prefecture || city || town) >= ?1 AND (prefecture || city || town) < ?2
+                    OR (city || town) >= ?1 AND (city || town) < ?2)
+                AND valid_from <= ?4 AND valid_to > ?4
+            ORDER BY
+                CASE WHEN (prefecture || city || town) = ?1 OR (city || town) = ?1 THEN 0 ELSE 1 END,
+                postal_code,
+                town,
+                town_kana
+            LIMIT ?3
+            "#,
+        )
+        .bind(address)
+        .bind(prefix_upper_bound(address))
+        .bind(page_size as i64)
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding records by address from utf_ken_all table"
+        );
+        let records = records
+            .into_iter()
+            .map(UtfKenAllRecord::from)
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySearchByAddressResponse {
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search_by_kana(
+        &self,
+        req: UtfKenAllRepositorySearchByKanaRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySearchByKanaResponse, Self::Error> {
+        let kana = req.kana;
+        let page_size = req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        tracing::info!(%kana, "Start finding records by kana from utf_ken_all table");
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let version = dataset_version.unwrap_or(0);
+        let query = format!("kana:{}", kana);
+        let oldest = tx.oldest_dataset_id().await?.unwrap_or(0);
+        let after = self.signer.verify_after(req.page_token, &query, oldest)?;
+        let mut records = sqlx::query_as::<_, DbUtfKenAllRecord>(
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec
+            FROM utf_ken_all
+            WHERE ((prefecture_kana || city_kana || town_kana) >= ?1
+                    AND (prefecture_kana || city_kana || town_kana) < ?8
+                    OR (city_kana || town_kana) >= ?1 AND (city_kana || town_kana) < ?8)
+                AND (?3 IS NULL
+                    OR (postal_code, town, town_kana, utf_ken_all_id) > (?3, ?4, ?5, ?6))
+                AND valid_from <= ?7 AND valid_to > ?7
+            ORDER BY postal_code, town, town_kana, utf_ken_all_id
+            LIMIT ?2
+            "#,
+        )
+        .bind(kana)
+        .bind((page_size + 1) as i64)
+        .bind(after.as_ref().map(|k| k.postal_code.as_str()))
+        .bind(after.as_ref().map(|k| k.town.as_str()))
+        .bind(after.as_ref().map(|k| k.town_kana.as_str()))
+        .bind(after.as_ref().map(|k| k.id))
+        .bind(dataset_version)
+        .bind(prefix_upper_bound(kana))
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding records by kana from utf_ken_all table"
+        );
+        let next_page_token = self.signer.next_page_token(
+            &mut records,
+            page_size,
+            &query,
+            version,
+            DbUtfKenAllRecord::sort_key,
+        );
+        let records = records
+            .into_iter()
+            .map(UtfKenAllRecord::from)
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySearchByKanaResponse {
+            records,
+            next_page_token,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn suggest(
+        &self,
+        req: UtfKenAllRepositorySuggestRequest<'_>,
+    ) -> Result<UtfKenAllRepositorySuggestResponse, Self::Error> {
+        let query = req.query;
+        tracing::info!(%query, field = ?req.field, "Start finding suggestions from utf_ken_all table");
+        // 対象の列が異なるだけなので、列名を差し替えて同じ問い合わせを使う
+        // （郵便番号は桁数が揃っているので、この並び順でも郵便番号順になる）
+        let (address, city_town, town) = match req.field {
+            SuggestField::PostalCode => ("postal_code", "postal_code", "postal_code"),
+            SuggestField::Address => ("prefecture || city || town", "city || town", "town"),
+            SuggestField::Kana => (
+                "prefecture_kana || city_kana || town_kana",
+                "city_kana || town_kana",
+                "town_kana",
+            ),
+        };
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let records = sqlx::query_as::<_, DbUtfKenAllRecord>(&format!(
+            r#"
+            SELECT
+                utf_ken_all_id,
+                local_government_code,
+                old_postal_code,
+                postal_code,
+                prefecture_kana,
+                city_kana,
+                town_kana,
+                prefecture,
+                city,
+                town,
+                has_multi_postal_code,
+                has_chome,
+                has_multi_town,
+                update_code,
+                update_reason,
+                prefecture_roman,
+                city_roman,
+                town_roman,
+                town_spec
+            FROM utf_ken_all
+            WHERE (({address}) >= ?1 AND ({address}) < ?2
+                    OR ({city_town}) >= ?1 AND ({city_town}) < ?2
+                    OR {town} >= ?1 AND {town} < ?2)
+                AND valid_from <= ?4 AND valid_to > ?4
+            ORDER BY
+                CASE
+                    WHEN ({address}) = ?1 OR ({city_town}) = ?1 OR {town} = ?1 THEN 0
+                    WHEN ({address}) >= ?1 AND ({address}) < ?2 THEN 1
+                    WHEN ({city_town}) >= ?1 AND ({city_town}) < ?2 THEN 2
+                    ELSE 3
+                END,
+                length({town}),
+                postal_code,
+                town,
+                town_kana,
+                utf_ken_all_id
+            LIMIT ?3
+            "#
+        ))
+        .bind(query)
+        .bind(prefix_upper_bound(query))
+        .bind(req.limit as i64)
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::info!(
+            count = records.len(),
+            "Finish finding suggestions from utf_ken_all table"
+        );
+        let records = records
+            .into_iter()
+            .map(UtfKenAllRecord::from)
+            .collect::<Vec<_>>();
+        Ok(UtfKenAllRepositorySuggestResponse {
+            records,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_prefectures(
+        &self,
+    ) -> Result<UtfKenAllRepositoryListPrefecturesResponse, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        // 全国地方公共団体コードの上2桁が都道府県コード
+        let prefectures = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT substr(local_government_code, 1, 2), prefecture, prefecture_kana
+            FROM utf_ken_all
+            WHERE valid_from <= ?1 AND valid_to > ?1
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2, 3
+            "#,
+        )
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|(code, name, name_kana)| Prefecture {
+            code,
+            name,
+            name_kana,
+        })
+        .collect();
+        tx.commit().await?;
+        Ok(UtfKenAllRepositoryListPrefecturesResponse {
+            prefectures,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_cities(
+        &self,
+        prefecture_code: Option<&str>,
+    ) -> Result<UtfKenAllRepositoryListCitiesResponse, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let cities = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT local_government_code, city, city_kana
+            FROM utf_ken_all
+            WHERE (?1 IS NULL OR local_government_code BETWEEN ?1 || '000' AND ?1 || '999')
+                AND valid_from <= ?2 AND valid_to > ?2
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2, 3
+            "#,
+        )
+        .bind(prefecture_code)
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|(local_government_code, name, name_kana)| City {
+            local_government_code,
+            name,
+            name_kana,
+        })
+        .collect();
+        tx.commit().await?;
+        Ok(UtfKenAllRepositoryListCitiesResponse {
+            cities,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_towns(
+        &self,
+        local_government_code: &str,
+    ) -> Result<UtfKenAllRepositoryListTownsResponse, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let towns = sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT local_government_code, postal_code, town, town_kana
+            FROM utf_ken_all
+            WHERE local_government_code = ?1 AND town <> ''
+                AND valid_from <= ?2 AND valid_to > ?2
+            GROUP BY 1, 2, 3, 4
+            ORDER BY 2, 4, 3
+            "#,
+        )
+        .bind(local_government_code)
+        .bind(dataset_version)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(
+            |(local_government_code, postal_code, name, name_kana)| Town {
+                local_government_code,
+                postal_code,
+                name,
+                name_kana,
+            },
+        )
+        .collect();
+        tx.commit().await?;
+        Ok(UtfKenAllRepositoryListTownsResponse {
+            towns,
+            dataset_version,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count(&self) -> Result<usize, Self::Error> {
+        let (mut tx, dataset_version) = begin_snapshot(&self.pool).await?;
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM utf_ken_all
+            WHERE valid_from <= ?1 AND valid_to > ?1
+            "#,
+        )
+        .bind(dataset_version)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(count as usize)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_versions(&self) -> Result<Vec<DatasetVersion>, Self::Error> {
+        let versions: Vec<(i64, i64, i64, chrono::DateTime<chrono::Utc>, bool)> = sqlx::query_as(
+            r#"
+            SELECT
+                dataset.dataset_id,
+                dataset.utf_ken_all_count,
+                dataset.jigyosyo_count,
+                dataset.created_at,
+                COALESCE(dataset.dataset_id = active_dataset.dataset_id, false)
+            FROM dataset
+            CROSS JOIN active_dataset
+            ORDER BY dataset.dataset_id DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(versions
+            .into_iter()
+            .map(
+                |(id, utf_ken_all_count, jigyosyo_count, created_at, active)| DatasetVersion {
+                    id,
+                    utf_ken_all_count: utf_ken_all_count as usize,
+                    jigyosyo_count: jigyosyo_count as usize,
+                    created_at,
+                    active,
+                },
+            )
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn active_version(&self) -> Result<Option<i64>, Self::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Ok(conn.active_dataset_id().await?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn activate_version(&mut self, id: i64) -> Result<(), Self::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE active_dataset SET dataset_id = ?1
+            WHERE EXISTS (SELECT 1 FROM dataset WHERE dataset_id = ?1)
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(UtfKenAllRepositoryError::DatasetVersionNotFound(id));
+        }
+        tracing::info!(dataset_id = id, "Activated dataset version");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn rollback_version(&mut self) -> Result<i64, Self::Error> {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            UPDATE active_dataset SET dataset_id = (
+                SELECT max(dataset.dataset_id) FROM dataset
+                WHERE dataset.dataset_id < active_dataset.dataset_id
+            )
+            WHERE EXISTS (
+                SELECT 1 FROM dataset WHERE dataset.dataset_id < active_dataset.dataset_id
+            )
+            RETURNING dataset_id
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(UtfKenAllRepositoryError::NoPreviousDatasetVersion)?;
+        tracing::info!(dataset_id = id, "Rolled back dataset version");
+        Ok(id)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_history(&self, postal_code: &str) -> Result<Vec<PostalCodeHistory>, Self::Error> {
+        let history = sqlx::query_as::<_, DbPostalCodeHistory>(
+            r#"
+            SELECT
+                postal_code_history_id,
+                dataset_id,
+                postal_code,
+                change,
+                fields,
+                update_code,
+                update_reason,
+                imported_at
+            FROM postal_code_history
+            WHERE postal_code = ?1
+            ORDER BY dataset_id DESC, postal_code_history_id
+            "#,
+        )
+        .bind(postal_code)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(PostalCodeHistory::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::{FieldChange, PostalCodeChange};
+    use crate::SQLITE_MIGRATOR;
+    use jp_postal_code_core::model::{UpdateCode, UpdateReason};
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_replace(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+
+        // 正しくデータが挿入されているかチェック
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "イカニケイサイガナイバアイ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "以下に掲載がない場合".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640941".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "アサヒガオカ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "旭ケ丘".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640820".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（２０〜２８丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        let records = {
+            let mut conn = pool.acquire().await.unwrap();
+            sqlx::query_as::<_, DbUtfKenAllRecord>(
+                r#"
+                SELECT
+                    utf_ken_all_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    prefecture_kana,
+                    city_kana,
+                    town_kana,
+                    prefecture,
+                    city,
+                    town,
+                    has_multi_postal_code,
+                    has_chome,
+                    has_multi_town,
+                    update_code,
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    town_spec
+                FROM utf_ken_all
+                JOIN active_dataset
+                    ON valid_from <= active_dataset.dataset_id AND valid_to > active_dataset.dataset_id
+                "#,
+            )
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap()
+        };
+        insta::assert_debug_snapshot!(records);
+
+        // 正しく置き換えられるかチェック
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640941".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "アサヒガオカ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "旭ケ丘".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        let records = {
+            let mut conn = pool.acquire().await.unwrap();
+            sqlx::query_as::<_, DbUtfKenAllRecord>(
+                r#"
+                SELECT
+                    utf_ken_all_id,
+                    local_government_code,
+                    old_postal_code,
+                    postal_code,
+                    prefecture_kana,
+                    city_kana,
+                    town_kana,
+                    prefecture,
+                    city,
+                    town,
+                    has_multi_postal_code,
+                    has_chome,
+                    has_multi_town,
+                    update_code,
+                    update_reason,
+                    prefecture_roman,
+                    city_roman,
+                    town_roman,
+                    town_spec
+                FROM utf_ken_all
+                JOIN active_dataset
+                    ON valid_from <= active_dataset.dataset_id AND valid_to > active_dataset.dataset_id
+                "#,
+            )
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap()
+        };
+        insta::assert_debug_snapshot!(records);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_search(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "イカニケイサイガナイバアイ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "以下に掲載がない場合".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640941".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "アサヒガオカ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "旭ケ丘".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640820".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（２０〜２８丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 完全一致で検索
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "0640820",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 前方一致で検索
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_search_invalid_page_token(pool: sqlx::SqlitePool) {
+        let repository = UtfKenAllRepositorySqlite::new(pool);
+
+        // 解釈できないページトークンはエラー
+        for page_token in ["invalid", "eyJvZmZzZXQiOiJhIn0", "MTA"] {
+            let err = repository
+                .search(UtfKenAllRepositorySearchRequest {
+                    postal_code: "060",
+                    page_size: None,
+                    page_token: Some(page_token),
+                })
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, UtfKenAllRepositoryError::InvalidPageToken),
+                "{page_token}: {err:?}"
+            );
+        }
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_search_page_size_and_page_token(pool: sqlx::SqlitePool) {
+        let mut repository =
+            UtfKenAllRepositorySqlite::new(pool.clone()).with_page_token_secret(b"secret");
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "イカニケイサイガナイバアイ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "以下に掲載がない場合".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640941".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "アサヒガオカ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "旭ケ丘".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640820".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（２０〜２８丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 1, 2 件目を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(2),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 3 件目を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(2),
+                page_token: response.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 別の検索条件で発行されたページトークンはエラー
+        let page_token = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(1),
+                page_token: None,
+            })
+            .await
+            .unwrap()
+            .next_page_token
+            .unwrap();
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "06",
+                page_size: Some(1),
+                page_token: Some(&page_token),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::PageTokenMismatch));
+
+        // 発行したバージョンが残っていれば、データベースを更新した後も続きから読める
+        let request = UtfKenAllRepositorySearchRequest {
+            postal_code: "060",
+            page_size: Some(1),
+            page_token: Some(&page_token),
+        };
+        let expected = repository.search(request.clone()).await.unwrap();
+        repository.apply_diff(&[], &[]).await.unwrap();
+        let response = repository.search(request).await.unwrap();
+        assert_eq!(response.records, expected.records);
+
+        // 発行したバージョンが削除されたページトークンはエラー
+        let mut repository = repository.with_versions_to_keep(1);
+        repository.apply_diff(&[], &[]).await.unwrap();
+        let err = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(1),
+                page_token: Some(&page_token),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UtfKenAllRepositoryError::StalePageToken));
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_search_by_address(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050014".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝１丁目".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦１丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ２チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦２丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 完全一致するレコードが先頭になる
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "東京都港区芝浦",
+                page_size: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 都道府県名を省略して前方一致で検索
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "港区芝",
+                page_size: Some(3),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // ワイルドカード文字はエスケープされる
+        let response = repository
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: "%",
+                page_size: None,
+            })
+            .await
+            .unwrap();
+        assert!(response.records.is_empty());
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_search_by_kana(pool: sqlx::SqlitePool) {
+        let mut repository =
+            UtfKenAllRepositorySqlite::new(pool.clone()).with_page_token_secret(b"secret");
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050014".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝１丁目".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦１丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ２チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦２丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 都道府県名カナを省略して前方一致で検索し、ページを辿る
+        let first = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトクシバウラ",
+                page_size: Some(2),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(first);
+        let second = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトクシバウラ",
+                page_size: Some(2),
+                page_token: first.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(second);
+
+        // 都道府県名カナから検索
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ホッカイドウサッポロ",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // ワイルドカード文字はエスケープされる
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "%",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        assert!(response.records.is_empty());
+
+        // 不正なページトークン
+        let result = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ミナトク",
+                page_size: None,
+                page_token: Some("invalid"),
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(UtfKenAllRepositoryError::InvalidPageToken)
+        ));
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_apply_diff(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+        let ikani = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600000".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let asahigaoka = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "064  ".to_string(),
+            postal_code: "0640941".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "アサヒガオカ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "旭ケ丘".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let odorihigashi = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600041".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "オオドオリヒガシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "大通東".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let kita1jonishi = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600001".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "キタ１ジョウニシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            has_multi_postal_code: 1,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Changed,
+            update_reason: UpdateReason::MunicipalityEnforcement,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        repository
+            .replace(&[ikani, asahigaoka.clone(), odorihigashi.clone()])
+            .await
+            .unwrap();
+
+        // 旭ケ丘を削除し、大通東を変更し、北一条西を新設する
+        repository
+            .apply_diff(
+                &[
+                    UtfKenAllRecord {
+                        update_code: UpdateCode::Changed,
+                        update_reason: UpdateReason::Correction,
+                        has_multi_town: 0,
+                        ..odorihigashi.clone()
+                    },
+                    kita1jonishi.clone(),
+                ],
+                &[
+                    UtfKenAllRecord {
+                        update_code: UpdateCode::Abolished,
+                        update_reason: UpdateReason::Abolished,
+                        ..asahigaoka.clone()
+                    },
+                    UtfKenAllRecord {
+                        update_code: UpdateCode::Changed,
+                        update_reason: UpdateReason::Correction,
+                        ..odorihigashi.clone()
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        let active_rows = || async {
+            sqlx::query_as::<_, (i64, String, String, i16, i16)>(
+                r#"
+                SELECT utf_ken_all_id, postal_code, town, has_multi_town, update_code
+                FROM utf_ken_all
+                JOIN active_dataset
+                    ON valid_from <= active_dataset.dataset_id AND valid_to > active_dataset.dataset_id
+                ORDER BY utf_ken_all_id
+                "#,
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+        let rows = active_rows().await;
+        // 変更のないレコードは ID を変えずに引き継ぎ、挿入したレコードはその後ろに並ぶ
+        assert_eq!(
+            rows.iter()
+                .map(|r| (r.0, r.1.as_str(), r.2.as_str(), r.3, r.4))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "0600000", "", 0, 0),
+                (4, "0600041", "大通東", 0, 1),
+                (5, "0600001", "北一条西", 1, 1),
+            ]
+        );
+
+        // 差分が空ならば内容も ID も変わらない
+        repository.apply_diff(&[], &[]).await.unwrap();
+        assert_eq!(repository.count().await.unwrap(), 3);
+        assert_eq!(active_rows().await, rows);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_replace_jigyosyo(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+
+        // 正しくデータが挿入されているかチェック
+        repository
+            .replace_jigyosyo(&[
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                    business_name: "札幌市役所".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条西".to_string(),
+                    block_address: "２丁目".to_string(),
+                    postal_code: "0608611".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｶﾌﾞｼｷｶﾞｲｼﾔ ﾎﾂｶｲﾄﾞｳｼﾝﾌﾞﾝｼﾔ".to_string(),
+                    business_name: "株式会社　北海道新聞社".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    block_address: "３丁目６".to_string(),
+                    postal_code: "0608711".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+            ])
+            .await
+            .unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jigyosyo")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // 住所の郵便番号には影響しない
+        assert_eq!(repository.count().await.unwrap(), 0);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_search_with_jigyosyo(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool).with_page_token_secret(b"secret");
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        repository
+            .replace_jigyosyo(&[
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                    business_name: "札幌市役所".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条西".to_string(),
+                    block_address: "２丁目".to_string(),
+                    postal_code: "0608611".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+                JigyosyoRecord {
+                    local_government_code: "01101".to_string(),
+                    business_name_kana: "ｶﾌﾞｼｷｶﾞｲｼﾔ ﾎﾂｶｲﾄﾞｳｼﾝﾌﾞﾝｼﾔ".to_string(),
+                    business_name: "株式会社　北海道新聞社".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    block_address: "３丁目６".to_string(),
+                    postal_code: "0608711".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    handling_office: "札幌中央".to_string(),
+                    business_type: 0,
+                    multi_number: 0,
+                    update_code: 0,
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 住所と事業所が郵便番号順に混ざって返る
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(3),
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+
+        // 残りの事業所を取得
+        let response = repository
+            .search(UtfKenAllRepositorySearchRequest {
+                postal_code: "060",
+                page_size: Some(3),
+                page_token: response.next_page_token.as_deref(),
+            })
+            .await
+            .unwrap();
+        insta::assert_debug_snapshot!(response);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_get_by_postal_code(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool);
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "キタ１ジョウヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        repository
+            .replace_jigyosyo(&[JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                business_name: "札幌市役所".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条西".to_string(),
+                block_address: "２丁目".to_string(),
+                postal_code: "0608611".to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            }])
+            .await
+            .unwrap();
+
+        // 郵便番号と完全一致するレコードをすべて返す
+        let records = repository
+            .get_by_postal_code("0600041")
+            .await
+            .unwrap()
+            .records;
+        insta::assert_debug_snapshot!(records);
+
+        // 事業所の個別郵便番号も取得できる
+        let records = repository
+            .get_by_postal_code("0608611")
+            .await
+            .unwrap()
+            .records;
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0], PostalCodeRecord::Business(_)));
+
+        // 前方一致はしない
+        let records = repository.get_by_postal_code("060").await.unwrap().records;
+        assert!(records.is_empty());
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_get_by_postal_codes(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool);
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "キタ１ジョウヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "北一条東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+        repository
+            .replace_jigyosyo(&[JigyosyoRecord {
+                local_government_code: "01101".to_string(),
+                business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+                business_name: "札幌市役所".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "北一条西".to_string(),
+                block_address: "２丁目".to_string(),
+                postal_code: "0608611".to_string(),
+                old_postal_code: "060  ".to_string(),
+                handling_office: "札幌中央".to_string(),
+                business_type: 0,
+                multi_number: 0,
+                update_code: 0,
+            }])
+            .await
+            .unwrap();
+
+        // 郵便番号順にまとめて返し、存在しない郵便番号や重複は無視する
+        let records = repository
+            .get_by_postal_codes(&[
+                "0608611".to_string(),
+                "0600041".to_string(),
+                "060".to_string(),
+                "0600041".to_string(),
+            ])
+            .await
+            .unwrap()
+            .records;
+        insta::assert_debug_snapshot!(records);
+
+        let records = repository.get_by_postal_codes(&[]).await.unwrap().records;
+        assert!(records.is_empty());
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_list_areas(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool);
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01102".to_string(),
+                    old_postal_code: "001  ".to_string(),
+                    postal_code: "0600807".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシキタク".to_string(),
+                    town_kana: "キタ７ジョウニシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市北区".to_string(),
+                    town: "北七条西".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 都道府県コード順に都道府県を返す
+        let prefectures = repository.list_prefectures().await.unwrap().prefectures;
+        insta::assert_debug_snapshot!(prefectures);
+
+        // 都道府県内の市区町村を全国地方公共団体コード順に返す
+        let cities = repository.list_cities(Some("01")).await.unwrap().cities;
+        insta::assert_debug_snapshot!(cities);
+        assert!(repository
+            .list_cities(Some("47"))
+            .await
+            .unwrap()
+            .cities
+            .is_empty());
+        // 都道府県を省略するとすべての市区町村を返す
+        let cities = repository.list_cities(None).await.unwrap().cities;
+        assert_eq!(
+            cities
+                .iter()
+                .map(|c| c.local_government_code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["01101", "01102", "13103"]
+        );
+
+        // 市区町村内の町域を郵便番号順に返し、町域名が空のものは除く
+        let towns = repository.list_towns("01101").await.unwrap().towns;
+        insta::assert_debug_snapshot!(towns);
+        assert!(repository
+            .list_towns("01103")
+            .await
+            .unwrap()
+            .towns
+            .is_empty());
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_suggest(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050014".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝１丁目".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "105  ".to_string(),
+                    postal_code: "1050023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ１チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦１丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "13103".to_string(),
+                    old_postal_code: "108  ".to_string(),
+                    postal_code: "1080023".to_string(),
+                    prefecture_kana: "トウキョウト".to_string(),
+                    city_kana: "ミナトク".to_string(),
+                    town_kana: "シバウラ２チョウメ".to_string(),
+                    prefecture: "東京都".to_string(),
+                    city: "港区".to_string(),
+                    town: "芝浦２丁目".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 1,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "11203".to_string(),
+                    old_postal_code: "333  ".to_string(),
+                    postal_code: "3330866".to_string(),
+                    prefecture_kana: "サイタマケン".to_string(),
+                    city_kana: "カワグチシ".to_string(),
+                    town_kana: "シバ".to_string(),
+                    prefecture: "埼玉県".to_string(),
+                    city: "川口市".to_string(),
+                    town: "芝".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        // 候補を郵便番号と町域名の組にして比較する
+        async fn suggest(
+            repository: &UtfKenAllRepositorySqlite,
+            field: SuggestField,
+            query: &str,
+            limit: usize,
+        ) -> Vec<(String, String)> {
+            repository
+                .suggest(UtfKenAllRepositorySuggestRequest {
+                    field,
+                    query,
+                    limit,
+                })
+                .await
+                .unwrap()
+                .records
+                .into_iter()
+                .map(|r| (r.postal_code, r.town))
+                .collect()
+        }
+        let pair = |postal_code: &str, town: &str| (postal_code.to_string(), town.to_string());
+
+        // 完全一致を優先し、町域名からの前方一致は町域名の短い順に並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "芝", 10).await,
+            vec![
+                pair("3330866", "芝"),
+                pair("1080023", "芝浦"),
+                pair("1050014", "芝１丁目"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "芝浦", 2).await,
+            vec![pair("1080023", "芝浦"), pair("1050023", "芝浦１丁目")]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "東京都港区芝浦", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Address, "札幌市中央区", 10).await,
+            vec![pair("0600000", "")]
+        );
+
+        // 読みでも同じ規則で並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::Kana, "シバウ", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+        assert_eq!(
+            suggest(&repository, SuggestField::Kana, "ミナトクシバ", 10).await,
+            vec![
+                pair("1080023", "芝浦"),
+                pair("1050014", "芝１丁目"),
+                pair("1050023", "芝浦１丁目"),
+                pair("1080023", "芝浦２丁目"),
+            ]
+        );
+
+        // 郵便番号は郵便番号順に並べる
+        assert_eq!(
+            suggest(&repository, SuggestField::PostalCode, "1050", 10).await,
+            vec![pair("1050014", "芝１丁目"), pair("1050023", "芝浦１丁目")]
+        );
+
+        // ワイルドカード文字はエスケープされる
+        assert!(suggest(&repository, SuggestField::Address, "%", 10)
+            .await
+            .is_empty());
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_roman(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool);
+        let record = UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: "0600041".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "オオドオリヒガシ".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "大通東".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "HOKKAIDO".to_string(),
+            city_roman: "SAPPORO SHI CHUO KU".to_string(),
+            town_roman: "ODORIHIGASHI".to_string(),
+            town_spec: Default::default(),
+        };
+        repository
+            .replace(std::slice::from_ref(&record))
+            .await
+            .unwrap();
+
+        // ローマ字表記も保存され、検索結果に含まれる
+        let records = repository
+            .get_by_postal_code("0600041")
+            .await
+            .unwrap()
+            .records;
+        assert_eq!(records, vec![PostalCodeRecord::Address(record.clone())]);
+        let response = repository
+            .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+                kana: "ホッカイドウ",
+                page_size: None,
+                page_token: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.records, vec![record]);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_count(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool.clone());
+
+        let count = repository.count().await.unwrap();
+        assert_eq!(count, 0);
+
+        // サンプルデータを入力
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600000".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "イカニケイサイガナイバアイ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "以下に掲載がない場合".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 0,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640941".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "アサヒガオカ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "旭ケ丘".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600041".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリヒガシ".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通東".to_string(),
+                    has_multi_postal_code: 0,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "060  ".to_string(),
+                    postal_code: "0600042".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（１−１９チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（１〜１９丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+                UtfKenAllRecord {
+                    local_government_code: "01101".to_string(),
+                    old_postal_code: "064  ".to_string(),
+                    postal_code: "0640820".to_string(),
+                    prefecture_kana: "ホッカイドウ".to_string(),
+                    city_kana: "サッポロシチュウオウク".to_string(),
+                    town_kana: "オオドオリニシ（２０−２８チョウメ）".to_string(),
+                    prefecture: "北海道".to_string(),
+                    city: "札幌市中央区".to_string(),
+                    town: "大通西（２０〜２８丁目）".to_string(),
+                    has_multi_postal_code: 1,
+                    has_chome: 0,
+                    has_multi_town: 1,
+                    update_code: UpdateCode::Unchanged,
+                    update_reason: UpdateReason::Unchanged,
+                    prefecture_roman: "".to_string(),
+                    city_roman: "".to_string(),
+                    town_roman: "".to_string(),
+                    town_spec: Default::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        let count = repository.count().await.unwrap();
+        assert_eq!(count, 5);
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_versions(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool).with_versions_to_keep(2);
+        let record = |postal_code: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+
+        // まだ何も取り込んでいない
+        assert_eq!(repository.active_version().await.unwrap(), None);
+        assert!(repository.list_versions().await.unwrap().is_empty());
+
+        // 取り込むたびに新しいバージョンを使用中にし、保持する数を超えた古いバージョンは削除する
+        repository.replace(&[record("0600000")]).await.unwrap();
+        repository
+            .replace(&[record("0600000"), record("0600041")])
+            .await
+            .unwrap();
+        repository
+            .apply_diff(&[record("0600042")], &[])
+            .await
+            .unwrap();
+        let versions = repository.list_versions().await.unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| (v.id, v.utf_ken_all_count, v.jigyosyo_count, v.active))
+                .collect::<Vec<_>>(),
+            vec![(3, 3, 0, true), (2, 2, 0, false)]
+        );
+        assert_eq!(repository.active_version().await.unwrap(), Some(3));
+        assert_eq!(repository.count().await.unwrap(), 3);
+
+        // 一つ前のバージョンに戻す
+        assert_eq!(repository.rollback_version().await.unwrap(), 2);
+        assert_eq!(repository.count().await.unwrap(), 2);
+        assert!(matches!(
+            repository.rollback_version().await,
+            Err(UtfKenAllRepositoryError::NoPreviousDatasetVersion)
+        ));
+
+        // 指定したバージョンに切り替える
+        repository.activate_version(3).await.unwrap();
+        assert_eq!(repository.count().await.unwrap(), 3);
+        assert!(matches!(
+            repository.activate_version(1).await,
+            Err(UtfKenAllRepositoryError::DatasetVersionNotFound(1))
+        ));
+
+        // 空のデータでは使用中のバージョンを切り替えない
+        assert!(matches!(
+            repository.replace(&[]).await,
+            Err(UtfKenAllRepositoryError::InvalidDataset(_))
+        ));
+        assert_eq!(repository.active_version().await.unwrap(), Some(3));
+        assert_eq!(repository.list_versions().await.unwrap().len(), 2);
+        assert_eq!(repository.count().await.unwrap(), 3);
+
+        // 戻したバージョンに差分を適用した新しいバージョンは、その後のバージョンの変更を含まない
+        let postal_codes = ["0600000", "0600041", "0600042", "0600043"].map(String::from);
+        let reader = repository.clone();
+        let active_postal_codes = || async {
+            reader
+                .get_by_postal_codes(&postal_codes)
+                .await
+                .unwrap()
+                .records
+                .iter()
+                .map(|r| r.postal_code().to_string())
+                .collect::<Vec<_>>()
+        };
+        repository.rollback_version().await.unwrap();
+        repository
+            .apply_diff(&[record("0600043")], &[record("0600041")])
+            .await
+            .unwrap();
+        assert_eq!(active_postal_codes().await, vec!["0600000", "0600043"]);
+
+        // その後のバージョンの内容は変わらない
+        repository.activate_version(3).await.unwrap();
+        assert_eq!(
+            active_postal_codes().await,
+            vec!["0600000", "0600041", "0600042"]
+        );
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_replace_dataset(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool);
+        let record = |postal_code: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let jigyosyo = |postal_code: &str| JigyosyoRecord {
+            local_government_code: "01101".to_string(),
+            business_name_kana: "ｻﾂﾎﾟﾛｼﾔｸｼﾖ".to_string(),
+            business_name: "札幌市役所".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: "北一条西".to_string(),
+            block_address: "２丁目".to_string(),
+            postal_code: postal_code.to_string(),
+            old_postal_code: "060  ".to_string(),
+            handling_office: "札幌中央".to_string(),
+            business_type: 0,
+            multi_number: 0,
+            update_code: 0,
+        };
+        let versions = |versions: Vec<DatasetVersion>| {
+            versions
+                .iter()
+                .map(|v| (v.id, v.utf_ken_all_count, v.jigyosyo_count, v.active))
+                .collect::<Vec<_>>()
+        };
+
+        // 住所と事業所の個別郵便番号をまとめて一つのバージョンにする
+        repository
+            .replace_dataset(
+                Some(&[record("0600000"), record("0600041")]),
+                Some(&[jigyosyo("0608611")]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap()),
+            vec![(1, 2, 1, true)]
+        );
+
+        // 指定しなかったものは使用中のバージョンから引き継ぐ
+        repository
+            .replace_dataset(None, Some(&[jigyosyo("0608611"), jigyosyo("0608711")]))
+            .await
+            .unwrap();
+        repository
+            .replace_dataset(Some(&[record("0600000")]), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap()),
+            vec![(3, 1, 2, true), (2, 2, 2, false), (1, 2, 1, false)]
+        );
+
+        // 一つ前のバージョンに戻すと両方が戻る
+        assert_eq!(repository.rollback_version().await.unwrap(), 2);
+        assert_eq!(repository.rollback_version().await.unwrap(), 1);
+        assert_eq!(
+            versions(repository.list_versions().await.unwrap())[2],
+            (1, 2, 1, true)
+        );
+    }
+
+    #[sqlx::test(migrator = "SQLITE_MIGRATOR")]
+    async fn utf_ken_all_repository_sqlite_history(pool: sqlx::SqlitePool) {
+        let mut repository = UtfKenAllRepositorySqlite::new(pool).with_versions_to_keep(1);
+        let record = |postal_code: &str, town: &str| UtfKenAllRecord {
+            local_government_code: "01101".to_string(),
+            old_postal_code: "060  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "サッポロシチュウオウク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "札幌市中央区".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+
+        // 最初の取り込みでは記録しない
+        repository
+            .replace(&[record("0600000", ""), record("0600041", "大通東")])
+            .await
+            .unwrap();
+        assert!(repository.list_history("0600041").await.unwrap().is_empty());
+
+        // 大通東の町域名カナが変わり、北一条西が追加される（更新の表示だけが変わったものは記録しない）
+        let changed = UtfKenAllRecord {
+            town_kana: "オオドオリヒガシ".to_string(),
+            update_code: UpdateCode::Changed,
+            update_reason: UpdateReason::Correction,
+            ..record("0600041", "大通東")
+        };
+        repository
+            .replace(&[
+                UtfKenAllRecord {
+                    update_code: UpdateCode::Changed,
+                    ..record("0600000", "")
+                },
+                changed.clone(),
+                record("0600001", "北一条西"),
+            ])
+            .await
+            .unwrap();
+        // 大通東が削除される
+        repository.apply_diff(&[], &[changed]).await.unwrap();
+
+        // 取り込みの新しい順に返し、古いバージョンを削除しても変更履歴は残す
+        let history = repository.list_history("0600041").await.unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| (h.dataset_id, h.change, h.update_code, h.update_reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    PostalCodeChange::Removed,
+                    UpdateCode::Changed,
+                    UpdateReason::Correction
+                ),
+                (
+                    2,
+                    PostalCodeChange::Changed,
+                    UpdateCode::Changed,
+                    UpdateReason::Correction
+                ),
+            ]
+        );
+        assert_eq!(history[0].fields.len(), 16);
+        assert!(history[0].fields.contains(&FieldChange {
+            field: "town".to_string(),
+            old: Some("大通東".to_string()),
+            new: None,
+        }));
+        assert_eq!(
+            history[1].fields,
+            vec![FieldChange {
+                field: "town_kana".to_string(),
+                old: Some("".to_string()),
+                new: Some("オオドオリヒガシ".to_string()),
+            }]
+        );
+        assert!(history[0].id > history[1].id);
+        assert!(history[0].imported_at >= history[1].imported_at);
+        assert_eq!(repository.list_versions().await.unwrap().len(), 1);
+
+        let history = repository.list_history("0600001").await.unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| (h.dataset_id, h.change))
+                .collect::<Vec<_>>(),
+            vec![(2, PostalCodeChange::Added)]
+        );
+        assert!(repository.list_history("0600000").await.unwrap().is_empty());
+    }
+}
@@ -1,8 +1,16 @@
 use anyhow::Context as _;
-use axum::{http::StatusCode, routing::get, Json, Router};
+use axum::{
+    http::{HeaderValue, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
 use jp_postal_code::{
-    config, grpc_service, infra, reflection, repo::UtfKenAllRepository as _, usecase, MIGRATOR,
+    config, grpc_service, infra, reflection,
+    repo::{self, UtfKenAllRepository},
+    usecase, MIGRATOR, SQLITE_MIGRATOR,
 };
+use jp_postal_code_core::address::ParsedAddress;
+use jp_postal_code_core::model::{self, JigyosyoRecord, PostalCodeRecord, UtfKenAllRecord};
 use jp_postal_code_proto::postal_address_service_server::PostalAddressServiceServer;
 use std::net::ToSocketAddrs;
 use tonic::transport::Server;
@@ -33,23 +41,96 @@ async fn main() {
 
 async fn main_internal() -> Result<(), anyhow::Error> {
     let conf = config::Config::new();
-    let pool = sqlx::PgPool::connect(conf.database_url.as_ref()).await?;
-    MIGRATOR.run(&pool).await?;
-
-    let mut repo = infra::postgres::UtfKenAllRepositoryPostgres::new(pool.clone());
+    // `DATABASE_URL` のスキームでリポジトリの実装を切り替える
+    if conf.database_url.starts_with("memory:") {
+        let mut repo = infra::ephemeral::UtfKenAllRepositoryEphemeral::default()
+            .with_versions_to_keep(conf.dataset_versions_to_keep);
+        if let Some(secret) = &conf.page_token_secret {
+            repo = repo.with_page_token_secret(secret.as_bytes());
+        }
+        if let Some(file) = &conf.postal_code_file {
+            tracing::info!(?file, "Loading postal code records from a file...");
+            // メモリ上の郵便番号データベースは起動のたびに読み込み直すので、置き換えて失うデータが
+            // ない。検証に失敗しても警告だけにして読み込む
+            usecase::update_postal_code_database_from_file(
+                &mut repo,
+                file,
+                conf.ken_all_rome_file.as_ref(),
+                conf.jigyosyo_file.as_ref(),
+                &usecase::ImportOptions {
+                    force: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        } else if let Some(file) = &conf.jigyosyo_file {
+            tracing::info!(?file, "Loading jigyosyo records from a file...");
+            usecase::update_jigyosyo_database_from_file(&mut repo, file).await?;
+        }
+        serve(conf, repo).await
+    } else if conf.database_url.starts_with("sqlite:") {
+        let options = conf
+            .database_url
+            .parse::<sqlx::sqlite::SqliteConnectOptions>()?
+            .create_if_missing(true);
+        let pool = sqlx::SqlitePool::connect_with(options).await?;
+        SQLITE_MIGRATOR.run(&pool).await?;
+        let mut repo = infra::sqlite::UtfKenAllRepositorySqlite::new(pool)
+            .with_versions_to_keep(conf.dataset_versions_to_keep);
+        if let Some(secret) = &conf.page_token_secret {
+            repo = repo.with_page_token_secret(secret.as_bytes());
+        }
+        serve(conf, repo).await
+    } else {
+        let pool = sqlx::PgPool::connect(conf.database_url.as_ref()).await?;
+        MIGRATOR.run(&pool).await?;
+        let mut repo = infra::postgres::UtfKenAllRepositoryPostgres::new(pool)
+            .with_versions_to_keep(conf.dataset_versions_to_keep);
+        if let Some(secret) = &conf.page_token_secret {
+            repo = repo.with_page_token_secret(secret.as_bytes());
+        }
+        serve(conf, repo).await
+    }
+}
 
+async fn serve<R>(conf: config::Config, mut repo: R) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository + 'static,
+{
     // 郵便番号データベースが空ならば初回ダウンロードを行う
     if repo.count().await? == 0 {
         tracing::info!("Postal address database is empty. Initializing...");
-        usecase::update_postal_code_database(&mut repo, None::<String>).await?;
+        usecase::update_postal_code_database(
+            &mut repo,
+            None::<String>,
+            None,
+            None,
+            &usecase::ImportOptions::default(),
+        )
+        .await?;
     }
 
     // HTTP サーバーの設定
-    let http_state = AppState {
-        repo: infra::postgres::UtfKenAllRepositoryPostgres::new(pool.clone()),
-    };
+    let http_state = AppState { repo: repo.clone() };
     let http_app = Router::new()
-        .route("/api/search", get(search))
+        .route("/api/search", get(search::<R>))
+        .route("/api/search-by-address", get(search_by_address::<R>))
+        .route("/api/search-by-kana", get(search_by_kana::<R>))
+        .route("/api/suggest", get(suggest::<R>))
+        .route("/api/parse-address", get(parse_address::<R>))
+        .route("/api/validate-address", get(validate_address::<R>))
+        .route("/api/postal-codes/{code}", get(get_postal_code::<R>))
+        .route(
+            "/api/postal-codes/{code}/history",
+            get(list_postal_code_history::<R>),
+        )
+        .route(
+            "/api/postal-codes:batchGet",
+            post(batch_get_postal_codes::<R>),
+        )
+        .route("/api/prefectures", get(list_prefectures::<R>))
+        .route("/api/prefectures/{code}/cities", get(list_cities::<R>))
+        .route("/api/cities/{code}/towns", get(list_towns::<R>))
         .layer(CorsLayer::permissive())
         .layer(
             TraceLayer::new_for_http()
@@ -59,9 +140,7 @@ async fn main_internal() -> Result<(), anyhow::Error> {
         .with_state(http_state);
 
     // gRPC サーバーの設定
-    let grpc_service = grpc_service::PostalAddressServiceImpl::new(
-        infra::postgres::UtfKenAllRepositoryPostgres::new(pool),
-    );
+    let grpc_service = grpc_service::PostalAddressServiceImpl::new(repo);
 
     let http_addr = conf.http_server_addr.clone();
     let grpc_addr = conf.grpc_server_addr.clone();
@@ -84,10 +163,13 @@ async fn start_http_server(addr: String, app: Router) -> Result<(), anyhow::Erro
         .map_err(anyhow::Error::from)
 }
 
-async fn start_grpc_server(
+async fn start_grpc_server<R>(
     addr: String,
-    service: grpc_service::PostalAddressServiceImpl,
-) -> Result<(), anyhow::Error> {
+    service: grpc_service::PostalAddressServiceImpl<R>,
+) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository + 'static,
+{
     let addr: std::net::SocketAddr = addr
         .to_socket_addrs()
         .with_context(|| format!("Failed to parse gRPC address: {addr}"))?
@@ -105,23 +187,121 @@ async fn start_grpc_server(
 
 #[derive(Debug, thiserror::Error)]
 enum AppError {
+    /// リクエストの値が不正（400）
+    #[error("{message}")]
+    InvalidArgument {
+        field: Option<&'static str>,
+        message: String,
+    },
+
+    /// リソースが存在しない（404）
+    #[error("{message}")]
+    NotFound { message: String },
+
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
+impl From<usecase::Error> for AppError {
+    fn from(err: usecase::Error) -> Self {
+        match err {
+            usecase::Error::InvalidArgument { field, message } => AppError::InvalidArgument {
+                field: Some(field),
+                message,
+            },
+            usecase::Error::NotFound { message } => AppError::NotFound { message },
+            usecase::Error::Unexpected(err) => AppError::Unknown(err),
+        }
+    }
+}
+
+impl From<axum::extract::rejection::QueryRejection> for AppError {
+    fn from(rejection: axum::extract::rejection::QueryRejection) -> Self {
+        AppError::InvalidArgument {
+            field: None,
+            message: rejection.body_text(),
+        }
+    }
+}
+
+impl From<axum::extract::rejection::JsonRejection> for AppError {
+    fn from(rejection: axum::extract::rejection::JsonRejection) -> Self {
+        AppError::InvalidArgument {
+            field: None,
+            message: rejection.body_text(),
+        }
+    }
+}
+
+/// エラー時のレスポンス
+#[derive(serde::Serialize)]
+struct ErrorResponse {
+    /// エラーの種類（`invalid_argument`、`not_found`、`internal` など）
+    code: &'static str,
+    message: String,
+    /// エラーの原因となったリクエストのフィールド名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<&'static str>,
+}
+
 impl axum::response::IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {self}"),
-        )
-            .into_response()
+        match self {
+            AppError::InvalidArgument { field, message } => (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    code: "invalid_argument",
+                    message,
+                    field,
+                }),
+            )
+                .into_response(),
+            AppError::NotFound { message } => (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    code: "not_found",
+                    message,
+                    field: None,
+                }),
+            )
+                .into_response(),
+            AppError::Unknown(err) => {
+                tracing::error!(?err, "Unexpected error occurred while handling request");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        code: "internal",
+                        message: "Something went wrong".to_string(),
+                        field: None,
+                    }),
+                )
+                    .into_response()
+            }
+        }
     }
 }
 
 #[derive(Debug, Clone)]
-struct AppState {
-    repo: infra::postgres::UtfKenAllRepositoryPostgres,
+struct AppState<R> {
+    repo: R,
+}
+
+/// レスポンスヘッダー `X-Dataset-Version` に、検索した郵便番号データベースのバージョンを付ける
+struct DatasetVersionHeader(Option<i64>);
+
+impl axum::response::IntoResponseParts for DatasetVersionHeader {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(
+        self,
+        mut res: axum::response::ResponseParts,
+    ) -> Result<axum::response::ResponseParts, Self::Error> {
+        if let Some(version) = self.0 {
+            res.headers_mut()
+                .insert("x-dataset-version", HeaderValue::from(version));
+        }
+        Ok(res)
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -134,6 +314,193 @@ struct PostalAddress {
     city_kana: String,
     town: String,
     town_kana: String,
+    /// 都道府県名（ローマ字、住所の郵便番号の場合のみ）
+    prefecture_roman: String,
+    /// 市区町村名（ローマ字、住所の郵便番号の場合のみ）
+    city_roman: String,
+    /// 町域名（ローマ字、住所の郵便番号の場合のみ）
+    town_roman: String,
+    kind: PostalAddressKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business: Option<Business>,
+    /// 全国地方公共団体コード（JIS X0401、X0402）
+    local_government_code: String,
+    /// 旧郵便番号（5桁）
+    old_postal_code: String,
+    /// 一町域が二以上の郵便番号で表される場合か
+    has_multi_postal_code: bool,
+    /// 丁目を有する町域か
+    has_chome: bool,
+    /// 一つの郵便番号で二以上の町域を表す場合か
+    has_multi_town: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_code: Option<UpdateCode>,
+    /// 更新理由（住所の郵便番号の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_reason: Option<UpdateReason>,
+}
+
+/// 更新の表示
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum UpdateCode {
+    /// 変更なし
+    Unchanged,
+    /// 変更あり
+    Changed,
+    /// 廃止
+    Abolished,
+}
+
+impl From<model::UpdateCode> for UpdateCode {
+    fn from(code: model::UpdateCode) -> Self {
+        match code {
+            model::UpdateCode::Unchanged => UpdateCode::Unchanged,
+            model::UpdateCode::Changed => UpdateCode::Changed,
+            model::UpdateCode::Abolished => UpdateCode::Abolished,
+        }
+    }
+}
+
+/// 更新理由
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum UpdateReason {
+    /// 変更なし
+    Unchanged,
+    /// 市政・区政・町政・分区・政令指定都市施行
+    MunicipalityEnforcement,
+    /// 住居表示の実施
+    ResidenceIndication,
+    /// 区画整理
+    LandReadjustment,
+    /// 郵便区調整等
+    PostalDistrictAdjustment,
+    /// 訂正
+    Correction,
+    /// 廃止
+    Abolished,
+}
+
+/// 郵便番号の種別
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum PostalAddressKind {
+    /// 住所の郵便番号
+    Address,
+    /// 事業所の個別郵便番号
+    Business,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Business {
+    name: String,
+    name_kana: String,
+    block_address: String,
+    handling_office: String,
+    business_type: BusinessType,
+}
+
+/// 個別番号の種別
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum BusinessType {
+    /// 大口事業所
+    Office,
+    /// 私書箱
+    PoBox,
+}
+
+impl From<model::UpdateReason> for UpdateReason {
+    fn from(reason: model::UpdateReason) -> Self {
+        match reason {
+            model::UpdateReason::Unchanged => UpdateReason::Unchanged,
+            model::UpdateReason::MunicipalityEnforcement => UpdateReason::MunicipalityEnforcement,
+            model::UpdateReason::ResidenceIndication => UpdateReason::ResidenceIndication,
+            model::UpdateReason::LandReadjustment => UpdateReason::LandReadjustment,
+            model::UpdateReason::PostalDistrictAdjustment => UpdateReason::PostalDistrictAdjustment,
+            model::UpdateReason::Correction => UpdateReason::Correction,
+            model::UpdateReason::Abolished => UpdateReason::Abolished,
+        }
+    }
+}
+
+impl From<UtfKenAllRecord> for PostalAddress {
+    fn from(r: UtfKenAllRecord) -> Self {
+        Self {
+            postal_code: r.postal_code,
+            prefecture: r.prefecture,
+            prefecture_kana: r.prefecture_kana,
+            city: r.city,
+            city_kana: r.city_kana,
+            town: r.town,
+            town_kana: r.town_kana,
+            prefecture_roman: r.prefecture_roman,
+            city_roman: r.city_roman,
+            town_roman: r.town_roman,
+            kind: PostalAddressKind::Address,
+            business: None,
+            local_government_code: r.local_government_code,
+            old_postal_code: r.old_postal_code,
+            has_multi_postal_code: r.has_multi_postal_code == 1,
+            has_chome: r.has_chome == 1,
+            has_multi_town: r.has_multi_town == 1,
+            update_code: Some(r.update_code.into()),
+            update_reason: Some(r.update_reason.into()),
+        }
+    }
+}
+
+impl From<JigyosyoRecord> for PostalAddress {
+    fn from(r: JigyosyoRecord) -> Self {
+        Self {
+            postal_code: r.postal_code,
+            prefecture: r.prefecture,
+            prefecture_kana: "".to_string(),
+            city: r.city,
+            city_kana: "".to_string(),
+            town: r.town,
+            town_kana: "".to_string(),
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            kind: PostalAddressKind::Business,
+            business: Some(Business {
+                name: r.business_name,
+                name_kana: r.business_name_kana,
+                block_address: r.block_address,
+                handling_office: r.handling_office,
+                business_type: if r.business_type == 1 {
+                    BusinessType::PoBox
+                } else {
+                    BusinessType::Office
+                },
+            }),
+            local_government_code: r.local_government_code,
+            old_postal_code: r.old_postal_code,
+            has_multi_postal_code: false,
+            has_chome: false,
+            has_multi_town: false,
+            // 事業所の修正コードは 0: 修正なし、1: 新規追加、5: 廃止
+            update_code: match r.update_code {
+                0 => Some(UpdateCode::Unchanged),
+                1 => Some(UpdateCode::Changed),
+                5 => Some(UpdateCode::Abolished),
+                _ => None,
+            },
+            update_reason: None,
+        }
+    }
+}
+
+impl From<PostalCodeRecord> for PostalAddress {
+    fn from(r: PostalCodeRecord) -> Self {
+        match r {
+            PostalCodeRecord::Address(r) => r.into(),
+            PostalCodeRecord::Business(r) => r.into(),
+        }
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -150,10 +517,14 @@ struct SearchResponse {
     next_page_token: Option<String>,
 }
 
-async fn search(
-    axum::extract::Query(query): axum::extract::Query<SearchQuery>,
-    axum::extract::State(state): axum::extract::State<AppState>,
-) -> Result<impl axum::response::IntoResponse, AppError> {
+async fn search<R>(
+    query: Result<axum::extract::Query<SearchQuery>, axum::extract::rejection::QueryRejection>,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let axum::extract::Query(query) = query?;
     let postal_code = query.postal_code.unwrap_or("".to_string());
     let response = usecase::search_postal_code(
         &state.repo,
@@ -167,21 +538,639 @@ async fn search(
     let addresses = response
         .records
         .into_iter()
-        .map(|r| PostalAddress {
-            postal_code: r.postal_code,
-            prefecture: r.prefecture,
-            prefecture_kana: r.prefecture_kana,
-            city: r.city,
-            city_kana: r.city_kana,
-            town: r.town,
-            town_kana: r.town_kana,
-        })
+        .map(PostalAddress::from)
         .collect::<Vec<_>>();
     Ok((
         StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
         Json(SearchResponse {
             addresses,
             next_page_token: response.next_page_token,
         }),
     ))
 }
+
+#[derive(serde::Deserialize)]
+struct SearchByAddressQuery {
+    address: Option<String>,
+    page_size: Option<usize>,
+    #[serde(default)]
+    did_you_mean: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchByAddressResponse {
+    addresses: Vec<PostalAddress>,
+    /// 表記揺れや入力の誤りを許容して見つけた候補（`did_you_mean` を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    did_you_mean: Option<Vec<PostalAddress>>,
+}
+
+async fn search_by_address<R>(
+    query: Result<
+        axum::extract::Query<SearchByAddressQuery>,
+        axum::extract::rejection::QueryRejection,
+    >,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let axum::extract::Query(query) = query?;
+    let address = query.address.unwrap_or("".to_string());
+    let response = usecase::search_postal_code_by_address(
+        &state.repo,
+        usecase::SearchPostalCodeByAddressRequest {
+            address,
+            page_size: query.page_size,
+            did_you_mean: query.did_you_mean,
+        },
+    )
+    .await?;
+    let addresses = response
+        .records
+        .into_iter()
+        .map(PostalAddress::from)
+        .collect::<Vec<_>>();
+    let did_you_mean = query.did_you_mean.then(|| {
+        response
+            .did_you_mean
+            .into_iter()
+            .map(PostalAddress::from)
+            .collect::<Vec<_>>()
+    });
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(SearchByAddressResponse {
+            addresses,
+            did_you_mean,
+        }),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+struct SearchByKanaQuery {
+    kana: Option<String>,
+    page_size: Option<usize>,
+    page_token: Option<String>,
+}
+
+async fn search_by_kana<R>(
+    query: Result<
+        axum::extract::Query<SearchByKanaQuery>,
+        axum::extract::rejection::QueryRejection,
+    >,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let axum::extract::Query(query) = query?;
+    let kana = query.kana.unwrap_or("".to_string());
+    let response = usecase::search_postal_code_by_kana(
+        &state.repo,
+        usecase::SearchPostalCodeByKanaRequest {
+            kana,
+            page_size: query.page_size,
+            page_token: query.page_token,
+        },
+    )
+    .await?;
+    let addresses = response
+        .records
+        .into_iter()
+        .map(PostalAddress::from)
+        .collect::<Vec<_>>();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(SearchResponse {
+            addresses,
+            next_page_token: response.next_page_token,
+        }),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+struct SuggestQuery {
+    q: Option<String>,
+    limit: Option<usize>,
+}
+
+/// 入力補完でクエリと一致した項目
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum SuggestField {
+    PostalCode,
+    Address,
+    Kana,
+}
+
+impl From<jp_postal_code::repo::SuggestField> for SuggestField {
+    fn from(field: jp_postal_code::repo::SuggestField) -> Self {
+        match field {
+            jp_postal_code::repo::SuggestField::PostalCode => SuggestField::PostalCode,
+            jp_postal_code::repo::SuggestField::Address => SuggestField::Address,
+            jp_postal_code::repo::SuggestField::Kana => SuggestField::Kana,
+        }
+    }
+}
+
+/// `text` のうちクエリと一致した範囲（文字単位、`end` は含まない）
+#[derive(serde::Serialize)]
+struct Highlight {
+    start: usize,
+    end: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Suggestion {
+    field: SuggestField,
+    text: String,
+    highlight: Highlight,
+    address: PostalAddress,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SuggestResponse {
+    suggestions: Vec<Suggestion>,
+}
+
+async fn suggest<R>(
+    query: Result<axum::extract::Query<SuggestQuery>, axum::extract::rejection::QueryRejection>,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let axum::extract::Query(query) = query?;
+    let response = usecase::suggest(
+        &state.repo,
+        usecase::SuggestRequest {
+            query: query.q.unwrap_or("".to_string()),
+            limit: query.limit,
+        },
+    )
+    .await?;
+    let suggestions = response
+        .suggestions
+        .into_iter()
+        .map(|s| Suggestion {
+            field: s.field.into(),
+            text: s.text,
+            highlight: Highlight {
+                start: s.highlight.start,
+                end: s.highlight.end,
+            },
+            address: s.record.into(),
+        })
+        .collect::<Vec<_>>();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(SuggestResponse { suggestions }),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+struct ParseAddressQuery {
+    address: Option<String>,
+}
+
+/// 分解した住所（特定できなかった部分は `null`）
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ParseAddressResponse {
+    prefecture: Option<String>,
+    city: Option<String>,
+    local_government_code: Option<String>,
+    town: Option<String>,
+    postal_code: Option<String>,
+    chome: Option<String>,
+    banchi: Option<String>,
+    building: Option<String>,
+}
+
+impl From<ParsedAddress> for ParseAddressResponse {
+    fn from(p: ParsedAddress) -> Self {
+        Self {
+            prefecture: p.prefecture,
+            city: p.city,
+            local_government_code: p.local_government_code,
+            town: p.town,
+            postal_code: p.postal_code,
+            chome: p.chome,
+            banchi: p.banchi,
+            building: p.building,
+        }
+    }
+}
+
+async fn parse_address<R>(
+    query: Result<
+        axum::extract::Query<ParseAddressQuery>,
+        axum::extract::rejection::QueryRejection,
+    >,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let axum::extract::Query(query) = query?;
+    let parsed =
+        usecase::parse_address(&state.repo, query.address.as_deref().unwrap_or("")).await?;
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(parsed.dataset_version),
+        Json(ParseAddressResponse::from(parsed.address)),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+struct ValidateAddressQuery {
+    postal_code: Option<String>,
+    prefecture: Option<String>,
+    city: Option<String>,
+    town: Option<String>,
+}
+
+/// 住所の検証で照合する項目
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum AddressField {
+    PostalCode,
+    Prefecture,
+    City,
+    Town,
+}
+
+impl From<usecase::AddressField> for AddressField {
+    fn from(field: usecase::AddressField) -> Self {
+        match field {
+            usecase::AddressField::PostalCode => AddressField::PostalCode,
+            usecase::AddressField::Prefecture => AddressField::Prefecture,
+            usecase::AddressField::City => AddressField::City,
+            usecase::AddressField::Town => AddressField::Town,
+        }
+    }
+}
+
+/// 住所の検証結果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ValidationResult {
+    Valid,
+    Partial,
+    Invalid,
+}
+
+impl From<usecase::ValidationResult> for ValidationResult {
+    fn from(result: usecase::ValidationResult) -> Self {
+        match result {
+            usecase::ValidationResult::Valid => ValidationResult::Valid,
+            usecase::ValidationResult::Partial => ValidationResult::Partial,
+            usecase::ValidationResult::Invalid => ValidationResult::Invalid,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidateAddressResponse {
+    result: ValidationResult,
+    mismatched_fields: Vec<AddressField>,
+    suggestions: Vec<PostalAddress>,
+}
+
+async fn validate_address<R>(
+    query: Result<
+        axum::extract::Query<ValidateAddressQuery>,
+        axum::extract::rejection::QueryRejection,
+    >,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let axum::extract::Query(query) = query?;
+    let response = usecase::validate_postal_address(
+        &state.repo,
+        usecase::ValidatePostalAddressRequest {
+            postal_code: query.postal_code.unwrap_or_default(),
+            prefecture: query.prefecture.unwrap_or_default(),
+            city: query.city.unwrap_or_default(),
+            town: query.town.unwrap_or_default(),
+        },
+    )
+    .await?;
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(ValidateAddressResponse {
+            result: response.result.into(),
+            mismatched_fields: response
+                .mismatched_fields
+                .into_iter()
+                .map(AddressField::from)
+                .collect(),
+            suggestions: response
+                .suggestions
+                .into_iter()
+                .map(PostalAddress::from)
+                .collect(),
+        }),
+    ))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GetPostalCodeResponse {
+    addresses: Vec<PostalAddress>,
+}
+
+async fn get_postal_code<R>(
+    axum::extract::Path(code): axum::extract::Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let response = usecase::get_postal_code(
+        &state.repo,
+        usecase::GetPostalCodeRequest { postal_code: code },
+    )
+    .await?;
+    let addresses = response
+        .records
+        .into_iter()
+        .map(PostalAddress::from)
+        .collect::<Vec<_>>();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(GetPostalCodeResponse { addresses }),
+    ))
+}
+
+/// 取り込みによる住所の郵便番号のレコードの変更の種類
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum PostalCodeChange {
+    /// レコードが追加された
+    Added,
+    /// レコードが削除された
+    Removed,
+    /// レコードの項目が変更された
+    Changed,
+}
+
+impl From<repo::PostalCodeChange> for PostalCodeChange {
+    fn from(change: repo::PostalCodeChange) -> Self {
+        match change {
+            repo::PostalCodeChange::Added => PostalCodeChange::Added,
+            repo::PostalCodeChange::Removed => PostalCodeChange::Removed,
+            repo::PostalCodeChange::Changed => PostalCodeChange::Changed,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldChange {
+    field: String,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostalCodeHistory {
+    change: PostalCodeChange,
+    /// 変更された項目（追加・削除された場合は全ての項目）
+    fields: Vec<FieldChange>,
+    update_code: UpdateCode,
+    update_reason: UpdateReason,
+    /// 変更を取り込んだ郵便番号データベースのバージョン
+    dataset_version: i64,
+    imported_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<repo::PostalCodeHistory> for PostalCodeHistory {
+    fn from(h: repo::PostalCodeHistory) -> Self {
+        Self {
+            change: h.change.into(),
+            fields: h
+                .fields
+                .into_iter()
+                .map(|f| FieldChange {
+                    field: f.field,
+                    old: f.old,
+                    new: f.new,
+                })
+                .collect(),
+            update_code: h.update_code.into(),
+            update_reason: h.update_reason.into(),
+            dataset_version: h.dataset_id,
+            imported_at: h.imported_at,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListPostalCodeHistoryResponse {
+    /// 取り込みの新しい順の変更履歴
+    history: Vec<PostalCodeHistory>,
+}
+
+async fn list_postal_code_history<R>(
+    axum::extract::Path(code): axum::extract::Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let history = usecase::list_postal_code_history(&state.repo, &code).await?;
+    let history = history.into_iter().map(PostalCodeHistory::from).collect();
+    Ok((
+        StatusCode::OK,
+        Json(ListPostalCodeHistoryResponse { history }),
+    ))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchGetPostalCodesBody {
+    postal_codes: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchGetPostalCodesResponse {
+    /// 入力された郵便番号ごとの住所
+    results: std::collections::BTreeMap<String, Vec<PostalAddress>>,
+    /// 見つからなかった郵便番号（入力順）
+    not_found: Vec<String>,
+}
+
+async fn batch_get_postal_codes<R>(
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+    body: Result<Json<BatchGetPostalCodesBody>, axum::extract::rejection::JsonRejection>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let Json(body) = body?;
+    let response = usecase::batch_get_postal_codes(
+        &state.repo,
+        usecase::BatchGetPostalCodesRequest {
+            postal_codes: body.postal_codes,
+        },
+    )
+    .await?;
+    let results = response
+        .results
+        .into_iter()
+        .map(|r| {
+            let addresses = r.records.into_iter().map(PostalAddress::from).collect();
+            (r.postal_code, addresses)
+        })
+        .collect();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(BatchGetPostalCodesResponse {
+            results,
+            not_found: response.not_found,
+        }),
+    ))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Prefecture {
+    code: String,
+    name: String,
+    name_kana: String,
+}
+
+impl From<model::Prefecture> for Prefecture {
+    fn from(p: model::Prefecture) -> Self {
+        Self {
+            code: p.code,
+            name: p.name,
+            name_kana: p.name_kana,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct City {
+    local_government_code: String,
+    name: String,
+    name_kana: String,
+}
+
+impl From<model::City> for City {
+    fn from(c: model::City) -> Self {
+        Self {
+            local_government_code: c.local_government_code,
+            name: c.name,
+            name_kana: c.name_kana,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Town {
+    local_government_code: String,
+    postal_code: String,
+    name: String,
+    name_kana: String,
+}
+
+impl From<model::Town> for Town {
+    fn from(t: model::Town) -> Self {
+        Self {
+            local_government_code: t.local_government_code,
+            postal_code: t.postal_code,
+            name: t.name,
+            name_kana: t.name_kana,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListPrefecturesResponse {
+    prefectures: Vec<Prefecture>,
+}
+
+async fn list_prefectures<R>(
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let response = usecase::list_prefectures(&state.repo).await?;
+    let prefectures = response
+        .prefectures
+        .into_iter()
+        .map(Prefecture::from)
+        .collect();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(ListPrefecturesResponse { prefectures }),
+    ))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListCitiesResponse {
+    cities: Vec<City>,
+}
+
+async fn list_cities<R>(
+    axum::extract::Path(code): axum::extract::Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let response = usecase::list_cities(&state.repo, &code).await?;
+    let cities = response.cities.into_iter().map(City::from).collect();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(ListCitiesResponse { cities }),
+    ))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListTownsResponse {
+    towns: Vec<Town>,
+}
+
+async fn list_towns<R>(
+    axum::extract::Path(code): axum::extract::Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState<R>>,
+) -> Result<impl axum::response::IntoResponse, AppError>
+where
+    R: UtfKenAllRepository,
+{
+    let response = usecase::list_towns(&state.repo, &code).await?;
+    let towns = response.towns.into_iter().map(Town::from).collect();
+    Ok((
+        StatusCode::OK,
+        DatasetVersionHeader(response.dataset_version),
+        Json(ListTownsResponse { towns }),
+    ))
+}
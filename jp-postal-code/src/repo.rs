@@ -1,7 +1,119 @@
-use jp_postal_code_core::model::UtfKenAllRecord;
+use jp_postal_code_core::model::{
+    City, JigyosyoRecord, PostalCodeRecord, Prefecture, Town, UpdateCode, UpdateReason,
+    UtfKenAllRecord,
+};
 
 pub const DEFAULT_SEARCH_PAGE_SIZE: usize = 10;
 
+/// 一度に検索できる最大件数
+pub const MAX_SEARCH_PAGE_SIZE: usize = 100;
+
+/// 一度にまとめて取得できる郵便番号の最大数
+pub const MAX_BATCH_GET_SIZE: usize = 10000;
+
+/// 入力補完で返す候補の既定の件数
+pub const DEFAULT_SUGGEST_LIMIT: usize = 5;
+
+/// 入力補完で一度に返せる最大件数
+pub const MAX_SUGGEST_LIMIT: usize = 20;
+
+/// 使用中のものを含めて保持する郵便番号データベースのバージョンの既定の数
+pub const DEFAULT_DATASET_VERSIONS_TO_KEEP: usize = 3;
+
+/// リポジトリの操作で発生するエラー
+#[derive(Debug, thiserror::Error)]
+pub enum UtfKenAllRepositoryError {
+    /// ページトークンを解釈できない、または署名が一致しない
+    #[error("invalid page token")]
+    InvalidPageToken,
+
+    /// ページトークンが別の検索条件で発行されたもの
+    #[error("page token does not match the query")]
+    PageTokenMismatch,
+
+    /// ページトークンを発行したバージョンの郵便番号データベースが削除された
+    #[error("page token is stale")]
+    StalePageToken,
+
+    /// 取り込んだ郵便番号データベースの検証に失敗したので、使用中のバージョンを切り替えなかった
+    #[error("invalid dataset: {0}")]
+    InvalidDataset(String),
+
+    /// 指定したバージョンを保持していない
+    #[error("dataset version {0} not found")]
+    DatasetVersionNotFound(i64),
+
+    /// 使用中のバージョンより古いバージョンを保持していない
+    #[error("no previous dataset version")]
+    NoPreviousDatasetVersion,
+
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// 郵便番号データベースのバージョン
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetVersion {
+    /// バージョンの ID（取り込むたびに増える）
+    pub id: i64,
+    /// 住所の郵便番号のレコード数
+    pub utf_ken_all_count: usize,
+    /// 事業所の個別郵便番号のレコード数
+    pub jigyosyo_count: usize,
+    /// バージョンを作成した日時
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// 使用中のバージョンかどうか
+    pub active: bool,
+}
+
+/// 検索したバージョンの ID
+///
+/// 検索系のメソッドは一つのバージョンから読んだ結果を、そのバージョンの ID とともに返す。まだ何も
+/// 取り込んでいなければ `None` になる。ユースケースが空のクエリなどでリポジトリを検索しなかった
+/// 場合も `None` になる。
+pub type SearchedVersion = Option<i64>;
+
+/// 取り込みによる住所の郵便番号のレコードの変更の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostalCodeChange {
+    /// レコードが追加された
+    Added,
+    /// レコードが削除された
+    Removed,
+    /// レコードの項目が変更された
+    Changed,
+}
+
+/// 変更された項目の変更前と変更後の値
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FieldChange {
+    /// 項目名（[`UtfKenAllRecord`] のフィールド名）
+    pub field: String,
+    /// 変更前の値（追加された場合は `None`）
+    pub old: Option<String>,
+    /// 変更後の値（削除された場合は `None`）
+    pub new: Option<String>,
+}
+
+/// 住所の郵便番号の変更履歴
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostalCodeHistory {
+    /// 変更履歴の ID（記録するたびに増える）
+    pub id: i64,
+    pub postal_code: String,
+    pub change: PostalCodeChange,
+    /// 変更された項目（追加・削除された場合は全ての項目）
+    pub fields: Vec<FieldChange>,
+    /// 更新の表示（削除された場合は削除前のレコードのもの）
+    pub update_code: UpdateCode,
+    /// 更新理由（削除された場合は削除前のレコードのもの）
+    pub update_reason: UpdateReason,
+    /// 変更を取り込んだバージョンの ID
+    pub dataset_id: i64,
+    /// 変更を取り込んだ日時（取り込んだレコードの `updated_at` と同じ）
+    pub imported_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct UtfKenAllRepositorySearchRequest<'a> {
     pub postal_code: &'a str,
@@ -11,26 +123,270 @@ pub struct UtfKenAllRepositorySearchRequest<'a> {
 
 #[derive(Debug, Clone)]
 pub struct UtfKenAllRepositorySearchResponse {
+    pub records: Vec<PostalCodeRecord>,
+    pub next_page_token: Option<String>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySearchByAddressRequest<'a> {
+    pub address: &'a str,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySearchByAddressResponse {
+    pub records: Vec<UtfKenAllRecord>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySearchByKanaRequest<'a> {
+    pub kana: &'a str,
+    pub page_size: Option<usize>,
+    pub page_token: Option<&'a str>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySearchByKanaResponse {
     pub records: Vec<UtfKenAllRecord>,
     pub next_page_token: Option<String>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 入力補完で前方一致させる項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestField {
+    /// 郵便番号
+    PostalCode,
+    /// 住所（都道府県名・市区町村名・町域名のいずれかから）
+    Address,
+    /// 住所の読み（都道府県名カナ・市区町村名カナ・町域名カナのいずれかから）
+    Kana,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySuggestRequest<'a> {
+    pub field: SuggestField,
+    pub query: &'a str,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositorySuggestResponse {
+    pub records: Vec<UtfKenAllRecord>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositoryGetResponse {
+    pub records: Vec<PostalCodeRecord>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositoryListPrefecturesResponse {
+    pub prefectures: Vec<Prefecture>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositoryListCitiesResponse {
+    pub cities: Vec<City>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtfKenAllRepositoryListTownsResponse {
+    pub towns: Vec<Town>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
 }
 
 /// 郵便番号データベースを扱うリポジトリ
+///
+/// 郵便番号データベースはバージョンごとに保持する。更新系のメソッドは使用中のバージョンを書き換えず、
+/// 新しいバージョンに取り込んで検証してから使用中のバージョンを切り替え、保持する数を超えた古い
+/// バージョンを削除する。検索系のメソッドは使用中のバージョンだけを対象にし、検索の途中で使用中の
+/// バージョンが切り替わっても一つのバージョンから読んだ結果を、そのバージョンの ID とともに返す。
+///
+/// 住所の郵便番号を取り込むたびに、使用中のバージョンから変わったレコードを郵便番号ごとの変更履歴
+/// として記録する（最初の取り込みでは記録しない）。変更履歴はバージョンを削除・切り替えても残す。
 pub trait UtfKenAllRepository: Clone + Send + Sync {
-    type Error: std::error::Error + Send + Sync + 'static;
+    type Error: std::error::Error + Send + Sync + 'static + Into<UtfKenAllRepositoryError>;
+
+    /// 住所の郵便番号と事業所の個別郵便番号をまとめて置き換え、一つのバージョンとして使用中にする
+    ///
+    /// `None` を指定したものは使用中のバージョンのものを引き継ぐ。使用中のバージョンで空でなかった
+    /// 住所の郵便番号・事業所の個別郵便番号が空になる場合は [UtfKenAllRepositoryError::InvalidDataset]
+    /// を返し、使用中のバージョンは切り替えない。
+    fn replace_dataset(
+        &mut self,
+        records: Option<&[UtfKenAllRecord]>,
+        jigyosyo_records: Option<&[JigyosyoRecord]>,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send;
 
     /// 郵便番号データベースを置き換える
+    ///
+    /// 事業所の個別郵便番号は使用中のバージョンのものを引き継ぐ。検証は [Self::replace_dataset] と
+    /// 同じく行う。
     fn replace(
         &mut self,
         records: &[UtfKenAllRecord],
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.replace_dataset(Some(records), None)
+    }
+
+    /// 郵便番号データベースに差分を適用する
+    ///
+    /// `deleted` と一致するレコードを削除してから `added` を挿入する。既存のレコードは書き換えない。
+    /// 検証は [Self::replace_dataset] と同じく行う。
+    fn apply_diff(
+        &mut self,
+        added: &[UtfKenAllRecord],
+        deleted: &[UtfKenAllRecord],
     ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send;
 
+    /// 事業所の個別郵便番号データベースを置き換える
+    ///
+    /// 住所の郵便番号は使用中のバージョンのものを引き継ぐ。検証は [Self::replace_dataset] と同じく
+    /// 行う。
+    fn replace_jigyosyo(
+        &mut self,
+        records: &[JigyosyoRecord],
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.replace_dataset(None, Some(records))
+    }
+
     /// 郵便番号データベースから前方一致でレコードを検索する
+    ///
+    /// 住所の郵便番号と事業所の個別郵便番号の両方を郵便番号順に返す。
+    /// ページトークンは検索条件と発行したバージョンに結びつけて署名する。レコードの ID はバージョン間で
+    /// 変わらないので、更新後も発行したバージョンが残っていれば続きから返す。解釈できない・改ざん
+    /// された場合は [UtfKenAllRepositoryError::InvalidPageToken]、別の検索条件で発行された場合は
+    /// [UtfKenAllRepositoryError::PageTokenMismatch]、発行したバージョンが削除された場合は
+    /// [UtfKenAllRepositoryError::StalePageToken] を返す。
     fn search(
         &self,
         req: UtfKenAllRepositorySearchRequest<'_>,
     ) -> impl std::future::Future<Output = Result<UtfKenAllRepositorySearchResponse, Self::Error>> + Send;
 
+    /// 郵便番号データベースから郵便番号と完全一致するレコードをすべて取得する
+    ///
+    /// 住所の郵便番号、事業所の個別郵便番号の順に返す。一致するレコードがなければ空を返す。
+    fn get_by_postal_code(
+        &self,
+        postal_code: &str,
+    ) -> impl std::future::Future<Output = Result<UtfKenAllRepositoryGetResponse, Self::Error>> + Send;
+
+    /// 郵便番号データベースから複数の郵便番号と完全一致するレコードをまとめて取得する
+    ///
+    /// 郵便番号順に、同じ郵便番号では住所の郵便番号、事業所の個別郵便番号の順に返す。
+    fn get_by_postal_codes(
+        &self,
+        postal_codes: &[String],
+    ) -> impl std::future::Future<Output = Result<UtfKenAllRepositoryGetResponse, Self::Error>> + Send;
+
+    /// 郵便番号データベースから住所（都道府県名＋市区町村名＋町域名）の前方一致でレコードを検索する
+    ///
+    /// 都道府県名は省略されていてもよい。住所と完全一致するレコードを優先して返す。
+    fn search_by_address(
+        &self,
+        req: UtfKenAllRepositorySearchByAddressRequest<'_>,
+    ) -> impl std::future::Future<
+        Output = Result<UtfKenAllRepositorySearchByAddressResponse, Self::Error>,
+    > + Send;
+
+    /// 郵便番号データベースから住所の読み（都道府県名カナ＋市区町村名カナ＋町域名カナ）の前方一致でレコードを検索する
+    ///
+    /// 都道府県名カナは省略されていてもよい。`kana` は全角カタカナに正規化されている前提。
+    /// ページトークンは [Self::search] と同じく扱う。
+    fn search_by_kana(
+        &self,
+        req: UtfKenAllRepositorySearchByKanaRequest<'_>,
+    ) -> impl std::future::Future<
+        Output = Result<UtfKenAllRepositorySearchByKanaResponse, Self::Error>,
+    > + Send;
+
+    /// 入力補完の候補を `field` の前方一致で検索する
+    ///
+    /// 住所の郵便番号だけを対象にし、打鍵ごとに呼ばれることを想定して `limit` 件までしか返さない。
+    /// `field` が住所・住所の読みの場合は、いずれかの項目と完全一致するもの、都道府県から前方一致
+    /// するもの、市区町村から前方一致するもの、町域から前方一致するものの順に並べ、同順位では
+    /// 町域名（読みの場合は町域名カナ）の短いものを優先する。郵便番号の場合は郵便番号順に返す。
+    fn suggest(
+        &self,
+        req: UtfKenAllRepositorySuggestRequest<'_>,
+    ) -> impl std::future::Future<Output = Result<UtfKenAllRepositorySuggestResponse, Self::Error>> + Send;
+
+    /// 郵便番号データベースに含まれる都道府県を都道府県コード順に一覧する
+    fn list_prefectures(
+        &self,
+    ) -> impl std::future::Future<
+        Output = Result<UtfKenAllRepositoryListPrefecturesResponse, Self::Error>,
+    > + Send;
+
+    /// 都道府県コード（2桁）で指定した都道府県の市区町村を全国地方公共団体コード順に一覧する
+    ///
+    /// 都道府県コードを省略するとすべての都道府県の市区町村を返す。
+    fn list_cities(
+        &self,
+        prefecture_code: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<UtfKenAllRepositoryListCitiesResponse, Self::Error>>
+           + Send;
+
+    /// 全国地方公共団体コード（5桁）で指定した市区町村の町域を郵便番号順に一覧する
+    ///
+    /// 町域名が空のレコード（「以下に掲載がない場合」を正規化したもの）は含めない。
+    fn list_towns(
+        &self,
+        local_government_code: &str,
+    ) -> impl std::future::Future<Output = Result<UtfKenAllRepositoryListTownsResponse, Self::Error>>
+           + Send;
+
     /// 郵便番号データベースの総数をカウントする
     fn count(&self) -> impl std::future::Future<Output = Result<usize, Self::Error>> + Send;
+
+    /// 保持している郵便番号データベースのバージョンを新しい順に一覧する
+    fn list_versions(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<DatasetVersion>, Self::Error>> + Send;
+
+    /// 使用中の郵便番号データベースのバージョンの ID を取得する
+    ///
+    /// まだ何も取り込んでいなければ `None` を返す。
+    fn active_version(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<i64>, Self::Error>> + Send;
+
+    /// 保持しているバージョンのうち `id` のものを使用中にする
+    ///
+    /// 保持していなければ [UtfKenAllRepositoryError::DatasetVersionNotFound] を返す。
+    fn activate_version(
+        &mut self,
+        id: i64,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send;
+
+    /// 使用中のバージョンの一つ前のバージョンを使用中にし、その ID を返す
+    ///
+    /// 使用中のバージョンより古いバージョンを保持していなければ
+    /// [UtfKenAllRepositoryError::NoPreviousDatasetVersion] を返す。
+    fn rollback_version(
+        &mut self,
+    ) -> impl std::future::Future<Output = Result<i64, Self::Error>> + Send;
+
+    /// 郵便番号と完全一致する住所の郵便番号の変更履歴を新しい順に一覧する
+    ///
+    /// 同じ取り込みで記録した変更履歴は記録した順に返す。
+    fn list_history(
+        &self,
+        postal_code: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<PostalCodeHistory>, Self::Error>> + Send;
 }
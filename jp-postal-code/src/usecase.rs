@@ -1,16 +1,317 @@
-use crate::repo::{UtfKenAllRepository, UtfKenAllRepositorySearchRequest};
-use jp_postal_code_core::model::UtfKenAllRecord;
+use crate::repo::{
+    DatasetVersion, PostalCodeHistory, SearchedVersion, SuggestField, UtfKenAllRepository,
+    UtfKenAllRepositoryError, UtfKenAllRepositorySearchByAddressRequest,
+    UtfKenAllRepositorySearchByKanaRequest, UtfKenAllRepositorySearchRequest,
+    UtfKenAllRepositorySuggestRequest, DEFAULT_SEARCH_PAGE_SIZE, DEFAULT_SUGGEST_LIMIT,
+    MAX_BATCH_GET_SIZE, MAX_SEARCH_PAGE_SIZE, MAX_SUGGEST_LIMIT,
+};
+use jp_postal_code_core::address::{covers, parse, AreaDictionary, ParsedAddress};
+use jp_postal_code_core::fuzzy::{fold_address, max_edit_distance, prefix_edit_distance};
+use jp_postal_code_core::model::{
+    City, JigyosyoRecord, KenAllRomeRecord, PostalCodeRecord, Prefecture, Town, UtfKenAllRecord,
+};
 use jp_postal_code_core::normalize::{
+    is_kana_query, normalize_address_query, normalize_kana_query, normalize_postal_code_query,
     normalize_utf_ken_all_record_town, normalize_utf_ken_all_record_town_kana,
+    normalize_utf_ken_all_record_town_spec, to_hepburn,
 };
-use jp_postal_code_util::{download, parse_utf_ken_all_zip, UTF_KEN_ALL_URL};
+use jp_postal_code_util::{
+    download, join_ken_all_rome, parse_jigyosyo, parse_jigyosyo_zip, parse_ken_all_rome,
+    parse_ken_all_rome_zip, parse_utf_ken_all, parse_utf_ken_all_zip, JIGYOSYO_URL,
+    KEN_ALL_ROME_URL, UTF_KEN_ALL_URL,
+};
+
+/// 検索系のユースケースで発生するエラー
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// リクエストの値が不正
+    #[error("invalid {field}: {message}")]
+    InvalidArgument {
+        /// 不正な値が指定されたフィールド名
+        field: &'static str,
+        message: String,
+    },
+
+    /// 指定されたリソースが存在しない
+    #[error("{message}")]
+    NotFound { message: String },
+
+    #[error(transparent)]
+    Unexpected(#[from] anyhow::Error),
+}
+
+impl From<UtfKenAllRepositoryError> for Error {
+    fn from(err: UtfKenAllRepositoryError) -> Self {
+        match err {
+            UtfKenAllRepositoryError::InvalidPageToken => Error::InvalidArgument {
+                field: "page_token",
+                message: "page token is malformed".to_string(),
+            },
+            UtfKenAllRepositoryError::PageTokenMismatch => Error::InvalidArgument {
+                field: "page_token",
+                message: "page token was issued for a different query".to_string(),
+            },
+            UtfKenAllRepositoryError::StalePageToken => Error::InvalidArgument {
+                field: "page_token",
+                message: "page token is stale; the dataset version it was issued for has been deleted, so restart from the first page".to_string(),
+            },
+            err => Error::Unexpected(err.into()),
+        }
+    }
+}
+
+/// 検索の最大件数を検証する
+fn validate_page_size(page_size: Option<usize>) -> Result<(), Error> {
+    match page_size {
+        Some(page_size) if page_size == 0 || page_size > MAX_SEARCH_PAGE_SIZE => {
+            Err(Error::InvalidArgument {
+                field: "page_size",
+                message: format!("page size must be between 1 and {MAX_SEARCH_PAGE_SIZE}"),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 郵便番号データベースを置き換える前の検証で、最低限必要なレコード数の既定値
+///
+/// 2025 年時点の `utf_ken_all.csv` は約 12 万件なので、途中で切れたファイルなどを弾ける値にする。
+pub const DEFAULT_IMPORT_MIN_RECORDS: usize = 100_000;
+
+/// 郵便番号データベースを置き換える前の検証で、レコード数の増減の上限（%）の既定値
+///
+/// 月次の更新で増減するレコード数は 1% に満たないので、余裕を持たせた値にする。
+pub const DEFAULT_IMPORT_MAX_CHANGE_PERCENT: f64 = 10.0;
+
+/// 郵便番号データに含まれるべき都道府県の数
+const PREFECTURE_COUNT: usize = 47;
+
+/// 郵便番号データベースを置き換える前の検証の設定
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportOptions {
+    /// 最低限必要なレコード数
+    pub min_records: usize,
+    /// 使用中の郵便番号データベースからのレコード数の増減の上限（%）
+    pub max_change_percent: f64,
+    /// 検証に失敗しても郵便番号データベースを置き換える
+    pub force: bool,
+    /// 検証だけを行い、郵便番号データベースは置き換えない
+    pub dry_run: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            min_records: DEFAULT_IMPORT_MIN_RECORDS,
+            max_change_percent: DEFAULT_IMPORT_MAX_CHANGE_PERCENT,
+            force: false,
+            dry_run: false,
+        }
+    }
+}
+
+/// 郵便番号データベースを置き換える前の検証ルールへの違反
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ImportViolation {
+    /// レコード数が少なすぎる
+    #[error("too few records: {count} (minimum {min})")]
+    TooFewRecords { count: usize, min: usize },
+
+    /// 47 都道府県がそろっていない、または存在しない都道府県コードがある
+    #[error("expected {PREFECTURE_COUNT} prefectures (missing: [{}], unexpected: [{}])", missing.join(", "), unexpected.join(", "))]
+    Prefectures {
+        /// 含まれていない都道府県コード
+        missing: Vec<String>,
+        /// 01〜47 以外の都道府県コード
+        unexpected: Vec<String>,
+    },
+
+    /// 郵便番号が 7 桁の数字でないレコードがある
+    #[error("{count} records have an invalid postal code (e.g. {example:?})")]
+    InvalidPostalCode { count: usize, example: String },
+
+    /// 全国地方公共団体コードが 5 桁の数字でないレコードがある
+    #[error("{count} records have an invalid local government code (e.g. {example:?})")]
+    InvalidLocalGovernmentCode { count: usize, example: String },
+
+    /// 使用中の郵便番号データベースからレコード数が大きく増減する
+    #[error("record count changes by {percent:.1}% from {current} to {count} (maximum {max}%)")]
+    TooLargeChange {
+        current: usize,
+        count: usize,
+        percent: f64,
+        max: f64,
+    },
+}
+
+/// 郵便番号データベースを置き換える前の検証結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportReport {
+    /// 取り込むレコード数
+    pub record_count: usize,
+    /// 使用中の郵便番号データベースのレコード数
+    pub current_count: usize,
+    /// 使用中の郵便番号データベースからのレコード数の増減（%）。空のデータベースでは `None`
+    pub change_percent: Option<f64>,
+    /// 含まれる都道府県の数
+    pub prefecture_count: usize,
+    /// 検証ルールへの違反（空なら検証に成功）
+    pub violations: Vec<ImportViolation>,
+    /// 郵便番号データベースを置き換えたかどうか
+    pub applied: bool,
+}
+
+/// 検証に失敗したため郵便番号データベースを置き換えなかった
+#[derive(Debug, thiserror::Error)]
+#[error("postal code records failed validation: {}", .0.violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+pub struct ImportValidationError(pub ImportReport);
+
+fn is_digits(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// 取り込むレコードを検証ルールと照らし合わせる
+fn validate_import(
+    records: &[UtfKenAllRecord],
+    current_count: usize,
+    options: &ImportOptions,
+) -> ImportReport {
+    let mut violations = vec![];
+    if records.len() < options.min_records {
+        violations.push(ImportViolation::TooFewRecords {
+            count: records.len(),
+            min: options.min_records,
+        });
+    }
+
+    let prefectures = records
+        .iter()
+        .filter(|r| is_digits(&r.local_government_code, 5))
+        .map(|r| &r.local_government_code[..2])
+        .collect::<std::collections::BTreeSet<_>>();
+    let expected = (1..=PREFECTURE_COUNT)
+        .map(|n| format!("{n:02}"))
+        .collect::<Vec<_>>();
+    let missing = expected
+        .iter()
+        .filter(|code| !prefectures.contains(code.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    let unexpected = prefectures
+        .iter()
+        .filter(|code| !expected.iter().any(|e| e == *code))
+        .map(|code| code.to_string())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() || !unexpected.is_empty() {
+        violations.push(ImportViolation::Prefectures {
+            missing,
+            unexpected,
+        });
+    }
+
+    let invalid = records
+        .iter()
+        .filter(|r| !is_digits(&r.postal_code, 7))
+        .collect::<Vec<_>>();
+    if let Some(r) = invalid.first() {
+        violations.push(ImportViolation::InvalidPostalCode {
+            count: invalid.len(),
+            example: r.postal_code.clone(),
+        });
+    }
+    let invalid = records
+        .iter()
+        .filter(|r| !is_digits(&r.local_government_code, 5))
+        .collect::<Vec<_>>();
+    if let Some(r) = invalid.first() {
+        violations.push(ImportViolation::InvalidLocalGovernmentCode {
+            count: invalid.len(),
+            example: r.local_government_code.clone(),
+        });
+    }
+
+    let change_percent = (current_count > 0)
+        .then(|| (records.len() as f64 - current_count as f64) / current_count as f64 * 100.0);
+    if let Some(percent) = change_percent {
+        if percent.abs() > options.max_change_percent {
+            violations.push(ImportViolation::TooLargeChange {
+                current: current_count,
+                count: records.len(),
+                percent,
+                max: options.max_change_percent,
+            });
+        }
+    }
+
+    ImportReport {
+        record_count: records.len(),
+        current_count,
+        change_percent,
+        prefecture_count: prefectures.len(),
+        violations,
+        applied: false,
+    }
+}
+
+/// 検証に成功した場合（または `force` の場合）に郵便番号データベースを置き換える
+///
+/// `jigyosyo` を指定した場合は事業所の個別郵便番号も合わせて置き換え、一つのバージョンにする。
+/// 指定しなかった場合は使用中のバージョンのものを引き継ぐ。`dry_run` の場合は検証結果を返すだけで
+/// 置き換えない。
+async fn replace_validated<R>(
+    repo: &mut R,
+    records: &[UtfKenAllRecord],
+    jigyosyo: Option<&[JigyosyoRecord]>,
+    options: &ImportOptions,
+) -> Result<ImportReport, anyhow::Error>
+where
+    R: UtfKenAllRepository,
+{
+    let report = validate_import(records, repo.count().await?, options);
+    for violation in &report.violations {
+        tracing::warn!(%violation, "Postal code records violate a validation rule");
+    }
+    if options.dry_run {
+        tracing::info!(
+            record_count = records.len(),
+            "Dry run; the database is not replaced"
+        );
+        return Ok(report);
+    }
+    if !report.violations.is_empty() {
+        if !options.force {
+            return Err(ImportValidationError(report).into());
+        }
+        tracing::warn!("Replace database despite the validation failures");
+    }
+    tracing::info!(
+        record_count = records.len(),
+        jigyosyo_record_count = jigyosyo.map(|r| r.len()),
+        "Replace database with the new records"
+    );
+    repo.replace_dataset(Some(records), jigyosyo).await?;
+    Ok(ImportReport {
+        applied: true,
+        ..report
+    })
+}
 
 /// 郵便番号データベースを更新する
-#[tracing::instrument(skip(repo, utf_ken_all_zip_url))]
+///
+/// `utf_ken_all.zip` と合わせて `KEN_ALL_ROME.zip` をダウンロードし、住所のローマ字表記を補う。
+/// `KEN_ALL_ROME.zip` を取得できなかった場合は警告を出し、ローマ字表記を仮名から変換して取り込みを続ける。
+/// 事業所の個別郵便番号も `jigyosyo.zip` をダウンロードして合わせて置き換え、一度の取り込みを一つの
+/// バージョンにする（`dry_run` の場合はダウンロードしない）。
+/// 置き換える前に `options` の検証ルールでレコードを検証し、違反があれば置き換えずに
+/// [`ImportValidationError`] を返す。
+#[tracing::instrument(skip(repo, utf_ken_all_zip_url, ken_all_rome_zip_url, jigyosyo_zip_url))]
 pub async fn update_postal_code_database<R, S>(
     repo: &mut R,
     utf_ken_all_zip_url: Option<S>,
-) -> Result<(), anyhow::Error>
+    ken_all_rome_zip_url: Option<S>,
+    jigyosyo_zip_url: Option<S>,
+    options: &ImportOptions,
+) -> Result<ImportReport, anyhow::Error>
 where
     R: UtfKenAllRepository,
     S: Into<String>,
@@ -22,6 +323,13 @@ where
             UTF_KEN_ALL_URL.into()
         }
     };
+    let ken_all_rome_zip_url = {
+        if let Some(ken_all_rome_zip_url) = ken_all_rome_zip_url {
+            ken_all_rome_zip_url.into()
+        } else {
+            KEN_ALL_ROME_URL.into()
+        }
+    };
     let mut tempfile = tempfile::tempfile()?;
     tracing::info!(
         ?utf_ken_all_zip_url,
@@ -29,31 +337,256 @@ where
         "Download utf_ken_all.zip into a temp file"
     );
     download(utf_ken_all_zip_url, &mut tempfile).await?;
-    tracing::info!(?tempfile, "Parse utf_ken_all.zip to records");
-    let records = parse_utf_ken_all_zip(tempfile)?
+    tracing::info!("Parse utf_ken_all.zip to records");
+    let records = parse_utf_ken_all_zip(tempfile)?;
+    let rome_records = match download_ken_all_rome(ken_all_rome_zip_url).await {
+        Ok(rome_records) => rome_records,
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                "Failed to get KEN_ALL_ROME.zip; convert romanized addresses from kana instead"
+            );
+            vec![]
+        }
+    };
+    let records = normalize_records(join_ken_all_rome(records, &rome_records));
+    let jigyosyo_records = if options.dry_run {
+        None
+    } else {
+        Some(download_jigyosyo(jigyosyo_zip_url).await?)
+    };
+    replace_validated(repo, &records, jigyosyo_records.as_deref(), options).await
+}
+
+/// `KEN_ALL_ROME.zip` をダウンロードしてレコードに変換する
+async fn download_ken_all_rome(
+    ken_all_rome_zip_url: String,
+) -> Result<Vec<KenAllRomeRecord>, anyhow::Error> {
+    let mut tempfile = tempfile::tempfile()?;
+    tracing::info!(
+        ?ken_all_rome_zip_url,
+        ?tempfile,
+        "Download KEN_ALL_ROME.zip into a temp file"
+    );
+    download(ken_all_rome_zip_url, &mut tempfile).await?;
+    tracing::info!(?tempfile, "Parse KEN_ALL_ROME.zip to records");
+    Ok(parse_ken_all_rome_zip(tempfile)?)
+}
+
+/// ローカルのファイルから郵便番号データベースを更新する
+///
+/// `path` には `utf_ken_all.zip` または展開済みの `utf_ken_all.csv` を指定する（内容から判定する）。
+/// `ken_all_rome_path` には `KEN_ALL_ROME.zip` または `KEN_ALL_ROME.CSV` を指定でき、省略した場合は
+/// 住所のローマ字表記を仮名から変換する。`jigyosyo_path` には `jigyosyo.zip` または `JIGYOSYO.CSV`
+/// を指定でき、合わせて一つのバージョンとして置き換える（省略した場合は使用中のバージョンのものを
+/// 引き継ぐ）。ネットワークにはアクセスしない。
+/// [update_postal_code_database] と同じく、置き換える前にレコードを検証する。
+#[tracing::instrument(skip(repo))]
+pub async fn update_postal_code_database_from_file<R, P>(
+    repo: &mut R,
+    path: P,
+    ken_all_rome_path: Option<P>,
+    jigyosyo_path: Option<P>,
+    options: &ImportOptions,
+) -> Result<ImportReport, anyhow::Error>
+where
+    R: UtfKenAllRepository,
+    P: AsRef<std::path::Path> + std::fmt::Debug,
+{
+    let file = std::fs::File::open(path.as_ref())?;
+    tracing::info!(?file, "Parse utf_ken_all file to records");
+    let records = parse_utf_ken_all(file)?;
+    let rome_records = match ken_all_rome_path {
+        Some(ken_all_rome_path) => {
+            let file = std::fs::File::open(ken_all_rome_path.as_ref())?;
+            tracing::info!(?file, "Parse KEN_ALL_ROME file to records");
+            parse_ken_all_rome(file)?
+        }
+        None => vec![],
+    };
+    let records = normalize_records(join_ken_all_rome(records, &rome_records));
+    let jigyosyo_records = match jigyosyo_path {
+        Some(jigyosyo_path) => {
+            let file = std::fs::File::open(jigyosyo_path.as_ref())?;
+            tracing::info!(?file, "Parse jigyosyo file to records");
+            Some(parse_jigyosyo(file)?)
+        }
+        None => None,
+    };
+    replace_validated(repo, &records, jigyosyo_records.as_deref(), options).await
+}
+
+/// 郵便番号データベースに月次の差分（`utf_add_YYMM.zip` と `utf_del_YYMM.zip`）を適用する
+///
+/// 全件を置き換える [update_postal_code_database] と異なり、影響のあるレコードのみを削除・挿入する。
+/// ローマ字版の差分は配布されていないため、追加するレコードのローマ字表記は仮名から変換する。
+#[tracing::instrument(skip(repo, utf_add_zip_url, utf_del_zip_url))]
+pub async fn apply_postal_code_database_diff<R, S>(
+    repo: &mut R,
+    utf_add_zip_url: S,
+    utf_del_zip_url: S,
+) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository,
+    S: Into<String>,
+{
+    let utf_add_zip_url = utf_add_zip_url.into();
+    let utf_del_zip_url = utf_del_zip_url.into();
+    let mut add_tempfile = tempfile::tempfile()?;
+    let mut del_tempfile = tempfile::tempfile()?;
+    tracing::info!(
+        ?utf_add_zip_url,
+        ?utf_del_zip_url,
+        "Download utf_add.zip and utf_del.zip into temp files"
+    );
+    download(utf_add_zip_url, &mut add_tempfile).await?;
+    download(utf_del_zip_url, &mut del_tempfile).await?;
+    tracing::info!("Parse utf_add.zip and utf_del.zip to records");
+    let added = normalize_records(join_ken_all_rome(parse_utf_ken_all_zip(add_tempfile)?, &[]));
+    let deleted = normalize_records(parse_utf_ken_all_zip(del_tempfile)?);
+    tracing::info!(
+        added = added.len(),
+        deleted = deleted.len(),
+        "Apply diff to database"
+    );
+    repo.apply_diff(&added, &deleted).await?;
+    Ok(())
+}
+
+/// 町域名と町域名カナを正規化し、複数の町域を含むレコードを町域ごとに分割する
+///
+/// 分割した町域ごとに、丁目・地割・番地の範囲を [`TownSpec`](jp_postal_code_core::model::TownSpec) として持たせる。
+///
+/// 町域名が変わったレコードは `KEN_ALL_ROME.CSV` のローマ字表記と対応しなくなるため、町域名の
+/// ローマ字表記を正規化した町域名カナから変換し直す。
+fn normalize_records(records: Vec<UtfKenAllRecord>) -> Vec<UtfKenAllRecord> {
+    records
         .into_iter()
         .flat_map(|r| {
             let towns = normalize_utf_ken_all_record_town(&r);
             let town_kanas = normalize_utf_ken_all_record_town_kana(&r);
+            let town_specs = normalize_utf_ken_all_record_town_spec(&r);
             towns
                 .into_iter()
-                .zip(town_kanas.into_iter())
-                .map(|(town, town_kana)| UtfKenAllRecord {
+                .zip(town_kanas)
+                .zip(town_specs)
+                .map(|((town, town_kana), town_spec)| UtfKenAllRecord {
+                    town_roman: if town == r.town {
+                        r.town_roman.clone()
+                    } else {
+                        to_hepburn(&town_kana)
+                    },
                     town,
                     town_kana,
+                    town_spec,
                     ..r.clone()
                 })
                 .collect::<Vec<_>>()
         })
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
+
+/// 事業所の個別郵便番号データベースを更新する
+///
+/// 住所の郵便番号は使用中のバージョンのものを引き継ぐ。
+#[tracing::instrument(skip(repo, jigyosyo_zip_url))]
+pub async fn update_jigyosyo_database<R, S>(
+    repo: &mut R,
+    jigyosyo_zip_url: Option<S>,
+) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository,
+    S: Into<String>,
+{
+    let records = download_jigyosyo(jigyosyo_zip_url).await?;
     tracing::info!(
         record_count = records.len(),
-        "Replace database with the new records"
+        "Replace jigyosyo database with the new records"
+    );
+    repo.replace_jigyosyo(&records).await?;
+    Ok(())
+}
+
+/// `jigyosyo.zip` をダウンロードしてレコードにする（URL を省略した場合は日本郵便のもの）
+async fn download_jigyosyo<S>(
+    jigyosyo_zip_url: Option<S>,
+) -> Result<Vec<JigyosyoRecord>, anyhow::Error>
+where
+    S: Into<String>,
+{
+    let jigyosyo_zip_url = {
+        if let Some(jigyosyo_zip_url) = jigyosyo_zip_url {
+            jigyosyo_zip_url.into()
+        } else {
+            JIGYOSYO_URL.into()
+        }
+    };
+    let mut tempfile = tempfile::tempfile()?;
+    tracing::info!(
+        ?jigyosyo_zip_url,
+        ?tempfile,
+        "Download jigyosyo.zip into a temp file"
+    );
+    download(jigyosyo_zip_url, &mut tempfile).await?;
+    tracing::info!(?tempfile, "Parse jigyosyo.zip to records");
+    Ok(parse_jigyosyo_zip(tempfile)?)
+}
+
+/// ローカルのファイルから事業所の個別郵便番号データベースを更新する
+///
+/// `path` には `jigyosyo.zip` または展開済みの `JIGYOSYO.CSV` を指定する（内容から判定する）。
+/// 住所の郵便番号は使用中のバージョンのものを引き継ぐ。ネットワークにはアクセスしない。
+#[tracing::instrument(skip(repo))]
+pub async fn update_jigyosyo_database_from_file<R, P>(
+    repo: &mut R,
+    path: P,
+) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository,
+    P: AsRef<std::path::Path> + std::fmt::Debug,
+{
+    let file = std::fs::File::open(path.as_ref())?;
+    tracing::info!(?file, "Parse jigyosyo file to records");
+    let records = parse_jigyosyo(file)?;
+    tracing::info!(
+        record_count = records.len(),
+        "Replace jigyosyo database with the new records"
     );
-    repo.replace(&records).await?;
+    repo.replace_jigyosyo(&records).await?;
     Ok(())
 }
 
+/// 保持している郵便番号データベースのバージョンを新しい順に一覧する
+#[tracing::instrument(skip(repo))]
+pub async fn list_dataset_versions<R>(repo: &R) -> Result<Vec<DatasetVersion>, anyhow::Error>
+where
+    R: UtfKenAllRepository,
+{
+    Ok(repo.list_versions().await?)
+}
+
+/// 保持している郵便番号データベースのバージョンのうち `id` のものを使用中にする
+#[tracing::instrument(skip(repo))]
+pub async fn activate_dataset_version<R>(repo: &mut R, id: i64) -> Result<(), anyhow::Error>
+where
+    R: UtfKenAllRepository,
+{
+    repo.activate_version(id).await?;
+    tracing::info!(id, "Activated dataset version");
+    Ok(())
+}
+
+/// 使用中の郵便番号データベースを一つ前のバージョンに戻し、戻した先のバージョンの ID を返す
+#[tracing::instrument(skip(repo))]
+pub async fn rollback_dataset_version<R>(repo: &mut R) -> Result<i64, anyhow::Error>
+where
+    R: UtfKenAllRepository,
+{
+    let id = repo.rollback_version().await?;
+    tracing::info!(id, "Rolled back dataset version");
+    Ok(id)
+}
+
 #[derive(Debug)]
 pub struct SearchPostalCodeRequest<P, T>
 where
@@ -67,62 +600,1480 @@ where
 
 #[derive(Debug)]
 pub struct SearchPostalCodeResponse {
-    pub records: Vec<UtfKenAllRecord>,
+    pub records: Vec<PostalCodeRecord>,
     pub next_page_token: Option<String>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
 }
 
 /// 郵便番号を検索する
+///
+/// 住所の郵便番号と事業所の個別郵便番号の両方が検索対象になる。
 #[tracing::instrument(skip(repo))]
 pub async fn search_postal_code<R, P, T>(
     repo: &R,
     req: SearchPostalCodeRequest<P, T>,
-) -> Result<SearchPostalCodeResponse, anyhow::Error>
+) -> Result<SearchPostalCodeResponse, Error>
 where
     R: UtfKenAllRepository,
     P: AsRef<str> + std::fmt::Debug,
     T: AsRef<str> + std::fmt::Debug,
 {
-    let postal_code = req.postal_code.as_ref();
+    let postal_code = normalize_postal_code_query(req.postal_code.as_ref()).map_err(|e| {
+        Error::InvalidArgument {
+            field: "postal_code",
+            message: e.to_string(),
+        }
+    })?;
+    let postal_code = postal_code.as_str();
     let page_size = req.page_size;
-    let page_token = req.page_token.as_ref().map(|s| s.as_ref());
+    validate_page_size(page_size)?;
+    // 空のページトークンは指定されなかったものとして扱う
+    let page_token = req
+        .page_token
+        .as_ref()
+        .map(|s| s.as_ref())
+        .filter(|s| !s.is_empty());
     let response = repo
         .search(UtfKenAllRepositorySearchRequest {
             postal_code,
             page_size,
             page_token,
         })
-        .await?;
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
     Ok(SearchPostalCodeResponse {
         records: response.records,
         next_page_token: response.next_page_token,
+        dataset_version: response.dataset_version,
     })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::infra::ephemeral::UtfKenAllRepositoryEphemeral;
+#[derive(Debug)]
+pub struct GetPostalCodeRequest<P>
+where
+    P: AsRef<str>,
+{
+    pub postal_code: P,
+}
 
-    #[tokio::test]
-    async fn test_update_utf_ken_all_database() {
-        let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("GET", "/zipcode/dl/utf/zip/utf_ken_all.zip")
-            .with_status(200)
-            .with_header("content-type", "application/zip")
-            .with_body_from_file("./testdata/partial_utf_ken_all.zip")
-            .create_async()
-            .await;
+#[derive(Debug)]
+pub struct GetPostalCodeResponse {
+    pub records: Vec<PostalCodeRecord>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
 
-        let mut repo = UtfKenAllRepositoryEphemeral::default();
-        update_postal_code_database(
+/// 郵便番号と完全一致する住所をすべて取得する
+///
+/// 郵便番号は検索と同じ規則で正規化し、7桁でなければ [Error::InvalidArgument] を返す。
+/// 一致するレコードがなければ [Error::NotFound] を返す。
+#[tracing::instrument(skip(repo))]
+pub async fn get_postal_code<R, P>(
+    repo: &R,
+    req: GetPostalCodeRequest<P>,
+) -> Result<GetPostalCodeResponse, Error>
+where
+    R: UtfKenAllRepository,
+    P: AsRef<str> + std::fmt::Debug,
+{
+    let postal_code = normalize_postal_code_query(req.postal_code.as_ref()).map_err(|e| {
+        Error::InvalidArgument {
+            field: "postal_code",
+            message: e.to_string(),
+        }
+    })?;
+    if postal_code.len() != 7 {
+        return Err(Error::InvalidArgument {
+            field: "postal_code",
+            message: "postal code must be 7 digits".to_string(),
+        });
+    }
+    let response = repo
+        .get_by_postal_code(&postal_code)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    if response.records.is_empty() {
+        return Err(Error::NotFound {
+            message: format!("postal code {postal_code} is not found"),
+        });
+    }
+    Ok(GetPostalCodeResponse {
+        records: response.records,
+        dataset_version: response.dataset_version,
+    })
+}
+
+#[derive(Debug)]
+pub struct BatchGetPostalCodesRequest<P>
+where
+    P: AsRef<str>,
+{
+    pub postal_codes: Vec<P>,
+}
+
+#[derive(Debug)]
+pub struct BatchGetPostalCodesResponse {
+    /// 見つかった郵便番号ごとの住所（入力順）
+    pub results: Vec<BatchGetPostalCodesResult>,
+    /// 見つからなかった郵便番号（入力順）
+    pub not_found: Vec<String>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+#[derive(Debug)]
+pub struct BatchGetPostalCodesResult {
+    /// 入力された郵便番号（正規化前）
+    pub postal_code: String,
+    pub records: Vec<PostalCodeRecord>,
+}
+
+/// 複数の郵便番号と完全一致する住所をまとめて取得する
+///
+/// 結果は入力された郵便番号（正規化前の文字列）ごとにまとめ、重複した入力は一度だけ返す。
+/// 正規化できない郵便番号や7桁でない郵便番号は見つからなかったものとして扱う。
+#[tracing::instrument(skip(repo, req), fields(count = req.postal_codes.len()))]
+pub async fn batch_get_postal_codes<R, P>(
+    repo: &R,
+    req: BatchGetPostalCodesRequest<P>,
+) -> Result<BatchGetPostalCodesResponse, Error>
+where
+    R: UtfKenAllRepository,
+    P: AsRef<str>,
+{
+    if req.postal_codes.len() > MAX_BATCH_GET_SIZE {
+        return Err(Error::InvalidArgument {
+            field: "postal_codes",
+            message: format!("at most {MAX_BATCH_GET_SIZE} postal codes can be requested at once"),
+        });
+    }
+    // 入力ごとに正規化した郵便番号を求める
+    let mut seen = std::collections::HashSet::new();
+    let inputs = req
+        .postal_codes
+        .iter()
+        .map(|p| p.as_ref())
+        .filter(|p| seen.insert(*p))
+        .map(|p| {
+            let normalized = normalize_postal_code_query(p)
+                .ok()
+                .filter(|code| code.len() == 7);
+            (p, normalized)
+        })
+        .collect::<Vec<_>>();
+    let mut postal_codes = inputs
+        .iter()
+        .filter_map(|(_, code)| code.clone())
+        .collect::<Vec<_>>();
+    postal_codes.sort();
+    postal_codes.dedup();
+
+    let (records, dataset_version) = if postal_codes.is_empty() {
+        (vec![], None)
+    } else {
+        let response = repo
+            .get_by_postal_codes(&postal_codes)
+            .await
+            .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+        (response.records, response.dataset_version)
+    };
+    let mut grouped = std::collections::HashMap::<String, Vec<PostalCodeRecord>>::new();
+    for record in records {
+        grouped
+            .entry(record.postal_code().to_string())
+            .or_default()
+            .push(record);
+    }
+
+    let mut results = vec![];
+    let mut not_found = vec![];
+    for (input, code) in inputs {
+        match code.and_then(|code| grouped.get(&code)) {
+            Some(records) => results.push(BatchGetPostalCodesResult {
+                postal_code: input.to_string(),
+                records: records.clone(),
+            }),
+            None => not_found.push(input.to_string()),
+        }
+    }
+    Ok(BatchGetPostalCodesResponse {
+        results,
+        not_found,
+        dataset_version,
+    })
+}
+
+/// 郵便番号の住所の変更履歴を取り込みの新しい順に一覧する
+///
+/// 郵便番号は検索と同じ規則で正規化し、7桁でなければ [Error::InvalidArgument] を返す。
+/// 廃止された郵便番号でも変更履歴があれば返し、変更履歴がなければ空を返す。
+#[tracing::instrument(skip(repo))]
+pub async fn list_postal_code_history<R>(
+    repo: &R,
+    postal_code: &str,
+) -> Result<Vec<PostalCodeHistory>, Error>
+where
+    R: UtfKenAllRepository,
+{
+    let postal_code =
+        normalize_postal_code_query(postal_code).map_err(|e| Error::InvalidArgument {
+            field: "postal_code",
+            message: e.to_string(),
+        })?;
+    validate_code("postal_code", &postal_code, 7)?;
+    let history = repo
+        .list_history(&postal_code)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    Ok(history)
+}
+
+/// 数字のみからなる指定桁数のコードか検証する
+fn validate_code(field: &'static str, code: &str, digits: usize) -> Result<(), Error> {
+    if code.len() != digits || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidArgument {
+            field,
+            message: format!("{} must be {digits} digits", field.replace('_', " ")),
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct ListPrefecturesResponse {
+    pub prefectures: Vec<Prefecture>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 都道府県を都道府県コード順に一覧する
+#[tracing::instrument(skip(repo))]
+pub async fn list_prefectures<R>(repo: &R) -> Result<ListPrefecturesResponse, Error>
+where
+    R: UtfKenAllRepository,
+{
+    let response = repo
+        .list_prefectures()
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    Ok(ListPrefecturesResponse {
+        prefectures: response.prefectures,
+        dataset_version: response.dataset_version,
+    })
+}
+
+#[derive(Debug)]
+pub struct ListCitiesResponse {
+    pub cities: Vec<City>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 都道府県の市区町村を全国地方公共団体コード順に一覧する
+///
+/// 都道府県コードは2桁の数字で指定する。該当する市区町村がなければ [Error::NotFound] を返す。
+#[tracing::instrument(skip(repo))]
+pub async fn list_cities<R>(repo: &R, prefecture_code: &str) -> Result<ListCitiesResponse, Error>
+where
+    R: UtfKenAllRepository,
+{
+    validate_code("prefecture_code", prefecture_code, 2)?;
+    let response = repo
+        .list_cities(Some(prefecture_code))
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    if response.cities.is_empty() {
+        return Err(Error::NotFound {
+            message: format!("prefecture {prefecture_code} is not found"),
+        });
+    }
+    Ok(ListCitiesResponse {
+        cities: response.cities,
+        dataset_version: response.dataset_version,
+    })
+}
+
+#[derive(Debug)]
+pub struct ListTownsResponse {
+    pub towns: Vec<Town>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 市区町村の町域を郵便番号順に一覧する
+///
+/// 全国地方公共団体コードは5桁の数字で指定する。該当する町域がなければ [Error::NotFound] を返す。
+#[tracing::instrument(skip(repo))]
+pub async fn list_towns<R>(
+    repo: &R,
+    local_government_code: &str,
+) -> Result<ListTownsResponse, Error>
+where
+    R: UtfKenAllRepository,
+{
+    validate_code("local_government_code", local_government_code, 5)?;
+    let response = repo
+        .list_towns(local_government_code)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    if response.towns.is_empty() {
+        return Err(Error::NotFound {
+            message: format!("city {local_government_code} is not found"),
+        });
+    }
+    Ok(ListTownsResponse {
+        towns: response.towns,
+        dataset_version: response.dataset_version,
+    })
+}
+
+#[derive(Debug)]
+pub struct SearchPostalCodeByAddressRequest<A>
+where
+    A: AsRef<str>,
+{
+    pub address: A,
+    pub page_size: Option<usize>,
+    /// 一致する住所がない場合に、表記揺れや入力の誤りを許容した候補を探すかどうか
+    pub did_you_mean: bool,
+}
+
+#[derive(Debug)]
+pub struct SearchPostalCodeByAddressResponse {
+    pub records: Vec<UtfKenAllRecord>,
+    /// 表記揺れや入力の誤りを許容して見つけた候補（近い順）
+    ///
+    /// `did_you_mean` を指定し、`records` が空の場合にのみ探す。
+    pub did_you_mean: Vec<UtfKenAllRecord>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 住所から郵便番号を検索する
+///
+/// 住所は町域と同じ規則で正規化してから検索し、一致度の高い順に候補を返す。
+/// `did_you_mean` を指定した場合は、一致する住所がなければ [`find_did_you_mean`] で候補を探す。
+#[tracing::instrument(skip(repo))]
+pub async fn search_postal_code_by_address<R, A>(
+    repo: &R,
+    req: SearchPostalCodeByAddressRequest<A>,
+) -> Result<SearchPostalCodeByAddressResponse, Error>
+where
+    R: UtfKenAllRepository,
+    A: AsRef<str> + std::fmt::Debug,
+{
+    validate_page_size(req.page_size)?;
+    let address = normalize_address_query(req.address.as_ref());
+    // 空のクエリで全件を返さないようにする
+    if address.is_empty() {
+        return Ok(SearchPostalCodeByAddressResponse {
+            records: vec![],
+            did_you_mean: vec![],
+            dataset_version: None,
+        });
+    }
+    let response = repo
+        .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+            address: &address,
+            page_size: req.page_size,
+        })
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    let (did_you_mean, dataset_version) = if req.did_you_mean && response.records.is_empty() {
+        find_did_you_mean(
+            repo,
+            &address,
+            req.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE),
+        )
+        .await?
+    } else {
+        (vec![], response.dataset_version)
+    };
+    Ok(SearchPostalCodeByAddressResponse {
+        records: response.records,
+        did_you_mean,
+        dataset_version,
+    })
+}
+
+/// 「もしかして」の候補を探すときに町域まで調べる市区町村の最大数
+const MAX_DID_YOU_MEAN_CITIES: usize = 10;
+
+/// 表記揺れや入力の誤りを許容して住所の候補を探す
+///
+/// 住所を [`fold_address`] で変換し、都道府県名（省略可）、市区町村名、町域名の順に先頭から
+/// 照合する。市区町村名と町域名はそれぞれ編集距離が [`max_edit_distance`] 以内のものを候補とし、
+/// 距離の合計が小さい順、同じ距離なら町域名の長い（より詳しい）順に並べる。町域名の後ろに続く
+/// 番地などは距離に含めない。候補は最後に読んだバージョンの ID とともに返す。
+async fn find_did_you_mean<R>(
+    repo: &R,
+    address: &str,
+    limit: usize,
+) -> Result<(Vec<UtfKenAllRecord>, Option<i64>), Error>
+where
+    R: UtfKenAllRepository,
+{
+    let query = fold_address(address);
+    let prefectures = repo
+        .list_prefectures()
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?
+        .prefectures;
+    // 都道府県名は省略されることがあるので、一致しなければすべての都道府県を対象にする
+    let (prefecture_code, query) = match prefectures
+        .iter()
+        .find(|p| query.starts_with(&fold_address(&p.name)))
+    {
+        Some(p) => (Some(p.code.as_str()), &query[fold_address(&p.name).len()..]),
+        None => (None, query.as_str()),
+    };
+
+    // 市区町村名は最も近いものだけを候補にする
+    let mut cities = vec![];
+    let response = repo
+        .list_cities(prefecture_code)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    for city in response.cities {
+        let name = fold_address(&city.name);
+        let (distance, len) = prefix_edit_distance(&name, query);
+        if distance <= max_edit_distance(&name) {
+            cities.push((distance, city, len));
+        }
+    }
+    let Some(min_distance) = cities.iter().map(|(distance, ..)| *distance).min() else {
+        return Ok((vec![], response.dataset_version));
+    };
+    cities.retain(|(distance, ..)| *distance == min_distance);
+    cities.truncate(MAX_DID_YOU_MEAN_CITIES);
+
+    let mut candidates = vec![];
+    for (city_distance, city, len) in cities {
+        let rest = query.chars().skip(len).collect::<String>();
+        let towns = repo
+            .list_towns(&city.local_government_code)
+            .await
+            .map_err(Into::<UtfKenAllRepositoryError>::into)?
+            .towns;
+        for town in towns {
+            let name = fold_address(&town.name);
+            let (distance, _) = prefix_edit_distance(&name, &rest);
+            if distance <= max_edit_distance(&name) {
+                candidates.push((
+                    city_distance + distance,
+                    std::cmp::Reverse(name.chars().count()),
+                    town,
+                ));
+            }
+        }
+    }
+    candidates.sort_by(|(distance_a, len_a, a), (distance_b, len_b, b)| {
+        (distance_a, len_a, &a.postal_code, &a.name).cmp(&(
+            distance_b,
+            len_b,
+            &b.postal_code,
+            &b.name,
+        ))
+    });
+    candidates.truncate(limit);
+
+    // 町域の一覧には住所の詳細が含まれないので、郵便番号からレコードを引き直す
+    let postal_codes = candidates
+        .iter()
+        .map(|(.., town)| town.postal_code.clone())
+        .collect::<Vec<_>>();
+    let response = repo
+        .get_by_postal_codes(&postal_codes)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    let did_you_mean = candidates
+        .into_iter()
+        .filter_map(|(.., town)| {
+            response.records.iter().find_map(|r| match r {
+                PostalCodeRecord::Address(r)
+                    if r.postal_code == town.postal_code
+                        && r.local_government_code == town.local_government_code
+                        && r.town == town.name =>
+                {
+                    Some(r.clone())
+                }
+                _ => None,
+            })
+        })
+        .collect();
+    Ok((did_you_mean, response.dataset_version))
+}
+
+#[derive(Debug)]
+pub struct SearchPostalCodeByKanaRequest<K, T>
+where
+    K: AsRef<str>,
+    T: AsRef<str>,
+{
+    pub kana: K,
+    pub page_size: Option<usize>,
+    pub page_token: Option<T>,
+}
+
+#[derive(Debug)]
+pub struct SearchPostalCodeByKanaResponse {
+    pub records: Vec<UtfKenAllRecord>,
+    pub next_page_token: Option<String>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 住所の読みから郵便番号を検索する
+///
+/// ひらがな・全角カタカナ・半角カタカナのいずれで指定してもよく、全角カタカナに正規化してから
+/// 前方一致で検索する。
+#[tracing::instrument(skip(repo))]
+pub async fn search_postal_code_by_kana<R, K, T>(
+    repo: &R,
+    req: SearchPostalCodeByKanaRequest<K, T>,
+) -> Result<SearchPostalCodeByKanaResponse, Error>
+where
+    R: UtfKenAllRepository,
+    K: AsRef<str> + std::fmt::Debug,
+    T: AsRef<str> + std::fmt::Debug,
+{
+    validate_page_size(req.page_size)?;
+    let kana = normalize_kana_query(req.kana.as_ref());
+    // 空のクエリで全件を返さないようにする
+    if kana.is_empty() {
+        return Ok(SearchPostalCodeByKanaResponse {
+            records: vec![],
+            next_page_token: None,
+            dataset_version: None,
+        });
+    }
+    // 空のページトークンは指定されなかったものとして扱う
+    let page_token = req
+        .page_token
+        .as_ref()
+        .map(|s| s.as_ref())
+        .filter(|s| !s.is_empty());
+    let response = repo
+        .search_by_kana(UtfKenAllRepositorySearchByKanaRequest {
+            kana: &kana,
+            page_size: req.page_size,
+            page_token,
+        })
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    Ok(SearchPostalCodeByKanaResponse {
+        records: response.records,
+        next_page_token: response.next_page_token,
+        dataset_version: response.dataset_version,
+    })
+}
+
+#[derive(Debug)]
+pub struct SuggestRequest<Q>
+where
+    Q: AsRef<str>,
+{
+    pub query: Q,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct SuggestResponse {
+    pub suggestions: Vec<Suggestion>,
+    /// 検索したバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 入力補完の候補
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// クエリと一致した項目
+    pub field: SuggestField,
+    /// 候補として表示する文字列（郵便番号、または都道府県名から町域名までの住所やその読み）
+    pub text: String,
+    /// `text` のうちクエリと一致した範囲（文字単位）
+    pub highlight: std::ops::Range<usize>,
+    pub record: UtfKenAllRecord,
+}
+
+impl Suggestion {
+    fn new(field: SuggestField, query: &str, record: UtfKenAllRecord) -> Self {
+        let parts = match field {
+            SuggestField::PostalCode => [record.postal_code.as_str(), "", ""],
+            SuggestField::Address => [
+                record.prefecture.as_str(),
+                record.city.as_str(),
+                record.town.as_str(),
+            ],
+            SuggestField::Kana => [
+                record.prefecture_kana.as_str(),
+                record.city_kana.as_str(),
+                record.town_kana.as_str(),
+            ],
+        };
+        let text = parts.concat();
+        // リポジトリと同じく都道府県名・市区町村名・町域名の順に前方一致する位置を探す
+        let start = (0..parts.len())
+            .find(|i| parts[*i..].concat().starts_with(query))
+            .map(|i| parts[..i].concat().chars().count())
+            .unwrap_or(0);
+        let end = (start + query.chars().count()).min(text.chars().count());
+        Self {
+            field,
+            text,
+            highlight: start..end,
+            record,
+        }
+    }
+}
+
+/// 入力途中のクエリから入力補完の候補を返す
+///
+/// クエリの種類を判別し、郵便番号（数字）、住所の読み（仮名のみ）、住所（それ以外）のいずれかの
+/// 前方一致で検索する。打鍵ごとに呼ばれることを想定しているので、候補は少数に限る。
+#[tracing::instrument(skip(repo))]
+pub async fn suggest<R, Q>(repo: &R, req: SuggestRequest<Q>) -> Result<SuggestResponse, Error>
+where
+    R: UtfKenAllRepository,
+    Q: AsRef<str> + std::fmt::Debug,
+{
+    let limit = match req.limit {
+        Some(limit) if limit == 0 || limit > MAX_SUGGEST_LIMIT => {
+            return Err(Error::InvalidArgument {
+                field: "limit",
+                message: format!("limit must be between 1 and {MAX_SUGGEST_LIMIT}"),
+            });
+        }
+        Some(limit) => limit,
+        None => DEFAULT_SUGGEST_LIMIT,
+    };
+    let query = req.query.as_ref();
+    let (field, query) = match normalize_postal_code_query(query) {
+        Ok(postal_code) if !postal_code.is_empty() => (SuggestField::PostalCode, postal_code),
+        _ => {
+            let kana = normalize_kana_query(query);
+            if is_kana_query(&kana) {
+                (SuggestField::Kana, kana)
+            } else {
+                (SuggestField::Address, normalize_address_query(query))
+            }
+        }
+    };
+    // 空のクエリで全件から候補を返さないようにする
+    if query.is_empty() {
+        return Ok(SuggestResponse {
+            suggestions: vec![],
+            dataset_version: None,
+        });
+    }
+    let response = repo
+        .suggest(UtfKenAllRepositorySuggestRequest {
+            field,
+            query: &query,
+            limit,
+        })
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    let suggestions = response
+        .records
+        .into_iter()
+        .map(|r| Suggestion::new(field, &query, r))
+        .collect();
+    Ok(SuggestResponse {
+        suggestions,
+        dataset_version: response.dataset_version,
+    })
+}
+
+#[derive(Debug)]
+pub struct ParseAddressResponse {
+    pub address: ParsedAddress,
+    /// 辞書として最後に読んだバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 住所を都道府県・市区町村・町域・丁目・番地・建物名に分解する
+///
+/// 郵便番号データベースの地名を [`parse`] の辞書として使う。都道府県、市区町村、町域の順に
+/// 照合し、一致した市区町村の町域だけを読み込む。
+#[tracing::instrument(skip(repo))]
+pub async fn parse_address<R>(repo: &R, address: &str) -> Result<ParseAddressResponse, Error>
+where
+    R: UtfKenAllRepository,
+{
+    let mut dictionary = AreaDictionary::default();
+    if normalize_address_query(address).is_empty() {
+        return Ok(ParseAddressResponse {
+            address: ParsedAddress::default(),
+            dataset_version: None,
+        });
+    }
+    dictionary.prefectures = repo
+        .list_prefectures()
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?
+        .prefectures;
+    // 都道府県名は省略されることがあるので、一致しなければすべての都道府県の市区町村を対象にする
+    let parsed = parse(address, &dictionary);
+    let prefecture_code = parsed
+        .prefecture
+        .and_then(|name| dictionary.prefectures.iter().find(|p| p.name == name))
+        .map(|p| p.code.clone());
+    let response = repo
+        .list_cities(prefecture_code.as_deref())
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    dictionary.cities = response.cities;
+    let parsed = parse(address, &dictionary);
+    let Some(local_government_code) = parsed.local_government_code else {
+        return Ok(ParseAddressResponse {
+            address: parsed,
+            dataset_version: response.dataset_version,
+        });
+    };
+    let response = repo
+        .list_towns(&local_government_code)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    dictionary.towns = response.towns;
+    Ok(ParseAddressResponse {
+        address: parse(address, &dictionary),
+        dataset_version: response.dataset_version,
+    })
+}
+
+/// 住所の検証で照合する項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AddressField {
+    PostalCode,
+    Prefecture,
+    City,
+    Town,
+}
+
+/// 住所の検証結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationResult {
+    /// すべての項目が一致する
+    Valid,
+    /// 一部の項目が一致しない
+    Partial,
+    /// 指定したすべての項目が一致しない
+    Invalid,
+}
+
+#[derive(Debug)]
+pub struct ValidatePostalAddressRequest<S>
+where
+    S: AsRef<str>,
+{
+    pub postal_code: S,
+    /// 都道府県名（空なら照合しない）
+    pub prefecture: S,
+    /// 市区町村名（空なら照合しない）
+    pub city: S,
+    /// 町域名（空なら照合しない）
+    pub town: S,
+}
+
+#[derive(Debug)]
+pub struct ValidatePostalAddressResponse {
+    pub result: ValidationResult,
+    /// 一致しなかった項目
+    pub mismatched_fields: Vec<AddressField>,
+    /// 修正の候補
+    ///
+    /// 郵便番号が誤っていれば住所に対応する郵便番号の住所、住所が誤っていれば郵便番号に対応する
+    /// 住所のうち一致する項目が最も多いもの。
+    pub suggestions: Vec<PostalCodeRecord>,
+    /// 照合に最後に使ったバージョンの ID
+    pub dataset_version: SearchedVersion,
+}
+
+/// 住所の検証で返す修正の候補の最大数
+const MAX_VALIDATION_SUGGESTIONS: usize = 5;
+
+/// 郵便番号と住所（都道府県・市区町村・町域）の組み合わせが正しいか検証する
+///
+/// 郵便番号に対応する住所と、各項目を [`fold_address`] で表記揺れを吸収してから照合する。仮名で
+/// 入力された項目は住所の読みと照合し、町域は後ろに番地などが続いていてもよい。丁目や番地が
+/// 続く場合は、それが町域の範囲（[`jp_postal_code_core::model::TownSpec`]）に含まれるかも確かめる。
+/// 郵便番号に対応する住所が複数あれば、一致しない項目が最も少ないものを採用する。
+///
+/// 住所が一致しなければ、住所から郵便番号を検索し、住所として正しければ郵便番号だけが誤っている
+/// とみなす。住所も見つからなければ [`find_did_you_mean`] で候補を探す。
+#[tracing::instrument(skip(repo))]
+pub async fn validate_postal_address<R, S>(
+    repo: &R,
+    req: ValidatePostalAddressRequest<S>,
+) -> Result<ValidatePostalAddressResponse, Error>
+where
+    R: UtfKenAllRepository,
+    S: AsRef<str> + std::fmt::Debug,
+{
+    let postal_code = normalize_postal_code_query(req.postal_code.as_ref()).map_err(|e| {
+        Error::InvalidArgument {
+            field: "postal_code",
+            message: e.to_string(),
+        }
+    })?;
+    if postal_code.len() != 7 {
+        return Err(Error::InvalidArgument {
+            field: "postal_code",
+            message: "postal code must be 7 digits".to_string(),
+        });
+    }
+    let inputs = [
+        (AddressField::Prefecture, req.prefecture.as_ref()),
+        (AddressField::City, req.city.as_ref()),
+        (AddressField::Town, req.town.as_ref()),
+    ]
+    .into_iter()
+    .filter(|(_, input)| !normalize_address_query(input).is_empty())
+    .collect::<Vec<_>>();
+
+    let response = repo
+        .get_by_postal_code(&postal_code)
+        .await
+        .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+    let mut records = response.records;
+    let dataset_version = response.dataset_version;
+    // 町域名がより長く一致するレコード（「大江」に対する「大江２丁目」）があれば、より詳しい範囲を
+    // 表すそちらだけで判定する
+    let town = fold_town_input(req.town.as_ref());
+    let town_len = |r: &PostalCodeRecord| match r {
+        PostalCodeRecord::Address(r) if !r.town.is_empty() => {
+            let name = fold_address(&r.town);
+            let len = name.chars().count();
+            (prefix_edit_distance(&name, &town) == (0, len)).then_some(len)
+        }
+        _ => None,
+    };
+    let longest = records.iter().filter_map(town_len).max();
+    records.retain(|r| town_len(r).is_none_or(|len| Some(len) == longest));
+    let mut candidates = records
+        .into_iter()
+        .map(|r| (mismatched_fields(&r, &inputs), r))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(mismatched, _)| mismatched.len());
+    if candidates
+        .first()
+        .is_some_and(|(mismatched, _)| mismatched.is_empty())
+    {
+        return Ok(ValidatePostalAddressResponse {
+            result: ValidationResult::Valid,
+            mismatched_fields: vec![],
+            suggestions: vec![],
+            dataset_version,
+        });
+    }
+
+    // 住所が正しければ郵便番号だけが誤っている
+    let address = normalize_address_query(
+        &inputs
+            .iter()
+            .map(|(_, input)| *input)
+            .collect::<Vec<_>>()
+            .concat(),
+    );
+    if !address.is_empty() {
+        let response = repo
+            .search_by_address(UtfKenAllRepositorySearchByAddressRequest {
+                address: &address,
+                page_size: Some(DEFAULT_SEARCH_PAGE_SIZE),
+            })
+            .await
+            .map_err(Into::<UtfKenAllRepositoryError>::into)?;
+        let suggestions = response
+            .records
+            .into_iter()
+            .map(PostalCodeRecord::Address)
+            .filter(|r| mismatched_fields(r, &inputs).is_empty())
+            .take(MAX_VALIDATION_SUGGESTIONS)
+            .collect::<Vec<_>>();
+        if !suggestions.is_empty() {
+            return Ok(ValidatePostalAddressResponse {
+                result: ValidationResult::Partial,
+                mismatched_fields: vec![AddressField::PostalCode],
+                suggestions,
+                dataset_version: response.dataset_version,
+            });
+        }
+    }
+
+    match candidates.first() {
+        Some((mismatched, _)) => {
+            let result = if mismatched.len() < inputs.len() {
+                ValidationResult::Partial
+            } else {
+                ValidationResult::Invalid
+            };
+            let mismatched_fields = mismatched.clone();
+            let suggestions = candidates
+                .into_iter()
+                .filter(|(m, _)| m.len() == mismatched_fields.len())
+                .map(|(_, r)| r)
+                .take(MAX_VALIDATION_SUGGESTIONS)
+                .collect();
+            Ok(ValidatePostalAddressResponse {
+                result,
+                mismatched_fields,
+                suggestions,
+                dataset_version,
+            })
+        }
+        // 郵便番号が存在しない
+        None => {
+            let (suggestions, dataset_version) = if address.is_empty() {
+                (vec![], dataset_version)
+            } else {
+                let (did_you_mean, dataset_version) =
+                    find_did_you_mean(repo, &address, MAX_VALIDATION_SUGGESTIONS).await?;
+                (
+                    did_you_mean
+                        .into_iter()
+                        .map(PostalCodeRecord::Address)
+                        .collect(),
+                    dataset_version,
+                )
+            };
+            Ok(ValidatePostalAddressResponse {
+                result: ValidationResult::Invalid,
+                mismatched_fields: vec![AddressField::PostalCode],
+                suggestions,
+                dataset_version,
+            })
+        }
+    }
+}
+
+/// レコードと一致しない項目を求める
+fn mismatched_fields(
+    record: &PostalCodeRecord,
+    inputs: &[(AddressField, &str)],
+) -> Vec<AddressField> {
+    inputs
+        .iter()
+        .filter(|(field, input)| {
+            let (name, kana) = match (record, field) {
+                (PostalCodeRecord::Address(r), AddressField::Prefecture) => {
+                    (&r.prefecture, Some(&r.prefecture_kana))
+                }
+                (PostalCodeRecord::Address(r), AddressField::City) => (&r.city, Some(&r.city_kana)),
+                (PostalCodeRecord::Address(r), _) => (&r.town, Some(&r.town_kana)),
+                // 事業所の個別郵便番号には住所の読みがない
+                (PostalCodeRecord::Business(r), AddressField::Prefecture) => (&r.prefecture, None),
+                (PostalCodeRecord::Business(r), AddressField::City) => (&r.city, None),
+                (PostalCodeRecord::Business(r), _) => (&r.town, None),
+            };
+            if !field_matches(*field, input, name, kana.map(String::as_str)) {
+                return true;
+            }
+            // 町域名が一致しても、丁目・番地が町域の範囲に含まれなければ一致しない
+            match (record, field) {
+                (PostalCodeRecord::Address(r), AddressField::Town)
+                    if !is_kana_query(&normalize_kana_query(input)) =>
+                {
+                    !covers(&r.town_spec, input)
+                }
+                _ => false,
+            }
+        })
+        .map(|(field, _)| *field)
+        .collect()
+}
+
+/// 入力された項目が住所の名前（仮名で入力された場合は読み）と一致するか
+///
+/// 町域は後ろに丁目や番地が続いていてもよく、町域名が空（「以下に掲載がない場合」）ならどの
+/// 町域とも一致する。
+fn field_matches(field: AddressField, input: &str, name: &str, kana: Option<&str>) -> bool {
+    let (input, name) = match kana {
+        Some(kana) if is_kana_query(&normalize_kana_query(input)) => {
+            (normalize_kana_query(input), normalize_kana_query(kana))
+        }
+        _ if field == AddressField::Town => (fold_town_input(input), fold_address(name)),
+        _ => (fold_address(input), fold_address(name)),
+    };
+    match field {
+        AddressField::Town => {
+            name.is_empty() || prefix_edit_distance(&name, &input) == (0, name.chars().count())
+        }
+        _ => input == name,
+    }
+}
+
+/// 入力された町域を町域名と比較するために [`fold_address`] で変換する
+///
+/// [`fold_address`] は「丁目」を除去するので、「大江２丁目６５１番地」の丁目と番地の数字が
+/// つながらないように区切りを残す。
+fn fold_town_input(input: &str) -> String {
+    fold_address(&input.replace("丁目", "丁目－"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::ephemeral::UtfKenAllRepositoryEphemeral;
+    use jp_postal_code_core::model::{UpdateCode, UpdateReason};
+
+    /// テスト用の一部の郵便番号データは検証ルールを満たさないので、検証に失敗しても取り込む
+    fn forced() -> ImportOptions {
+        ImportOptions {
+            force: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_utf_ken_all_database() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_ken_all.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_ken_all.zip")
+            .create_async()
+            .await;
+        let mock_rome = server
+            .mock("GET", "/zipcode/dl/roman/KEN_ALL_ROME.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_ken_all_rome.zip")
+            .create_async()
+            .await;
+
+        let mock_jigyosyo = server
+            .mock("GET", "/zipcode/dl/jigyosyo/zip/jigyosyo.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_jigyosyo.zip")
+            .create_async()
+            .await;
+
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database(
+            &mut repo,
+            Some(server.url() + "/zipcode/dl/utf/zip/utf_ken_all.zip"),
+            Some(server.url() + "/zipcode/dl/roman/KEN_ALL_ROME.zip"),
+            Some(server.url() + "/zipcode/dl/jigyosyo/zip/jigyosyo.zip"),
+            &forced(),
+        )
+        .await
+        .unwrap();
+        mock.assert_async().await;
+        mock_rome.assert_async().await;
+        mock_jigyosyo.assert_async().await;
+        // 住所と事業所の個別郵便番号を一つのバージョンとして取り込む
+        assert_eq!(repo.jigyosyo_records().len(), 4);
+        assert_eq!(repo.list_versions().await.unwrap().len(), 1);
+        insta::assert_debug_snapshot!(repo.records());
+    }
+
+    #[tokio::test]
+    async fn test_update_utf_ken_all_database_without_ken_all_rome() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_ken_all.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_ken_all.zip")
+            .create_async()
+            .await;
+        let mock_rome = server
+            .mock("GET", "/zipcode/dl/roman/KEN_ALL_ROME.zip")
+            .with_status(404)
+            .create_async()
+            .await;
+        let mock_jigyosyo = server
+            .mock("GET", "/zipcode/dl/jigyosyo/zip/jigyosyo.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_jigyosyo.zip")
+            .create_async()
+            .await;
+
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        let report = update_postal_code_database(
+            &mut repo,
+            Some(server.url() + "/zipcode/dl/utf/zip/utf_ken_all.zip"),
+            Some(server.url() + "/zipcode/dl/roman/KEN_ALL_ROME.zip"),
+            Some(server.url() + "/zipcode/dl/jigyosyo/zip/jigyosyo.zip"),
+            &forced(),
+        )
+        .await
+        .unwrap();
+        mock.assert_async().await;
+        mock_rome.assert_async().await;
+        mock_jigyosyo.assert_async().await;
+        // KEN_ALL_ROME.zip を取得できなくても、ローマ字表記を仮名から変換して取り込む
+        assert!(report.applied);
+        assert!(!repo.records().is_empty());
+        assert!(repo
+            .records()
+            .iter()
+            .all(|r| r.city_roman == to_hepburn(&r.city_kana)));
+    }
+
+    #[tokio::test]
+    async fn test_apply_postal_code_database_diff() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_ken_all = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_ken_all.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_ken_all.zip")
+            .create_async()
+            .await;
+        let mock_add = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_add_2501.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_add.zip")
+            .create_async()
+            .await;
+        let mock_del = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_del_2501.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_del.zip")
+            .create_async()
+            .await;
+        let mock_rome = server
+            .mock("GET", "/zipcode/dl/roman/KEN_ALL_ROME.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_ken_all_rome.zip")
+            .create_async()
+            .await;
+
+        let mock_jigyosyo = server
+            .mock("GET", "/zipcode/dl/jigyosyo/zip/jigyosyo.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_jigyosyo.zip")
+            .create_async()
+            .await;
+
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database(
+            &mut repo,
+            Some(server.url() + "/zipcode/dl/utf/zip/utf_ken_all.zip"),
+            Some(server.url() + "/zipcode/dl/roman/KEN_ALL_ROME.zip"),
+            Some(server.url() + "/zipcode/dl/jigyosyo/zip/jigyosyo.zip"),
+            &forced(),
+        )
+        .await
+        .unwrap();
+        apply_postal_code_database_diff(
+            &mut repo,
+            server.url() + "/zipcode/dl/utf/zip/utf_add_2501.zip",
+            server.url() + "/zipcode/dl/utf/zip/utf_del_2501.zip",
+        )
+        .await
+        .unwrap();
+        mock_ken_all.assert_async().await;
+        mock_rome.assert_async().await;
+        mock_jigyosyo.assert_async().await;
+        mock_add.assert_async().await;
+        mock_del.assert_async().await;
+        // 旭ケ丘が削除され、大通東の変更と北一条西の新設が反映される
+        insta::assert_debug_snapshot!(repo.records());
+    }
+
+    #[tokio::test]
+    async fn test_update_postal_code_database_from_file() {
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            Some("./testdata/partial_ken_all_rome.zip"),
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        let from_zip = repo.records();
+
+        // 展開済みの CSV からも同じレコードが得られる
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "../jp-postal-code-util/testdata/partial_utf_ken_all.csv",
+            Some("../jp-postal-code-util/testdata/partial_ken_all_rome.csv"),
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        let from_csv = repo.records();
+        assert_eq!(from_zip, from_csv);
+        assert!(!from_zip.is_empty());
+
+        // ローマ字版を省略すると仮名から変換する
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        let from_kana = repo.records();
+        assert_eq!(from_kana.len(), from_zip.len());
+        assert_eq!(from_zip[1].city_roman, "SAPPORO SHI CHUO KU");
+        assert_eq!(from_kana[1].city_roman, "SAPPOROSHICHUOKU");
+        assert_eq!(from_zip[1].town_roman, "ASAHIGAOKA");
+        assert_eq!(from_kana[1].town_roman, "ASAHIGAOKA");
+
+        // 事業所の個別郵便番号を指定すると一つのバージョンとして合わせて置き換え、省略すると使用中の
+        // バージョンのものを引き継ぐ
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            Some("./testdata/partial_jigyosyo.zip"),
+            &forced(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.list_versions().await.unwrap().len(), 1);
+        assert_eq!(repo.jigyosyo_records().len(), 4);
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.list_versions().await.unwrap().len(), 2);
+        assert_eq!(repo.jigyosyo_records().len(), 4);
+
+        // 存在しないファイルはエラー
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        assert!(update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/not_found.zip",
+            None,
+            None,
+            &forced()
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_postal_code_database_validation() {
+        // 一部の郵便番号データは件数が少なく、北海道しか含まないので既定の検証ルールを満たさない
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        let err = update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        let report = &err.downcast_ref::<ImportValidationError>().unwrap().0;
+        assert_eq!(report.record_count, 33);
+        assert_eq!(report.prefecture_count, 1);
+        assert!(!report.applied);
+        assert_eq!(
+            report.violations[0],
+            ImportViolation::TooFewRecords {
+                count: 33,
+                min: DEFAULT_IMPORT_MIN_RECORDS
+            }
+        );
+        assert!(matches!(
+            &report.violations[1],
+            ImportViolation::Prefectures { missing, unexpected }
+                if missing.len() == 46 && missing[0] == "02" && unexpected.is_empty()
+        ));
+        assert_eq!(repo.count().await.unwrap(), 0);
+
+        // dry run は検証結果を返すだけで置き換えない
+        let report = update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &ImportOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.violations.len(), 2);
+        assert!(!report.applied);
+        assert_eq!(repo.count().await.unwrap(), 0);
+
+        // force なら検証に失敗しても置き換える
+        let report = update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.violations.len(), 2);
+        assert!(report.applied);
+        assert_eq!(repo.count().await.unwrap(), 33);
+    }
+
+    #[test]
+    fn test_validate_import() {
+        let base = parse_utf_ken_all(
+            std::fs::File::open("../jp-postal-code-util/testdata/partial_utf_ken_all.csv").unwrap(),
+        )
+        .unwrap()
+        .remove(0);
+        let record = |local_government_code: String, postal_code: &str| UtfKenAllRecord {
+            local_government_code,
+            postal_code: postal_code.to_string(),
+            ..base.clone()
+        };
+        let options = ImportOptions {
+            min_records: 1,
+            ..Default::default()
+        };
+        let all = (1..=47)
+            .map(|n| record(format!("{n:02}101"), "0600000"))
+            .collect::<Vec<_>>();
+        let report = validate_import(&all, 0, &options);
+        assert_eq!(report.prefecture_count, 47);
+        assert_eq!(report.change_percent, None);
+        assert_eq!(report.violations, vec![]);
+
+        // 郵便番号・全国地方公共団体コードの形式と、レコード数の増減
+        let mut invalid = all.clone();
+        invalid[0].postal_code = "060-0000".to_string();
+        invalid[1].local_government_code = "2101".to_string();
+        invalid.push(record("48101".to_string(), "0600000"));
+        let report = validate_import(&invalid, 40, &options);
+        assert_eq!(report.change_percent, Some(20.0));
+        assert_eq!(
+            report.violations,
+            vec![
+                ImportViolation::Prefectures {
+                    missing: vec!["02".to_string()],
+                    unexpected: vec!["48".to_string()],
+                },
+                ImportViolation::InvalidPostalCode {
+                    count: 1,
+                    example: "060-0000".to_string(),
+                },
+                ImportViolation::InvalidLocalGovernmentCode {
+                    count: 1,
+                    example: "2101".to_string(),
+                },
+                ImportViolation::TooLargeChange {
+                    current: 40,
+                    count: 48,
+                    percent: 20.0,
+                    max: DEFAULT_IMPORT_MAX_CHANGE_PERCENT,
+                },
+            ]
+        );
+        assert!(validate_import(&invalid, 45, &options)
+            .violations
+            .iter()
+            .all(|v| !matches!(v, ImportViolation::TooLargeChange { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_update_jigyosyo_database_from_file() {
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_jigyosyo_database_from_file(&mut repo, "./testdata/partial_jigyosyo.zip")
+            .await
+            .unwrap();
+        assert_eq!(repo.jigyosyo_records().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_update_jigyosyo_database() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/zipcode/dl/jigyosyo/zip/jigyosyo.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_jigyosyo.zip")
+            .create_async()
+            .await;
+
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_jigyosyo_database(
             &mut repo,
-            Some(server.url() + "/zipcode/dl/utf/zip/utf_ken_all.zip"),
+            Some(server.url() + "/zipcode/dl/jigyosyo/zip/jigyosyo.zip"),
         )
         .await
         .unwrap();
         mock.assert_async().await;
-        insta::assert_debug_snapshot!(repo.into_inner().lock().unwrap());
+        insta::assert_debug_snapshot!(repo.jigyosyo_records());
+    }
+
+    #[tokio::test]
+    async fn test_search_postal_code_invalid_argument() {
+        let repo = UtfKenAllRepositoryEphemeral::default();
+        let search =
+            |postal_code: &'static str, page_size: Option<usize>, page_token: &'static str| {
+                search_postal_code(
+                    &repo,
+                    SearchPostalCodeRequest {
+                        postal_code,
+                        page_size,
+                        page_token: Some(page_token),
+                    },
+                )
+            };
+        let field = |result: Result<SearchPostalCodeResponse, Error>| match result {
+            Err(Error::InvalidArgument { field, .. }) => field,
+            result => panic!("unexpected result: {result:?}"),
+        };
+
+        // 数字以外を含む郵便番号
+        assert_eq!(field(search("06a", None, "").await), "postal_code");
+        assert_eq!(field(search("06%", None, "").await), "postal_code");
+        assert_eq!(field(search("06000000", None, "").await), "postal_code");
+        // 範囲外の最大件数
+        assert_eq!(field(search("060", Some(0), "").await), "page_size");
+        assert_eq!(
+            field(search("060", Some(MAX_SEARCH_PAGE_SIZE + 1), "").await),
+            "page_size"
+        );
+        // 解釈できないページトークン
+        assert_eq!(field(search("060", None, "invalid").await), "page_token");
+
+        // 空のページトークンは指定なしと同じ
+        assert!(search("060", Some(MAX_SEARCH_PAGE_SIZE), "").await.is_ok());
+
+        // 住所検索の最大件数も検証する
+        let result = search_postal_code_by_address(
+            &repo,
+            SearchPostalCodeByAddressRequest {
+                address: "札幌市",
+                page_size: Some(0),
+                did_you_mean: false,
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument {
+                field: "page_size",
+                ..
+            })
+        ));
     }
 
     #[tokio::test]
@@ -141,8 +2092,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 0,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -157,8 +2112,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -173,8 +2132,12 @@ mod tests {
                 has_multi_postal_code: 0,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -189,8 +2152,12 @@ mod tests {
                 has_multi_postal_code: 1,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
             UtfKenAllRecord {
                 local_government_code: "01101".to_string(),
@@ -205,8 +2172,12 @@ mod tests {
                 has_multi_postal_code: 1,
                 has_chome: 0,
                 has_multi_town: 1,
-                update_code: 0,
-                update_reason: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
             },
         ]);
         let records = search_postal_code(
@@ -220,5 +2191,1066 @@ mod tests {
         .await
         .unwrap();
         insta::assert_debug_snapshot!(records);
+
+        // 全角数字や郵便記号を含むクエリも正規化して検索できる
+        let normalized = search_postal_code(
+            &repo,
+            SearchPostalCodeRequest {
+                postal_code: "〒０６４",
+                page_size: None,
+                page_token: None::<&str>,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(format!("{normalized:?}"), format!("{records:?}"));
+    }
+
+    #[tokio::test]
+    async fn test_get_postal_code() {
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![UtfKenAllRecord {
+            local_government_code: "13103".to_string(),
+            old_postal_code: "108  ".to_string(),
+            postal_code: "1080023".to_string(),
+            prefecture_kana: "トウキョウト".to_string(),
+            city_kana: "ミナトク".to_string(),
+            town_kana: "シバウラ".to_string(),
+            prefecture: "東京都".to_string(),
+            city: "港区".to_string(),
+            town: "芝浦".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 1,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        }]);
+        let get = |postal_code: &'static str| {
+            get_postal_code(&repo, GetPostalCodeRequest { postal_code })
+        };
+
+        let response = get("〒１０８－００２３").await.unwrap();
+        assert_eq!(response.records.len(), 1);
+
+        // 存在しない郵便番号
+        assert!(matches!(get("1080024").await, Err(Error::NotFound { .. })));
+        // 7桁でない郵便番号は前方一致せず不正な値として扱う
+        assert!(matches!(
+            get("108").await,
+            Err(Error::InvalidArgument {
+                field: "postal_code",
+                ..
+            })
+        ));
+        assert!(matches!(
+            get("108002a").await,
+            Err(Error::InvalidArgument {
+                field: "postal_code",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_postal_code_history() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_add = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_add_2501.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_add.zip")
+            .create_async()
+            .await;
+        let mock_del = server
+            .mock("GET", "/zipcode/dl/utf/zip/utf_del_2501.zip")
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body_from_file("./testdata/partial_utf_del.zip")
+            .create_async()
+            .await;
+
+        // 差分と同じくローマ字表記を仮名から変換しておき、変わった項目だけが記録されるようにする
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        // 最初の取り込みでは記録しない
+        assert_eq!(
+            list_postal_code_history(&repo, "0600041").await.unwrap(),
+            vec![]
+        );
+        apply_postal_code_database_diff(
+            &mut repo,
+            server.url() + "/zipcode/dl/utf/zip/utf_add_2501.zip",
+            server.url() + "/zipcode/dl/utf/zip/utf_del_2501.zip",
+        )
+        .await
+        .unwrap();
+        mock_add.assert_async().await;
+        mock_del.assert_async().await;
+
+        // 大通東は「一つの郵便番号で二以上の町域を表す場合の表示」が変わった
+        let history = list_postal_code_history(&repo, "〒０６０－００４１")
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].change, crate::repo::PostalCodeChange::Changed);
+        assert_eq!(
+            history[0].fields,
+            vec![crate::repo::FieldChange {
+                field: "has_multi_town".to_string(),
+                old: Some("1".to_string()),
+                new: Some("0".to_string()),
+            }]
+        );
+        assert_eq!(history[0].update_code, UpdateCode::Changed);
+        assert_eq!(history[0].update_reason, UpdateReason::Correction);
+        assert_eq!(history[0].dataset_id, 2);
+
+        // 旭ケ丘は削除され、北一条西は追加された
+        let history = list_postal_code_history(&repo, "0640941").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].change, crate::repo::PostalCodeChange::Removed);
+        assert!(history[0].fields.iter().all(|f| f.new.is_none()));
+        // 削除された場合の更新の表示は削除前のレコードのもの
+        assert_eq!(history[0].update_code, UpdateCode::Unchanged);
+        // 北一条西は丁目を省いた町域と、丁目ごとに分割した 19 件のレコードが追加された
+        let history = list_postal_code_history(&repo, "0600001").await.unwrap();
+        assert_eq!(history.len(), 20);
+        assert!(history
+            .iter()
+            .all(|h| h.change == crate::repo::PostalCodeChange::Added
+                && h.fields.iter().all(|f| f.old.is_none())));
+
+        assert!(matches!(
+            list_postal_code_history(&repo, "060").await,
+            Err(Error::InvalidArgument {
+                field: "postal_code",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_postal_codes() {
+        let record = |postal_code: &str, town: &str| UtfKenAllRecord {
+            local_government_code: "13103".to_string(),
+            old_postal_code: "108  ".to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "トウキョウト".to_string(),
+            city_kana: "ミナトク".to_string(),
+            town_kana: "".to_string(),
+            prefecture: "東京都".to_string(),
+            city: "港区".to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 1,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![
+            record("1080023", "芝浦"),
+            record("1080022", "海岸"),
+        ]);
+
+        let response = batch_get_postal_codes(
+            &repo,
+            BatchGetPostalCodesRequest {
+                postal_codes: vec!["108-0023", "1080024", "1080022", "108", "abc", "108-0023"],
+            },
+        )
+        .await
+        .unwrap();
+        // 入力した文字列をキーとして入力順に返す
+        let found = response
+            .results
+            .iter()
+            .map(|r| (r.postal_code.as_str(), r.records.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![("108-0023", 1), ("1080022", 1)]);
+        assert_eq!(response.not_found, vec!["1080024", "108", "abc"]);
+
+        // 件数の上限を超えると不正な値として扱う
+        let result = batch_get_postal_codes(
+            &repo,
+            BatchGetPostalCodesRequest {
+                postal_codes: vec!["1080023"; MAX_BATCH_GET_SIZE + 1],
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument {
+                field: "postal_codes",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_areas() {
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![UtfKenAllRecord {
+            local_government_code: "13103".to_string(),
+            old_postal_code: "108  ".to_string(),
+            postal_code: "1080023".to_string(),
+            prefecture_kana: "トウキョウト".to_string(),
+            city_kana: "ミナトク".to_string(),
+            town_kana: "シバウラ".to_string(),
+            prefecture: "東京都".to_string(),
+            city: "港区".to_string(),
+            town: "芝浦".to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 1,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        }]);
+
+        let prefectures = list_prefectures(&repo).await.unwrap();
+        assert_eq!(prefectures.prefectures.len(), 1);
+        assert_eq!(prefectures.dataset_version, Some(1));
+        assert_eq!(list_cities(&repo, "13").await.unwrap().cities.len(), 1);
+        assert_eq!(list_towns(&repo, "13103").await.unwrap().towns.len(), 1);
+
+        // 存在しない都道府県・市区町村
+        assert!(matches!(
+            list_cities(&repo, "47").await,
+            Err(Error::NotFound { .. })
+        ));
+        assert!(matches!(
+            list_towns(&repo, "13104").await,
+            Err(Error::NotFound { .. })
+        ));
+        // 桁数が合わないコード
+        assert!(matches!(
+            list_cities(&repo, "131").await,
+            Err(Error::InvalidArgument {
+                field: "prefecture_code",
+                ..
+            })
+        ));
+        assert!(matches!(
+            list_towns(&repo, "1310a").await,
+            Err(Error::InvalidArgument {
+                field: "local_government_code",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_postal_code_by_address() {
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050014".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝１丁目".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦１丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ２チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦２丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ]);
+        // 空白や半角数字は正規化してから検索される
+        let records = search_postal_code_by_address(
+            &repo,
+            SearchPostalCodeByAddressRequest {
+                address: "港区 芝浦2丁目",
+                page_size: None,
+                did_you_mean: false,
+            },
+        )
+        .await
+        .unwrap();
+        insta::assert_debug_snapshot!(records);
+
+        // 空のクエリでは何も返さない
+        let records = search_postal_code_by_address(
+            &repo,
+            SearchPostalCodeByAddressRequest {
+                address: "　",
+                page_size: None,
+                did_you_mean: true,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(records.records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_postal_code_by_kana() {
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050014".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝１丁目".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦１丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ２チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦２丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ])
+        .with_page_token_secret(b"secret");
+        // ひらがなや半角カタカナは全角カタカナに正規化してから検索される
+        let first = search_postal_code_by_kana(
+            &repo,
+            SearchPostalCodeByKanaRequest {
+                kana: "みなとく ｼﾊﾞｳﾗ",
+                page_size: Some(2),
+                page_token: None::<&str>,
+            },
+        )
+        .await
+        .unwrap();
+        let second = search_postal_code_by_kana(
+            &repo,
+            SearchPostalCodeByKanaRequest {
+                kana: "みなとく ｼﾊﾞｳﾗ",
+                page_size: Some(2),
+                page_token: first.next_page_token.as_deref(),
+            },
+        )
+        .await
+        .unwrap();
+        insta::assert_debug_snapshot!((first, second));
+
+        // 空のクエリでは何も返さない
+        let records = search_postal_code_by_kana(
+            &repo,
+            SearchPostalCodeByKanaRequest {
+                kana: "　",
+                page_size: None,
+                page_token: None::<&str>,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(records.records.is_empty());
+
+        // 不正なページトークン
+        let result = search_postal_code_by_kana(
+            &repo,
+            SearchPostalCodeByKanaRequest {
+                kana: "しばうら",
+                page_size: None,
+                page_token: Some("invalid"),
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument {
+                field: "page_token",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_suggest() {
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050014".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝１丁目".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "105  ".to_string(),
+                postal_code: "1050023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ１チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦１丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "13103".to_string(),
+                old_postal_code: "108  ".to_string(),
+                postal_code: "1080023".to_string(),
+                prefecture_kana: "トウキョウト".to_string(),
+                city_kana: "ミナトク".to_string(),
+                town_kana: "シバウラ２チョウメ".to_string(),
+                prefecture: "東京都".to_string(),
+                city: "港区".to_string(),
+                town: "芝浦２丁目".to_string(),
+                has_multi_postal_code: 1,
+                has_chome: 1,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+            UtfKenAllRecord {
+                local_government_code: "01101".to_string(),
+                old_postal_code: "060  ".to_string(),
+                postal_code: "0600000".to_string(),
+                prefecture_kana: "ホッカイドウ".to_string(),
+                city_kana: "サッポロシチュウオウク".to_string(),
+                town_kana: "".to_string(),
+                prefecture: "北海道".to_string(),
+                city: "札幌市中央区".to_string(),
+                town: "".to_string(),
+                has_multi_postal_code: 0,
+                has_chome: 0,
+                has_multi_town: 0,
+                update_code: UpdateCode::Unchanged,
+                update_reason: UpdateReason::Unchanged,
+                prefecture_roman: "".to_string(),
+                city_roman: "".to_string(),
+                town_roman: "".to_string(),
+                town_spec: Default::default(),
+            },
+        ]);
+        let suggest = |query: &'static str, limit: Option<usize>| {
+            let repo = repo.clone();
+            async move {
+                suggest(&repo, SuggestRequest { query, limit })
+                    .await
+                    .map(|response| {
+                        response
+                            .suggestions
+                            .into_iter()
+                            .map(|s| (s.field, s.text, s.highlight))
+                            .collect::<Vec<_>>()
+                    })
+            }
+        };
+
+        // 数字は郵便番号として前方一致で検索する
+        assert_eq!(
+            suggest("〒108-0", None).await.unwrap(),
+            vec![
+                (SuggestField::PostalCode, "1080023".to_string(), 0..4),
+                (SuggestField::PostalCode, "1080023".to_string(), 0..4),
+            ]
+        );
+
+        // 漢字を含むものは住所として検索し、一致した位置を返す
+        assert_eq!(
+            suggest("芝浦", Some(1)).await.unwrap(),
+            vec![(SuggestField::Address, "東京都港区芝浦".to_string(), 5..7)]
+        );
+        assert_eq!(
+            suggest("港区 芝", Some(2)).await.unwrap(),
+            vec![
+                (SuggestField::Address, "東京都港区芝浦".to_string(), 3..6),
+                (
+                    SuggestField::Address,
+                    "東京都港区芝１丁目".to_string(),
+                    3..6
+                ),
+            ]
+        );
+
+        // 仮名のみのものは読みとして検索する
+        assert_eq!(
+            suggest("しばう", Some(1)).await.unwrap(),
+            vec![(
+                SuggestField::Kana,
+                "トウキョウトミナトクシバウラ".to_string(),
+                10..13
+            )]
+        );
+
+        // 空のクエリでは何も返さない
+        assert!(suggest("　", None).await.unwrap().is_empty());
+
+        // 件数の上限を超えるとエラー
+        for limit in [0, MAX_SUGGEST_LIMIT + 1] {
+            assert!(matches!(
+                suggest("芝浦", Some(limit)).await,
+                Err(Error::InvalidArgument { field: "limit", .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_postal_code_by_address_did_you_mean() {
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        let search = |address: &'static str, did_you_mean: bool| {
+            let repo = repo.clone();
+            async move {
+                let response = search_postal_code_by_address(
+                    &repo,
+                    SearchPostalCodeByAddressRequest {
+                        address,
+                        page_size: None,
+                        did_you_mean,
+                    },
+                )
+                .await
+                .unwrap();
+                let postal_codes = |records: Vec<UtfKenAllRecord>| {
+                    records
+                        .into_iter()
+                        .map(|r| r.postal_code)
+                        .collect::<Vec<_>>()
+                };
+                (
+                    postal_codes(response.records),
+                    postal_codes(response.did_you_mean),
+                )
+            }
+        };
+
+        // 異体字と番地を含む住所
+        assert_eq!(
+            search("北海道札幌市中央区旭が丘2-3", true).await,
+            (vec![], vec!["0640941".to_string()])
+        );
+        // 都道府県名の省略
+        assert_eq!(
+            search("札幌市中央区旭ヶ丘", true).await,
+            (vec![], vec!["0640941".to_string()])
+        );
+        // 市区町村名の誤り
+        assert_eq!(
+            search("札幌市中大区旭ケ丘", true).await,
+            (vec![], vec!["0640941".to_string()])
+        );
+        // 丁目の数字まで一致するものを優先する
+        let (_, did_you_mean) = search("札幌市中央区大通西二十二丁目", true).await;
+        assert_eq!(did_you_mean[0], "0640820");
+        let (_, did_you_mean) = search("札幌市中央区大通西3丁目4-5", true).await;
+        assert_eq!(did_you_mean[0], "0600042");
+        // 指定しなければ探さない
+        assert_eq!(
+            search("北海道札幌市中央区旭が丘", false).await,
+            (vec![], vec![])
+        );
+        // 一致するものがあれば探さない
+        assert_eq!(
+            search("札幌市中央区旭ケ丘", true).await,
+            (vec!["0640941".to_string()], vec![])
+        );
+        // 近いものがなければ空
+        assert_eq!(search("大阪府大阪市北区梅田", true).await, (vec![], vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_search_postal_code_by_address_did_you_mean_without_prefecture() {
+        let record = |local_government_code: &str,
+                      postal_code: &str,
+                      prefecture: &str,
+                      city: &str,
+                      town: &str| UtfKenAllRecord {
+            local_government_code: local_government_code.to_string(),
+            old_postal_code: postal_code[..3].to_string(),
+            postal_code: postal_code.to_string(),
+            prefecture_kana: "".to_string(),
+            city_kana: "".to_string(),
+            town_kana: "".to_string(),
+            prefecture: prefecture.to_string(),
+            city: city.to_string(),
+            town: town.to_string(),
+            has_multi_postal_code: 0,
+            has_chome: 0,
+            has_multi_town: 0,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let repo = UtfKenAllRepositoryEphemeral::new(vec![
+            record("01101", "0640941", "北海道", "札幌市中央区", "旭ケ丘"),
+            record("13103", "1080023", "東京都", "港区", "芝浦"),
+            record("27127", "5300001", "大阪府", "大阪市北区", "梅田"),
+        ]);
+        let search = |address: &'static str| {
+            let repo = repo.clone();
+            async move {
+                search_postal_code_by_address(
+                    &repo,
+                    SearchPostalCodeByAddressRequest {
+                        address,
+                        page_size: None,
+                        did_you_mean: true,
+                    },
+                )
+                .await
+                .unwrap()
+                .did_you_mean
+                .into_iter()
+                .map(|r| r.postal_code)
+                .collect::<Vec<_>>()
+            }
+        };
+
+        // 都道府県名を省略するとすべての都道府県の市区町村から探す
+        assert_eq!(search("港区芝蒲").await, vec!["1080023".to_string()]);
+        assert_eq!(search("大坂市北区梅田").await, vec!["5300001".to_string()]);
+        // 都道府県名があればその都道府県の市区町村だけから探す
+        assert_eq!(search("東京都大坂市北区梅田").await, Vec::<String>::new());
+    }
+
+    #[tokio::test]
+    async fn test_parse_address() {
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        let response = parse_address(&repo, "北海道札幌市中央区大通西3丁目4-5 札幌ビル 2F")
+            .await
+            .unwrap();
+        assert_eq!(response.dataset_version, Some(1));
+        insta::assert_debug_snapshot!(response.address);
+
+        // 都道府県名の省略と異体字
+        let parsed = parse_address(&repo, "札幌市中央区旭ヶ丘5-6-51")
+            .await
+            .unwrap()
+            .address;
+        assert_eq!(parsed.prefecture.as_deref(), Some("北海道"));
+        assert_eq!(parsed.town.as_deref(), Some("旭ケ丘"));
+        assert_eq!(parsed.postal_code.as_deref(), Some("0640941"));
+        assert_eq!(parsed.banchi.as_deref(), Some("５－６－５１"));
+
+        // 空の住所
+        let response = parse_address(&repo, "　").await.unwrap();
+        assert_eq!(response.address, ParsedAddress::default());
+        assert_eq!(response.dataset_version, None);
+    }
+
+    #[tokio::test]
+    async fn test_validate_postal_address() {
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        update_postal_code_database_from_file(
+            &mut repo,
+            "./testdata/partial_utf_ken_all.zip",
+            None,
+            None,
+            &forced(),
+        )
+        .await
+        .unwrap();
+        let validate = |address: [&'static str; 4]| {
+            let repo = repo.clone();
+            async move {
+                let [postal_code, prefecture, city, town] = address;
+                let response = validate_postal_address(
+                    &repo,
+                    ValidatePostalAddressRequest {
+                        postal_code,
+                        prefecture,
+                        city,
+                        town,
+                    },
+                )
+                .await
+                .unwrap();
+                let suggestions = response
+                    .suggestions
+                    .iter()
+                    .map(|r| r.postal_code().to_string())
+                    .collect::<Vec<_>>();
+                (response.result, response.mismatched_fields, suggestions)
+            }
+        };
+
+        // 表記揺れや番地を含んでいても一致する
+        assert_eq!(
+            validate(["064-0941", "北海道", "札幌市中央区", "旭ヶ丘5丁目6-51"]).await,
+            (ValidationResult::Valid, vec![], vec![])
+        );
+        // 仮名で入力された項目は読みと照合する
+        assert_eq!(
+            validate(["0640941", "ほっかいどう", "ｻｯﾎﾟﾛｼﾁｭｳｵｳｸ", "旭ケ丘"]).await,
+            (ValidationResult::Valid, vec![], vec![])
+        );
+        // 空の項目は照合しない
+        assert_eq!(
+            validate(["0640941", "", "", ""]).await,
+            (ValidationResult::Valid, vec![], vec![])
+        );
+        // 住所は正しいが郵便番号が誤っている
+        assert_eq!(
+            validate(["0640941", "北海道", "札幌市中央区", "大通西３丁目"]).await,
+            (
+                ValidationResult::Partial,
+                vec![AddressField::PostalCode],
+                vec!["0600042".to_string()]
+            )
+        );
+        // 郵便番号は正しいが一部の項目が誤っている
+        assert_eq!(
+            validate(["0640941", "北海道", "札幌市北区", "旭ケ丘"]).await,
+            (
+                ValidationResult::Partial,
+                vec![AddressField::City],
+                vec!["0640941".to_string()]
+            )
+        );
+        // すべての項目が誤っている
+        assert_eq!(
+            validate(["0640941", "大阪府", "大阪市北区", "梅田"]).await,
+            (
+                ValidationResult::Invalid,
+                vec![
+                    AddressField::Prefecture,
+                    AddressField::City,
+                    AddressField::Town
+                ],
+                vec!["0640941".to_string()]
+            )
+        );
+        // 存在しない郵便番号は住所から候補を探す
+        assert_eq!(
+            validate(["9999999", "北海道", "札幌市中大区", "旭が丘"]).await,
+            (
+                ValidationResult::Invalid,
+                vec![AddressField::PostalCode],
+                vec!["0640941".to_string()]
+            )
+        );
+
+        // 郵便番号の形式が不正
+        let result = validate_postal_address(
+            &repo,
+            ValidatePostalAddressRequest {
+                postal_code: "123",
+                prefecture: "",
+                city: "",
+                town: "",
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument {
+                field: "postal_code",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validate_postal_address_town_spec() {
+        let record = UtfKenAllRecord {
+            local_government_code: "01409".to_string(),
+            old_postal_code: "04824".to_string(),
+            postal_code: "0482402".to_string(),
+            prefecture_kana: "ホッカイドウ".to_string(),
+            city_kana: "ヨイチグンニキチョウ".to_string(),
+            town_kana: "オオエ（１チョウメ、２チョウメ＜６５１、６６２、６６８バンチ＞イガイ、３チョウメ５、１３－４、２０、６７８、６８７バンチ）".to_string(),
+            prefecture: "北海道".to_string(),
+            city: "余市郡仁木町".to_string(),
+            town: "大江（１丁目、２丁目「６５１、６６２、６６８番地」以外、３丁目５、１３－４、２０、６７８、６８７番地）".to_string(),
+            has_multi_postal_code: 1,
+            has_chome: 0,
+            has_multi_town: 1,
+            update_code: UpdateCode::Unchanged,
+            update_reason: UpdateReason::Unchanged,
+            prefecture_roman: "".to_string(),
+            city_roman: "".to_string(),
+            town_roman: "".to_string(),
+            town_spec: Default::default(),
+        };
+        let mut repo = UtfKenAllRepositoryEphemeral::default();
+        repo.replace(&normalize_records(vec![record]))
+            .await
+            .unwrap();
+        let validate = |town: &'static str| {
+            let repo = repo.clone();
+            async move {
+                let response = validate_postal_address(
+                    &repo,
+                    ValidatePostalAddressRequest {
+                        postal_code: "0482402",
+                        prefecture: "北海道",
+                        city: "余市郡仁木町",
+                        town,
+                    },
+                )
+                .await
+                .unwrap();
+                (response.result, response.mismatched_fields)
+            }
+        };
+
+        // 町域の範囲に含まれる
+        assert_eq!(
+            validate("大江２丁目６５０番地").await,
+            (ValidationResult::Valid, vec![])
+        );
+        assert_eq!(
+            validate("大江３丁目１３－４").await,
+            (ValidationResult::Valid, vec![])
+        );
+        // 除外された番地や範囲外の丁目は含まれない
+        assert_eq!(
+            validate("大江２丁目６５１番地").await,
+            (ValidationResult::Partial, vec![AddressField::Town])
+        );
+        assert_eq!(
+            validate("大江３丁目６番地").await,
+            (ValidationResult::Partial, vec![AddressField::Town])
+        );
     }
 }
@@ -0,0 +1,2 @@
+create index idx_utf_ken_all_prefecture_city_town on utf_ken_all ((prefecture || city || town) text_pattern_ops);
+create index idx_utf_ken_all_city_town on utf_ken_all ((city || town) text_pattern_ops);
@@ -0,0 +1,36 @@
+create table jigyosyo (
+  jigyosyo_id bigint generated always as identity primary key,
+  local_government_code text not null,
+  business_name_kana text not null,
+  business_name text not null,
+  prefecture text not null,
+  city text not null,
+  town text not null,
+  block_address text not null,
+  postal_code varchar(7) not null,
+  old_postal_code varchar(5) not null,
+  handling_office text not null,
+  business_type smallint not null,
+  multi_number smallint not null,
+  update_code smallint not null,
+  updated_at timestamp with time zone not null
+);
+
+create index idx_jigyosyo_postal_code_jigyosyo_id on jigyosyo (postal_code, jigyosyo_id);
+
+comment on table jigyosyo is '郵便局が配布している事業所の個別郵便番号データ';
+comment on column jigyosyo.jigyosyo_id is 'レコードのID';
+comment on column jigyosyo.local_government_code is '大口事業所の所在地の全国地方公共団体コード（JIS X0401、X0402）';
+comment on column jigyosyo.business_name_kana is '大口事業所名（仮名）';
+comment on column jigyosyo.business_name is '大口事業所名';
+comment on column jigyosyo.prefecture is '都道府県名';
+comment on column jigyosyo.city is '市区町村名';
+comment on column jigyosyo.town is '町域名';
+comment on column jigyosyo.block_address is '小字名、丁目、番地等';
+comment on column jigyosyo.postal_code is '大口事業所個別番号（7桁）';
+comment on column jigyosyo.old_postal_code is '旧郵便番号（5桁）';
+comment on column jigyosyo.handling_office is '取扱局';
+comment on column jigyosyo.business_type is '個別番号の種別の表示（「0」大口事業所、「1」私書箱）';
+comment on column jigyosyo.multi_number is '複数番号の有無（「0」複数番号無し、「1」〜「3」複数番号を設定している場合の個別番号の番号）';
+comment on column jigyosyo.update_code is '修正コード（「0」修正なし、「1」新規追加、「5」廃止）';
+comment on column jigyosyo.updated_at is 'レコードの更新日時';
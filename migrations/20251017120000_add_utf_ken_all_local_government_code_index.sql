@@ -0,0 +1 @@
+create index idx_utf_ken_all_local_government_code_postal_code on utf_ken_all (local_government_code, postal_code);
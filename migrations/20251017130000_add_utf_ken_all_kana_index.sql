@@ -0,0 +1,2 @@
+create index idx_utf_ken_all_prefecture_kana_city_kana_town_kana on utf_ken_all ((prefecture_kana || city_kana || town_kana) text_pattern_ops);
+create index idx_utf_ken_all_city_kana_town_kana on utf_ken_all ((city_kana || town_kana) text_pattern_ops);
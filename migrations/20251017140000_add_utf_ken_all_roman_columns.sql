@@ -0,0 +1,7 @@
+alter table utf_ken_all add column prefecture_roman text not null default '';
+alter table utf_ken_all add column city_roman text not null default '';
+alter table utf_ken_all add column town_roman text not null default '';
+
+comment on column utf_ken_all.prefecture_roman is '都道府県名（ローマ字）';
+comment on column utf_ken_all.city_roman is '市区町村名（ローマ字）';
+comment on column utf_ken_all.town_roman is '町域名（ローマ字）';
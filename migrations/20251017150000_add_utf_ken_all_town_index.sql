@@ -0,0 +1,2 @@
+create index idx_utf_ken_all_town on utf_ken_all (town text_pattern_ops);
+create index idx_utf_ken_all_town_kana on utf_ken_all (town_kana text_pattern_ops);
@@ -0,0 +1,3 @@
+alter table utf_ken_all add column town_spec jsonb not null default '{}';
+
+comment on column utf_ken_all.town_spec is '町域の範囲（丁目・地割・番地）';
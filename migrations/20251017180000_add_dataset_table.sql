@@ -0,0 +1,48 @@
+create table dataset (
+  dataset_id bigint generated always as identity primary key,
+  utf_ken_all_count bigint not null,
+  jigyosyo_count bigint not null,
+  created_at timestamp with time zone not null
+);
+
+comment on table dataset is '郵便番号データベースのバージョン';
+comment on column dataset.dataset_id is 'バージョンのID（取り込むたびに増える）';
+comment on column dataset.utf_ken_all_count is 'utf_ken_all テーブルのレコード数';
+comment on column dataset.jigyosyo_count is 'jigyosyo テーブルのレコード数';
+comment on column dataset.created_at is 'バージョンを作成した日時';
+
+-- 既存のレコードは最初のバージョンにまとめる（まだ何も取り込んでいなければ作らない）
+insert into dataset (utf_ken_all_count, jigyosyo_count, created_at)
+select (select count(*) from utf_ken_all), (select count(*) from jigyosyo), now()
+where exists (select 1 from utf_ken_all) or exists (select 1 from jigyosyo);
+
+-- レコードはそれが含まれるバージョンの範囲 [valid_from, valid_to) を持ち、変わらないレコードは
+-- バージョン間で共有する（取り込み直しても ID が変わらない）
+alter table utf_ken_all add column valid_from bigint;
+alter table utf_ken_all add column valid_to bigint not null default 9223372036854775807;
+update utf_ken_all set valid_from = coalesce((select max(dataset_id) from dataset), 0);
+alter table utf_ken_all alter column valid_from set not null;
+create index idx_utf_ken_all_valid_from on utf_ken_all (valid_from);
+create index idx_utf_ken_all_valid_to on utf_ken_all (valid_to);
+comment on column utf_ken_all.valid_from is 'レコードが含まれる最初のバージョンのID';
+comment on column utf_ken_all.valid_to is 'レコードが含まれなくなったバージョンのID（最新のバージョンに含まれていれば bigint の最大値）';
+
+alter table jigyosyo add column valid_from bigint;
+alter table jigyosyo add column valid_to bigint not null default 9223372036854775807;
+update jigyosyo set valid_from = coalesce((select max(dataset_id) from dataset), 0);
+alter table jigyosyo alter column valid_from set not null;
+create index idx_jigyosyo_valid_from on jigyosyo (valid_from);
+create index idx_jigyosyo_valid_to on jigyosyo (valid_to);
+comment on column jigyosyo.valid_from is 'レコードが含まれる最初のバージョンのID';
+comment on column jigyosyo.valid_to is 'レコードが含まれなくなったバージョンのID（最新のバージョンに含まれていれば bigint の最大値）';
+
+create table active_dataset (
+  singleton boolean primary key default true check (singleton),
+  dataset_id bigint references dataset
+);
+
+insert into active_dataset (dataset_id) select max(dataset_id) from dataset;
+
+comment on table active_dataset is '使用中の郵便番号データベースのバージョン（1行だけ持つ）';
+comment on column active_dataset.singleton is '行を1つに制限するためのキー';
+comment on column active_dataset.dataset_id is '使用中のバージョンのID（まだ何も取り込んでいなければ NULL）';
@@ -0,0 +1,22 @@
+create table postal_code_history (
+  postal_code_history_id bigint generated always as identity primary key,
+  dataset_id bigint not null,
+  postal_code varchar(7) not null,
+  change text not null check (change in ('added', 'removed', 'changed')),
+  fields jsonb not null,
+  update_code smallint not null,
+  update_reason smallint not null,
+  imported_at timestamp with time zone not null
+);
+
+create index idx_postal_code_history_postal_code on postal_code_history (postal_code, dataset_id);
+
+comment on table postal_code_history is '取り込みで変わった住所の郵便番号の変更履歴';
+comment on column postal_code_history.postal_code_history_id is '変更履歴のID';
+comment on column postal_code_history.dataset_id is '変更を取り込んだバージョンのID（バージョンを削除しても変更履歴は残す）';
+comment on column postal_code_history.postal_code is '郵便番号（7桁）';
+comment on column postal_code_history.change is '変更の種類（added: 追加、removed: 削除、changed: 変更）';
+comment on column postal_code_history.fields is '変更された項目の変更前と変更後の値（{"field", "old", "new"} の配列）';
+comment on column postal_code_history.update_code is '更新の表示（削除された場合は削除前のレコードのもの）';
+comment on column postal_code_history.update_reason is '更新理由（削除された場合は削除前のレコードのもの）';
+comment on column postal_code_history.imported_at is '変更を取り込んだ日時';
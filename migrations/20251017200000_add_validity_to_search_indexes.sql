@@ -0,0 +1,16 @@
+-- 使用中でないバージョンのレコードも同じ表に残るので、住所・読み仮名・町域の前方一致検索用の索引に有効期間の列を加え、
+-- 表を読まずに使用中のバージョンのレコードに絞り込めるようにする
+drop index idx_utf_ken_all_prefecture_city_town;
+drop index idx_utf_ken_all_city_town;
+create index idx_utf_ken_all_prefecture_city_town on utf_ken_all ((prefecture || city || town) text_pattern_ops, valid_from, valid_to);
+create index idx_utf_ken_all_city_town on utf_ken_all ((city || town) text_pattern_ops, valid_from, valid_to);
+
+drop index idx_utf_ken_all_prefecture_kana_city_kana_town_kana;
+drop index idx_utf_ken_all_city_kana_town_kana;
+create index idx_utf_ken_all_prefecture_kana_city_kana_town_kana on utf_ken_all ((prefecture_kana || city_kana || town_kana) text_pattern_ops, valid_from, valid_to);
+create index idx_utf_ken_all_city_kana_town_kana on utf_ken_all ((city_kana || town_kana) text_pattern_ops, valid_from, valid_to);
+
+drop index idx_utf_ken_all_town;
+drop index idx_utf_ken_all_town_kana;
+create index idx_utf_ken_all_town on utf_ken_all (town text_pattern_ops, valid_from, valid_to);
+create index idx_utf_ken_all_town_kana on utf_ken_all (town_kana text_pattern_ops, valid_from, valid_to);
@@ -0,0 +1,22 @@
+-- 郵便局が配布している郵便番号データを正規化したデータ
+-- 各カラムの意味は PostgreSQL 版の utf_ken_all テーブルと同じ
+create table utf_ken_all (
+  utf_ken_all_id integer primary key autoincrement,
+  local_government_code text not null,
+  old_postal_code text not null,
+  postal_code text not null,
+  prefecture_kana text not null,
+  city_kana text not null,
+  town_kana text not null,
+  prefecture text not null,
+  city text not null,
+  town text not null,
+  has_multi_postal_code integer not null,
+  has_chome integer not null,
+  has_multi_town integer not null,
+  update_code integer not null,
+  update_reason integer not null,
+  updated_at text not null
+);
+
+create index idx_utf_ken_all_postal_code_town_town_kana_utf_ken_all_id on utf_ken_all (postal_code, town, town_kana, utf_ken_all_id);
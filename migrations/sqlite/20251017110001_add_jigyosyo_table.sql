@@ -0,0 +1,21 @@
+-- 郵便局が配布している事業所の個別郵便番号データ
+-- 各カラムの意味は PostgreSQL 版の jigyosyo テーブルと同じ
+create table jigyosyo (
+  jigyosyo_id integer primary key autoincrement,
+  local_government_code text not null,
+  business_name_kana text not null,
+  business_name text not null,
+  prefecture text not null,
+  city text not null,
+  town text not null,
+  block_address text not null,
+  postal_code text not null,
+  old_postal_code text not null,
+  handling_office text not null,
+  business_type integer not null,
+  multi_number integer not null,
+  update_code integer not null,
+  updated_at text not null
+);
+
+create index idx_jigyosyo_postal_code_jigyosyo_id on jigyosyo (postal_code, jigyosyo_id);
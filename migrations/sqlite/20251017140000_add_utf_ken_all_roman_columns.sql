@@ -0,0 +1,4 @@
+-- 住所のローマ字表記（KEN_ALL_ROME.CSV と突き合わせたもの）
+alter table utf_ken_all add column prefecture_roman text not null default '';
+alter table utf_ken_all add column city_roman text not null default '';
+alter table utf_ken_all add column town_roman text not null default '';
@@ -0,0 +1,2 @@
+-- 町域の範囲（丁目・地割・番地）を JSON で保持する
+alter table utf_ken_all add column town_spec text not null default '{}';
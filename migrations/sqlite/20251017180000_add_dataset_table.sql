@@ -0,0 +1,35 @@
+-- 郵便番号データベースのバージョン
+-- 各カラムの意味は PostgreSQL 版の dataset テーブルと同じ
+create table dataset (
+  dataset_id integer primary key autoincrement,
+  utf_ken_all_count integer not null,
+  jigyosyo_count integer not null,
+  created_at text not null
+);
+
+-- 既存のレコードは最初のバージョンにまとめる（まだ何も取り込んでいなければ作らない）
+insert into dataset (utf_ken_all_count, jigyosyo_count, created_at)
+select (select count(*) from utf_ken_all), (select count(*) from jigyosyo), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+where exists (select 1 from utf_ken_all) or exists (select 1 from jigyosyo);
+
+-- 各カラムの意味は PostgreSQL 版の utf_ken_all・jigyosyo テーブルの valid_from・valid_to と同じ
+alter table utf_ken_all add column valid_from integer not null default 0;
+alter table utf_ken_all add column valid_to integer not null default 9223372036854775807;
+update utf_ken_all set valid_from = coalesce((select max(dataset_id) from dataset), 0);
+create index idx_utf_ken_all_valid_from on utf_ken_all (valid_from);
+create index idx_utf_ken_all_valid_to on utf_ken_all (valid_to);
+
+alter table jigyosyo add column valid_from integer not null default 0;
+alter table jigyosyo add column valid_to integer not null default 9223372036854775807;
+update jigyosyo set valid_from = coalesce((select max(dataset_id) from dataset), 0);
+create index idx_jigyosyo_valid_from on jigyosyo (valid_from);
+create index idx_jigyosyo_valid_to on jigyosyo (valid_to);
+
+-- 使用中の郵便番号データベースのバージョン（1行だけ持つ）
+-- 各カラムの意味は PostgreSQL 版の active_dataset テーブルと同じ
+create table active_dataset (
+  singleton integer primary key default 1 check (singleton = 1),
+  dataset_id integer references dataset
+);
+
+insert into active_dataset (dataset_id) select max(dataset_id) from dataset;
@@ -0,0 +1,14 @@
+-- 取り込みで変わった住所の郵便番号の変更履歴
+-- 各カラムの意味は PostgreSQL 版の postal_code_history テーブルと同じ
+create table postal_code_history (
+  postal_code_history_id integer primary key autoincrement,
+  dataset_id integer not null,
+  postal_code text not null,
+  change text not null check (change in ('added', 'removed', 'changed')),
+  fields text not null,
+  update_code integer not null,
+  update_reason integer not null,
+  imported_at text not null
+);
+
+create index idx_postal_code_history_postal_code on postal_code_history (postal_code, dataset_id);
@@ -0,0 +1,11 @@
+-- 郵便番号・住所の前方一致検索用の索引
+-- 有効期間の列も含めて、表を読まずに使用中のバージョンのレコードに絞り込めるようにする
+drop index idx_utf_ken_all_postal_code_town_town_kana_utf_ken_all_id;
+create index idx_utf_ken_all_postal_code_town_town_kana_utf_ken_all_id on utf_ken_all (postal_code, town, town_kana, utf_ken_all_id, valid_from, valid_to);
+
+drop index idx_jigyosyo_postal_code_jigyosyo_id;
+create index idx_jigyosyo_postal_code_jigyosyo_id on jigyosyo (postal_code, jigyosyo_id, valid_from, valid_to);
+
+-- 問い合わせと同じ式で作らないと使われない
+create index idx_utf_ken_all_prefecture_city_town on utf_ken_all ((prefecture || city || town), valid_from, valid_to);
+create index idx_utf_ken_all_city_town on utf_ken_all ((city || town), valid_from, valid_to);
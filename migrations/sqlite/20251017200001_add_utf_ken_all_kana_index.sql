@@ -0,0 +1,3 @@
+-- 読み仮名の前方一致検索用の索引（問い合わせと同じ式で作らないと使われない）
+create index idx_utf_ken_all_prefecture_kana_city_kana_town_kana on utf_ken_all ((prefecture_kana || city_kana || town_kana), valid_from, valid_to);
+create index idx_utf_ken_all_city_kana_town_kana on utf_ken_all ((city_kana || town_kana), valid_from, valid_to);
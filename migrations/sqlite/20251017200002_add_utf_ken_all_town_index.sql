@@ -0,0 +1,3 @@
+-- 町域・町域の読み仮名の前方一致による入力候補の検索用の索引
+create index idx_utf_ken_all_town on utf_ken_all (town, valid_from, valid_to);
+create index idx_utf_ken_all_town_kana on utf_ken_all (town_kana, valid_from, valid_to);